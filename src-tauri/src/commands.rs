@@ -1,11 +1,18 @@
 use crate::obs::client::{OBSClient, OBSConnectionConfig, OBSConnectionStatus};
 use crate::obs::events::OBSEventHandler;
-use crate::sync::master::MasterSync;
-use crate::sync::slave::SlaveSync;
-use crate::sync::protocol::{SyncMessage, SyncTargetType};
+use crate::obs::commands::OBSCommands;
+use crate::obs::instances::{OBSInstanceStatus, OBSInstances, DEFAULT_INSTANCE};
+use crate::sync::master::{HotkeyForwarding, MasterSync, SlaveOutputReport};
+use crate::sync::slave::{SlaveSync, SlaveSyncSettings, TextLatencyStats};
+use crate::sync::protocol::{KeySequence, SyncMessage, SyncTargetType};
+use crate::network::{MasterTransport, SlaveTransport, TransportConfig};
+use crate::network::queue::{BackpressureConfig, ClientQueueMetrics};
 use crate::network::server::MasterServer;
 use crate::network::client::SlaveClient;
+use crate::network::relay::{RelayClient, RelayServer};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::IpAddr;
 use std::sync::Arc;
 use tauri::{Emitter, State};
 use tokio::sync::{mpsc, RwLock, Mutex};
@@ -22,20 +29,25 @@ pub enum AppMode {
 pub struct NetworkConfig {
     pub host: String,
     pub port: u16,
+    #[serde(default)]
+    pub transport: TransportConfig,
 }
 
 #[derive(Clone)]
 pub struct AppState {
-    pub obs_client: Arc<OBSClient>,
+    pub obs_instances: Arc<OBSInstances>,
     pub mode: Arc<RwLock<Option<AppMode>>>,
     pub network_port: Arc<RwLock<u16>>,
     // Master mode components
-    pub master_server: Arc<RwLock<Option<Arc<MasterServer>>>>,
+    pub master_server: Arc<RwLock<Option<Arc<MasterTransport>>>>,
     pub master_sync: Arc<RwLock<Option<Arc<MasterSync>>>>,
     pub obs_event_handler: Arc<RwLock<Option<Arc<OBSEventHandler>>>>,
+    pub hotkey_forwarding: Arc<RwLock<HotkeyForwarding>>,
     // Slave mode components
-    pub slave_client: Arc<RwLock<Option<Arc<SlaveClient>>>>,
-    pub slave_sync: Arc<RwLock<Option<Arc<SlaveSync>>>>,
+    pub slave_client: Arc<RwLock<Option<Arc<SlaveTransport>>>>,
+    /// One SlaveSync per local OBS instance receiving the master's messages
+    pub slave_syncs: Arc<RwLock<HashMap<String, Arc<SlaveSync>>>>,
+    pub slave_settings: Arc<RwLock<SlaveSyncSettings>>,
     // Message channels
     pub sync_message_tx: Arc<Mutex<Option<mpsc::UnboundedSender<SyncMessage>>>>,
     // Tauri app handle
@@ -45,14 +57,16 @@ pub struct AppState {
 impl AppState {
     pub fn new() -> Self {
         Self {
-            obs_client: Arc::new(OBSClient::new()),
+            obs_instances: Arc::new(OBSInstances::new()),
             mode: Arc::new(RwLock::new(None)),
             network_port: Arc::new(RwLock::new(8080)),
             master_server: Arc::new(RwLock::new(None)),
             master_sync: Arc::new(RwLock::new(None)),
             obs_event_handler: Arc::new(RwLock::new(None)),
+            hotkey_forwarding: Arc::new(RwLock::new(HotkeyForwarding::default())),
             slave_client: Arc::new(RwLock::new(None)),
-            slave_sync: Arc::new(RwLock::new(None)),
+            slave_syncs: Arc::new(RwLock::new(HashMap::new())),
+            slave_settings: Arc::new(RwLock::new(SlaveSyncSettings::default())),
             sync_message_tx: Arc::new(Mutex::new(None)),
             app_handle: Arc::new(RwLock::new(None)),
         }
@@ -63,30 +77,68 @@ impl AppState {
     }
 }
 
+/// Resolve an optional instance name (default instance when omitted)
+async fn obs_instance(state: &AppState, instance: Option<String>) -> Result<Arc<OBSClient>, String> {
+    let name = instance.unwrap_or_else(|| DEFAULT_INSTANCE.to_string());
+    state
+        .obs_instances
+        .get(&name)
+        .await
+        .ok_or_else(|| format!("Unknown OBS instance: {}", name))
+}
+
 #[tauri::command]
 pub async fn connect_obs(
     state: State<'_, AppState>,
     config: OBSConnectionConfig,
+    instance: Option<String>,
 ) -> Result<(), String> {
+    let name = instance.unwrap_or_else(|| DEFAULT_INSTANCE.to_string());
     state
-        .obs_client
+        .obs_instances
+        .get_or_create(&name)
+        .await
         .connect(config)
         .await
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub async fn disconnect_obs(state: State<'_, AppState>) -> Result<(), String> {
-    state
-        .obs_client
+pub async fn disconnect_obs(
+    state: State<'_, AppState>,
+    instance: Option<String>,
+) -> Result<(), String> {
+    obs_instance(&state, instance)
+        .await?
         .disconnect()
         .await
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub async fn get_obs_status(state: State<'_, AppState>) -> Result<OBSConnectionStatus, String> {
-    Ok(state.obs_client.get_status().await)
+pub async fn get_obs_status(
+    state: State<'_, AppState>,
+    instance: Option<String>,
+) -> Result<OBSConnectionStatus, String> {
+    Ok(obs_instance(&state, instance).await?.get_status().await)
+}
+
+#[tauri::command]
+pub async fn get_obs_instances(state: State<'_, AppState>) -> Result<Vec<OBSInstanceStatus>, String> {
+    Ok(state.obs_instances.statuses().await)
+}
+
+#[tauri::command]
+pub async fn remove_obs_instance(state: State<'_, AppState>, instance: String) -> Result<(), String> {
+    if state.slave_syncs.read().await.contains_key(&instance) {
+        return Err(format!("OBS instance {} is receiving sync messages", instance));
+    }
+    let client = state
+        .obs_instances
+        .remove(&instance)
+        .await
+        .ok_or_else(|| format!("Cannot remove OBS instance: {}", instance))?;
+    client.disconnect().await.map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -104,41 +156,85 @@ pub async fn get_app_mode(state: State<'_, AppState>) -> Result<Option<AppMode>,
 pub async fn start_master_server(
     state: State<'_, AppState>,
     port: u16,
-) -> Result<(), String> {
-    // Check if OBS is connected
-    if !state.obs_client.is_connected().await {
+    source_instance: Option<String>,
+    transport: Option<TransportConfig>,
+    bind_address: Option<String>,
+    backpressure: Option<BackpressureConfig>,
+) -> Result<Option<String>, String> {
+    // The master sources its state and events from a single instance
+    let obs_client = obs_instance(&state, source_instance).await?;
+    if !obs_client.is_connected().await {
         return Err("OBS is not connected".to_string());
     }
 
-    // Update port
-    *state.network_port.write().await = port;
+    // Restarting: release the previous server's port first
+    if let Some(previous) = state.master_server.write().await.take() {
+        previous.stop().await;
+    }
 
     // Create MasterSync
-    let (master_sync, sync_rx) = MasterSync::new(state.obs_client.clone());
+    let (master_sync, sync_rx) = MasterSync::new(
+        obs_client.clone(),
+        state.hotkey_forwarding.clone(),
+    );
     let master_sync = Arc::new(master_sync);
     *state.master_sync.write().await = Some(master_sync.clone());
 
-    // Create and start MasterServer
-    let master_server = Arc::new(MasterServer::new(port));
+    // Create and start the selected transport
+    let transport = transport.unwrap_or_default();
+    let master_server = Arc::new(match &transport {
+        TransportConfig::WebSocket => {
+            MasterTransport::WebSocket(MasterServer::new(
+                parse_bind_address(bind_address)?,
+                port,
+                backpressure.unwrap_or_default(),
+            ))
+        }
+        TransportConfig::ObsRelay { instance } => {
+            let relay_client = obs_instance(&state, Some(instance.clone())).await?;
+            if !relay_client.is_connected().await {
+                return Err(format!("Relay OBS instance {} is not connected", instance));
+            }
+            MasterTransport::ObsRelay(RelayServer::new(relay_client))
+        }
+    });
     
-    // Set up callback to send initial state when new slave connects
+    // Set up callback to collect the state for a new or resyncing slave
     let master_sync_for_callback = master_sync.clone();
     master_server.set_initial_state_callback(move |client_id: String| {
         let master_sync_clone = master_sync_for_callback.clone();
         async move {
-            println!("Sending initial state to new slave: {}", client_id);
+            println!("Sending initial state to slave: {}", client_id);
             // Small delay to ensure connection is fully established
             tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-            if let Err(e) = master_sync_clone.send_initial_state().await {
-                eprintln!("Failed to send initial state to {}: {}", client_id, e);
+            match master_sync_clone.collect_initial_state().await {
+                Ok(state) => state,
+                Err(e) => {
+                    eprintln!("Failed to collect initial state for {}: {}", client_id, e);
+                    None
+                }
             }
         }
     }).await;
     
+    // Messages coming back from slaves (output status reports)
+    let master_sync_for_reports = master_sync.clone();
+    master_server.set_client_message_callback(move |client_id: String, message: SyncMessage| {
+        let master_sync_clone = master_sync_for_reports.clone();
+        async move {
+            master_sync_clone.handle_client_message(client_id, message).await;
+        }
+    }).await;
+    
     master_server
         .start(sync_rx)
         .await
         .map_err(|e| format!("Failed to start master server: {}", e))?;
+    // Port 0 is resolved to the port the OS picked
+    let local_addr = master_server.local_addr().await;
+    if let Some(addr) = local_addr {
+        *state.network_port.write().await = addr.port();
+    }
     *state.master_server.write().await = Some(master_server);
 
     // Create OBS event handler
@@ -146,15 +242,10 @@ pub async fn start_master_server(
     let event_handler = Arc::new(event_handler);
     
     // Start listening to OBS events
-    let client_arc = state.obs_client.get_client_arc();
-    let client_lock = client_arc.read().await;
-    if let Some(obs_client) = client_lock.as_ref() {
-        event_handler
-            .start_listening(obs_client)
-            .await
-            .map_err(|e| format!("Failed to start OBS event listener: {}", e))?;
-    }
-    drop(client_lock);
+    event_handler
+        .start_listening(&obs_client)
+        .await
+        .map_err(|e| format!("Failed to start OBS event listener: {}", e))?;
     
     // Start monitoring OBS events
     master_sync.start_monitoring(event_rx).await;
@@ -162,8 +253,26 @@ pub async fn start_master_server(
     // Store event handler
     *state.obs_event_handler.write().await = Some(event_handler);
 
-    println!("Master server started on port {}", port);
-    Ok(())
+    match transport {
+        TransportConfig::WebSocket => println!("Master server started on {:?}", local_addr),
+        TransportConfig::ObsRelay { instance } => println!("Master server relaying through OBS instance {}", instance),
+    }
+    Ok(local_addr.map(|addr| addr.to_string()))
+}
+
+/// Interface to listen on; all IPv4 interfaces when omitted
+fn parse_bind_address(bind_address: Option<String>) -> Result<IpAddr, String> {
+    let Some(address) = bind_address.filter(|a| !a.trim().is_empty()) else {
+        return Ok(IpAddr::from([0, 0, 0, 0]));
+    };
+    let trimmed = address.trim();
+    let trimmed = trimmed
+        .strip_prefix('[')
+        .and_then(|a| a.strip_suffix(']'))
+        .unwrap_or(trimmed);
+    trimmed
+        .parse()
+        .map_err(|_| format!("Invalid bind address: {}", address))
 }
 
 #[tauri::command]
@@ -186,36 +295,96 @@ pub async fn stop_master_server(state: State<'_, AppState>) -> Result<(), String
 pub async fn connect_to_master(
     state: State<'_, AppState>,
     config: NetworkConfig,
+    instances: Option<Vec<String>>,
 ) -> Result<(), String> {
-    // Check if OBS is connected
-    if !state.obs_client.is_connected().await {
-        return Err("OBS is not connected".to_string());
+    // Received messages fan out to every selected local instance
+    let instances = instances.unwrap_or_else(|| vec![DEFAULT_INSTANCE.to_string()]);
+    if instances.is_empty() {
+        return Err("No OBS instance selected".to_string());
+    }
+    let mut obs_clients = Vec::with_capacity(instances.len());
+    for name in &instances {
+        let obs_client = obs_instance(&state, Some(name.clone())).await?;
+        if !obs_client.is_connected().await {
+            return Err(format!("OBS instance {} is not connected", name));
+        }
+        obs_clients.push((name.clone(), obs_client));
     }
 
-    println!("Connecting to master at {}:{}", config.host, config.port);
-
-    // Create SlaveClient
-    let slave_client = Arc::new(SlaveClient::new(config.host.clone(), config.port));
+    // Create the selected transport
+    let slave_client = Arc::new(match &config.transport {
+        TransportConfig::WebSocket => {
+            println!("Connecting to master at {}:{}", config.host, config.port);
+            SlaveTransport::WebSocket(SlaveClient::new(config.host.clone(), config.port))
+        }
+        TransportConfig::ObsRelay { instance } => {
+            println!("Connecting to master through OBS instance {}", instance);
+            let relay_client = obs_instance(&state, Some(instance.clone())).await?;
+            if !relay_client.is_connected().await {
+                return Err(format!("Relay OBS instance {} is not connected", instance));
+            }
+            SlaveTransport::ObsRelay(RelayClient::new(relay_client))
+        }
+    });
     
     // Connect to master and get sync message receiver
     let sync_rx = slave_client
         .connect()
         .await
         .map_err(|e| format!("Failed to connect to master: {}", e))?;
+    let report_tx = slave_client.sender();
     
     *state.slave_client.write().await = Some(slave_client);
 
-    // Create SlaveSync
-    let (slave_sync, alert_rx) = SlaveSync::new(state.obs_client.clone());
-    let slave_sync = Arc::new(slave_sync);
-    *state.slave_sync.write().await = Some(slave_sync.clone());
+    // Create one SlaveSync per instance, each with its own queue so a slow
+    // instance does not hold back the others
+    let mut instance_txs = Vec::with_capacity(obs_clients.len());
+    let mut slave_syncs = HashMap::new();
+    for (name, obs_client) in obs_clients {
+        let (slave_sync, alert_rx) = SlaveSync::new(
+            name.clone(),
+            obs_client,
+            state.slave_settings.clone(),
+            report_tx.clone(),
+        );
+        let slave_sync = Arc::new(slave_sync);
+        slave_syncs.insert(name.clone(), slave_sync.clone());
+
+        // Start periodic state checking (every 5 seconds)
+        slave_sync.start_periodic_check(5);
+        println!("Started periodic desync detection for {} (interval: 5s)", name);
 
-    // Start periodic state checking (every 5 seconds)
-    slave_sync.start_periodic_check(5);
-    println!("Started periodic desync detection (interval: 5s)");
+        // Start processing sync messages
+        let (instance_tx, mut instance_rx) = mpsc::unbounded_channel::<SyncMessage>();
+        instance_txs.push(instance_tx);
+        let instance_name = name.clone();
+        tokio::spawn(async move {
+            while let Some(message) = instance_rx.recv().await {
+                if let Err(e) = slave_sync.apply_sync_message(message).await {
+                    eprintln!("[{}] Failed to apply sync message: {}", instance_name, e);
+                }
+            }
+        });
+
+        // Start processing alerts (forward to frontend via Tauri events)
+        let app_handle_lock = state.app_handle.clone();
+        tokio::spawn(async move {
+            let mut rx = alert_rx;
+            while let Some(alert) = rx.recv().await {
+                println!("🚨 Desync Alert [{}]: {} - {}", alert.instance, alert.scene_name, alert.message);
+                
+                // Emit Tauri event to frontend
+                if let Some(handle) = app_handle_lock.read().await.as_ref() {
+                    if let Err(e) = handle.emit("desync-alert", alert.clone()) {
+                        eprintln!("Failed to emit desync alert event: {}", e);
+                    }
+                }
+            }
+        });
+    }
+    *state.slave_syncs.write().await = slave_syncs;
 
-    // Start processing sync messages
-    let slave_sync_for_processing = slave_sync.clone();
+    // Fan received messages out to every instance
     tokio::spawn(async move {
         let mut rx = sync_rx;
         let mut first_message = true;
@@ -226,29 +395,13 @@ pub async fn connect_to_master(
                 first_message = false;
             }
             
-            if let Err(e) = slave_sync_for_processing.apply_sync_message(message).await {
-                eprintln!("Failed to apply sync message: {}", e);
+            for instance_tx in &instance_txs {
+                let _ = instance_tx.send(message.clone());
             }
         }
     });
 
-    // Start processing alerts (forward to frontend via Tauri events)
-    let app_handle_lock = state.app_handle.clone();
-    tokio::spawn(async move {
-        let mut rx = alert_rx;
-        while let Some(alert) = rx.recv().await {
-            println!("🚨 Desync Alert: {} - {}", alert.scene_name, alert.message);
-            
-            // Emit Tauri event to frontend
-            if let Some(handle) = app_handle_lock.read().await.as_ref() {
-                if let Err(e) = handle.emit("desync-alert", alert.clone()) {
-                    eprintln!("Failed to emit desync alert event: {}", e);
-                }
-            }
-        }
-    });
-
-    println!("Connected to master at {}:{}", config.host, config.port);
+    println!("Connected to master");
     println!("Note: Initial state will be synchronized from master...");
     Ok(())
 }
@@ -261,7 +414,7 @@ pub async fn disconnect_from_master(state: State<'_, AppState>) -> Result<(), St
     }
 
     // Clear slave components
-    *state.slave_sync.write().await = None;
+    state.slave_syncs.write().await.clear();
 
     println!("Disconnected from master");
     Ok(())
@@ -276,7 +429,10 @@ pub async fn set_sync_targets(
     
     // Update targets for master mode
     if let Some(master_sync) = state.master_sync.read().await.as_ref() {
-        master_sync.set_active_targets(targets).await;
+        master_sync
+            .set_active_targets(targets)
+            .await
+            .map_err(|e| e.to_string())?;
     } else {
         // Just log the targets if not in master mode (slave mode doesn't need to set targets)
         println!("Sync targets set (not in master mode)");
@@ -285,6 +441,22 @@ pub async fn set_sync_targets(
     Ok(())
 }
 
+#[tauri::command]
+pub async fn set_slave_sync_settings(
+    state: State<'_, AppState>,
+    settings: SlaveSyncSettings,
+) -> Result<(), String> {
+    println!("Setting slave sync settings: {:?}", settings);
+    // Shared with SlaveSync, so this applies immediately while connected
+    *state.slave_settings.write().await = settings;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_slave_sync_settings(state: State<'_, AppState>) -> Result<SlaveSyncSettings, String> {
+    Ok(state.slave_settings.read().await.clone())
+}
+
 #[tauri::command]
 pub async fn get_connected_clients_count(state: State<'_, AppState>) -> Result<usize, String> {
     if let Some(server) = state.master_server.read().await.as_ref() {
@@ -294,6 +466,106 @@ pub async fn get_connected_clients_count(state: State<'_, AppState>) -> Result<u
     }
 }
 
+#[tauri::command]
+pub async fn get_client_queue_metrics(
+    state: State<'_, AppState>,
+) -> Result<Vec<ClientQueueMetrics>, String> {
+    if let Some(server) = state.master_server.read().await.as_ref() {
+        Ok(server.get_queue_metrics().await)
+    } else {
+        Ok(Vec::new())
+    }
+}
+
+#[tauri::command]
+pub async fn get_text_sync_latency(
+    state: State<'_, AppState>,
+    instance: Option<String>,
+) -> Result<Option<TextLatencyStats>, String> {
+    let name = instance.unwrap_or_else(|| DEFAULT_INSTANCE.to_string());
+    let slave_syncs = state.slave_syncs.read().await;
+    // Fall back to any instance when the default one is not receiving
+    if let Some(slave_sync) = slave_syncs.get(&name).or_else(|| slave_syncs.values().next()) {
+        Ok(Some(slave_sync.get_text_latency().await))
+    } else {
+        Ok(None)
+    }
+}
+
+#[tauri::command]
+pub async fn get_slave_output_status(
+    state: State<'_, AppState>,
+) -> Result<Vec<SlaveOutputReport>, String> {
+    let Some(server) = state.master_server.read().await.clone() else {
+        return Ok(Vec::new());
+    };
+    let client_ids = server.get_client_ids().await;
+    if let Some(master_sync) = state.master_sync.read().await.as_ref() {
+        Ok(master_sync.get_slave_outputs(&client_ids).await)
+    } else {
+        Ok(Vec::new())
+    }
+}
+
+#[tauri::command]
+pub async fn save_replay_buffer(state: State<'_, AppState>) -> Result<(), String> {
+    if let Some(master_sync) = state.master_sync.read().await.as_ref() {
+        master_sync
+            .save_replay_buffer()
+            .await
+            .map_err(|e| e.to_string())
+    } else {
+        Err("Master server is not running".to_string())
+    }
+}
+
+#[tauri::command]
+pub async fn get_obs_hotkeys(
+    state: State<'_, AppState>,
+    instance: Option<String>,
+) -> Result<Vec<String>, String> {
+    let client_arc = obs_instance(&state, instance).await?.get_client_arc();
+    let client_lock = client_arc.read().await;
+    let client = client_lock
+        .as_ref()
+        .ok_or_else(|| "OBS is not connected".to_string())?;
+    OBSCommands::get_hotkey_names(client)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn set_hotkey_forwarding(
+    state: State<'_, AppState>,
+    forwarding: HotkeyForwarding,
+) -> Result<(), String> {
+    println!("Setting forwarded hotkeys: {:?}", forwarding);
+    // Shared with MasterSync, so this applies immediately while the server runs
+    *state.hotkey_forwarding.write().await = forwarding;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_hotkey_forwarding(state: State<'_, AppState>) -> Result<HotkeyForwarding, String> {
+    Ok(state.hotkey_forwarding.read().await.clone())
+}
+
+#[tauri::command]
+pub async fn trigger_hotkey(
+    state: State<'_, AppState>,
+    hotkey_name: Option<String>,
+    key_sequence: Option<KeySequence>,
+) -> Result<(), String> {
+    if let Some(master_sync) = state.master_sync.read().await.as_ref() {
+        master_sync
+            .trigger_hotkey(hotkey_name, key_sequence)
+            .await
+            .map_err(|e| e.to_string())
+    } else {
+        Err("Master server is not running".to_string())
+    }
+}
+
 #[tauri::command]
 pub fn greet(name: &str) -> String {
     format!("Hello, {}! You've been greeted from Rust!", name)
@@ -27,6 +27,8 @@ pub fn run() {
             commands::connect_obs,
             commands::disconnect_obs,
             commands::get_obs_status,
+            commands::get_obs_instances,
+            commands::remove_obs_instance,
             commands::set_app_mode,
             commands::get_app_mode,
             commands::start_master_server,
@@ -34,7 +36,17 @@ pub fn run() {
             commands::connect_to_master,
             commands::disconnect_from_master,
             commands::set_sync_targets,
+            commands::set_slave_sync_settings,
+            commands::get_slave_sync_settings,
             commands::get_connected_clients_count,
+            commands::get_client_queue_metrics,
+            commands::get_text_sync_latency,
+            commands::get_slave_output_status,
+            commands::save_replay_buffer,
+            commands::get_obs_hotkeys,
+            commands::set_hotkey_forwarding,
+            commands::get_hotkey_forwarding,
+            commands::trigger_hotkey,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
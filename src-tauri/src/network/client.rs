@@ -1,30 +1,48 @@
-use crate::sync::protocol::SyncMessage;
+use crate::sync::protocol::{BulkChunkPayload, SyncMessage, SyncMessageType};
 use anyhow::{Context, Result};
 use futures::{SinkExt, StreamExt};
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::net::TcpStream;
-use tokio::sync::{mpsc, RwLock};
+use tokio::sync::{mpsc, Mutex, RwLock};
 use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
 
 type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
 
+/// A transfer still incomplete after this long is abandoned
+const TRANSFER_TIMEOUT: Duration = Duration::from_secs(60);
+/// Transfers growing beyond this are dropped
+const MAX_TRANSFER_BYTES: usize = 256 * 1024 * 1024;
+
 pub struct SlaveClient {
     host: String,
     port: u16,
     ws_stream: Arc<RwLock<Option<WsStream>>>,
+    /// Messages to the master (status reports), written by the connection task
+    outgoing_tx: mpsc::UnboundedSender<SyncMessage>,
+    outgoing_rx: Mutex<Option<mpsc::UnboundedReceiver<SyncMessage>>>,
 }
 
 impl SlaveClient {
     pub fn new(host: String, port: u16) -> Self {
+        let (outgoing_tx, outgoing_rx) = mpsc::unbounded_channel();
         Self {
             host,
             port,
             ws_stream: Arc::new(RwLock::new(None)),
+            outgoing_tx,
+            outgoing_rx: Mutex::new(Some(outgoing_rx)),
         }
     }
 
+    /// Sender for messages to the master; they are queued until connected
+    pub fn sender(&self) -> mpsc::UnboundedSender<SyncMessage> {
+        self.outgoing_tx.clone()
+    }
+
     pub async fn connect(&self) -> Result<mpsc::UnboundedReceiver<SyncMessage>> {
-        let url = format!("ws://{}:{}", self.host, self.port);
+        let url = master_url(&self.host, self.port);
         let (ws_stream, _) = connect_async(&url)
             .await
             .context(format!("Failed to connect to {}", url))?;
@@ -34,13 +52,51 @@ impl SlaveClient {
         let (mut ws_sender, mut ws_receiver) = ws_stream.split();
         let (tx, rx) = mpsc::unbounded_channel();
 
+        // Single writer for pongs and outgoing messages
+        let (frame_tx, mut frame_rx) = mpsc::unbounded_channel::<Message>();
+        tokio::spawn(async move {
+            while let Some(frame) = frame_rx.recv().await {
+                if ws_sender.send(frame).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        if let Some(mut outgoing_rx) = self.outgoing_rx.lock().await.take() {
+            let frame_tx = frame_tx.clone();
+            tokio::spawn(async move {
+                while let Some(message) = outgoing_rx.recv().await {
+                    match serde_json::to_string(&message) {
+                        Ok(json) => {
+                            if frame_tx.send(Message::Text(json.into())).is_err() {
+                                break;
+                            }
+                        }
+                        Err(e) => {
+                            eprintln!("Failed to serialize message to master: {}", e);
+                        }
+                    }
+                }
+            });
+        }
+
         // Handle incoming messages
         tokio::spawn(async move {
+            // Partial transfers belong to this connection and end with it
+            let mut transfers = Transfers::default();
             while let Some(msg) = ws_receiver.next().await {
                 match msg {
                     Ok(Message::Text(text)) => {
                         match serde_json::from_str::<SyncMessage>(&text) {
                             Ok(sync_msg) => {
+                                let sync_msg = if sync_msg.message_type == SyncMessageType::BulkChunk {
+                                    match transfers.reassemble(sync_msg, Instant::now()) {
+                                        Some(complete) => complete,
+                                        None => continue,
+                                    }
+                                } else {
+                                    sync_msg
+                                };
                                 if tx.send(sync_msg).is_err() {
                                     break;
                                 }
@@ -52,11 +108,15 @@ impl SlaveClient {
                     }
                     Ok(Message::Ping(data)) => {
                         // Send pong
-                        let _ = ws_sender.send(Message::Pong(data)).await;
+                        let _ = frame_tx.send(Message::Pong(data));
                     }
-                    Ok(Message::Close(_)) => {
-                        println!("Connection closed by master");
-                        break;
+                    Ok(Message::Close(frame)) => {
+                        match frame {
+                            Some(frame) => println!("Connection closed by master: {}", frame.reason),
+                            None => println!("Connection closed by master"),
+                        }
+                        // Keep reading so the close reply is flushed; the
+                        // stream ends once the master drops the socket
                     }
                     Err(e) => {
                         eprintln!("WebSocket error: {}", e);
@@ -81,3 +141,199 @@ impl SlaveClient {
         }
     }
 }
+
+struct PartialTransfer {
+    text: String,
+    next_index: u32,
+    started_at: Instant,
+}
+
+/// `BulkChunk`s of the transfers in progress on one connection
+#[derive(Default)]
+struct Transfers {
+    partial: HashMap<u64, PartialTransfer>,
+}
+
+impl Transfers {
+    /// Collect a `BulkChunk`; returns the original message once its last
+    /// chunk has arrived. A transfer with a missing chunk, one that takes too
+    /// long or grows too large is dropped.
+    fn reassemble(&mut self, chunk: SyncMessage, now: Instant) -> Option<SyncMessage> {
+        self.partial.retain(|transfer_id, transfer| {
+            let expired = now.duration_since(transfer.started_at) > TRANSFER_TIMEOUT;
+            if expired {
+                eprintln!("Dropping transfer {}: not completed in {:?}", transfer_id, TRANSFER_TIMEOUT);
+            }
+            !expired
+        });
+
+        let chunk: BulkChunkPayload = match serde_json::from_value(chunk.payload) {
+            Ok(chunk) => chunk,
+            Err(e) => {
+                eprintln!("Failed to parse bulk chunk: {}", e);
+                return None;
+            }
+        };
+
+        if chunk.index == 0 {
+            self.partial.insert(
+                chunk.transfer_id,
+                PartialTransfer {
+                    text: String::new(),
+                    next_index: 0,
+                    started_at: now,
+                },
+            );
+        }
+        let Some(transfer) = self.partial.get_mut(&chunk.transfer_id) else {
+            eprintln!("Dropping chunk {} of unknown transfer {}", chunk.index, chunk.transfer_id);
+            return None;
+        };
+        if chunk.index != transfer.next_index {
+            eprintln!(
+                "Dropping transfer {}: expected chunk {}, got {}",
+                chunk.transfer_id, transfer.next_index, chunk.index
+            );
+            self.partial.remove(&chunk.transfer_id);
+            return None;
+        }
+        if transfer.text.len() + chunk.data.len() > MAX_TRANSFER_BYTES {
+            eprintln!("Dropping transfer {}: larger than {} bytes", chunk.transfer_id, MAX_TRANSFER_BYTES);
+            self.partial.remove(&chunk.transfer_id);
+            return None;
+        }
+        transfer.text.push_str(&chunk.data);
+        transfer.next_index += 1;
+        if !chunk.last {
+            return None;
+        }
+
+        let text = self.partial.remove(&chunk.transfer_id).map(|transfer| transfer.text).unwrap_or_default();
+        match serde_json::from_str::<SyncMessage>(&text) {
+            Ok(message) => Some(message),
+            Err(e) => {
+                eprintln!("Failed to parse reassembled message: {}", e);
+                None
+            }
+        }
+    }
+}
+
+/// WebSocket URL of the master. IPv6 literals (with or without brackets,
+/// optionally with a zone such as `fe80::1%eth0`) are bracketed; IPv4
+/// addresses and hostnames are used as they are.
+fn master_url(host: &str, port: u16) -> String {
+    let host = host.trim();
+    let host = host
+        .strip_prefix('[')
+        .and_then(|h| h.strip_suffix(']'))
+        .unwrap_or(host);
+    if host.contains(':') {
+        // The zone separator must be percent-encoded inside a URL
+        let host = if host.contains("%25") {
+            host.to_string()
+        } else {
+            host.replacen('%', "%25", 1)
+        };
+        format!("ws://[{}]:{}", host, port)
+    } else {
+        format!("ws://{}:{}", host, port)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::protocol::SyncTargetType;
+    use serde_json::json;
+
+    /// The chunks of `message`, cut into three
+    fn chunks(transfer_id: u64, message: &SyncMessage) -> Vec<SyncMessage> {
+        let text = serde_json::to_string(message).unwrap();
+        let size = text.len().div_ceil(3);
+        let parts = [&text[..size], &text[size..2 * size], &text[2 * size..]];
+        parts
+            .iter()
+            .enumerate()
+            .map(|(index, data)| {
+                SyncMessage::new(
+                    SyncMessageType::BulkChunk,
+                    SyncTargetType::Program,
+                    json!({ "transfer_id": transfer_id, "index": index, "last": index == 2, "data": data }),
+                )
+            })
+            .collect()
+    }
+
+    fn state(scene_name: &str) -> SyncMessage {
+        SyncMessage::new(
+            SyncMessageType::StateSync,
+            SyncTargetType::Program,
+            json!({ "current_program_scene": scene_name }),
+        )
+    }
+
+    #[test]
+    fn reassembles_interleaved_transfers() {
+        let mut transfers = Transfers::default();
+        let now = Instant::now();
+        let first = chunks(1, &state("Live"));
+        let second = chunks(2, &state("Break"));
+
+        let mut completed = Vec::new();
+        for chunk in [&first[0], &second[0], &first[1], &second[1], &second[2], &first[2]] {
+            completed.extend(transfers.reassemble(chunk.clone(), now));
+        }
+        let scenes: Vec<_> = completed.iter().map(|m| m.payload["current_program_scene"].clone()).collect();
+        assert_eq!(scenes, vec![json!("Break"), json!("Live")]);
+        assert!(transfers.partial.is_empty());
+    }
+
+    #[test]
+    fn drops_transfer_with_chunks_out_of_order() {
+        let mut transfers = Transfers::default();
+        let now = Instant::now();
+        let chunks = chunks(1, &state("Live"));
+
+        assert!(transfers.reassemble(chunks[0].clone(), now).is_none());
+        assert!(transfers.reassemble(chunks[2].clone(), now).is_none());
+        assert!(transfers.partial.is_empty());
+        assert!(transfers.reassemble(chunks[1].clone(), now).is_none());
+        assert!(transfers.partial.is_empty());
+    }
+
+    #[test]
+    fn drops_abandoned_transfer() {
+        let mut transfers = Transfers::default();
+        let now = Instant::now();
+        let abandoned = chunks(1, &state("Live"));
+        let next = chunks(2, &state("Break"));
+
+        assert!(transfers.reassemble(abandoned[0].clone(), now).is_none());
+        let later = now + TRANSFER_TIMEOUT + Duration::from_secs(1);
+        assert!(transfers.reassemble(next[0].clone(), later).is_none());
+        assert_eq!(transfers.partial.keys().collect::<Vec<_>>(), vec![&2]);
+
+        // The rest of the abandoned transfer is ignored
+        assert!(transfers.reassemble(abandoned[1].clone(), later).is_none());
+        assert!(transfers.reassemble(abandoned[2].clone(), later).is_none());
+        assert!(transfers.reassemble(next[1].clone(), later).is_none());
+        assert!(transfers.reassemble(next[2].clone(), later).is_some());
+    }
+
+    #[test]
+    fn master_url_formats_hosts() {
+        assert_eq!(master_url("192.168.1.20", 8080), "ws://192.168.1.20:8080");
+        assert_eq!(master_url(" obs-master.local ", 8080), "ws://obs-master.local:8080");
+        assert_eq!(master_url("::1", 8080), "ws://[::1]:8080");
+        assert_eq!(master_url("[2001:db8::5]", 8080), "ws://[2001:db8::5]:8080");
+    }
+
+    #[test]
+    fn master_url_encodes_ipv6_zone() {
+        assert_eq!(master_url("fe80::1%eth0", 8080), "ws://[fe80::1%25eth0]:8080");
+        assert_eq!(master_url("[fe80::1%eth0]", 8080), "ws://[fe80::1%25eth0]:8080");
+        // Already encoded zones are kept
+        assert_eq!(master_url("fe80::1%25eth0", 8080), "ws://[fe80::1%25eth0]:8080");
+    }
+}
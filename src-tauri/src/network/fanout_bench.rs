@@ -0,0 +1,119 @@
+//! Fan-out cost with 100 local slaves. Not part of the regular test run:
+//!
+//! ```text
+//! cargo test --release fanout -- --ignored --nocapture --test-threads=1
+//! ```
+
+use super::*;
+use crate::network::client::SlaveClient;
+use crate::network::queue::Frames;
+use crate::sync::protocol::{SyncMessageType, SyncTargetType};
+use std::time::{Duration, Instant};
+
+const CLIENTS: usize = 100;
+const PAYLOAD_SIZES: [usize; 3] = [1024, 256 * 1024, 4 * 1024 * 1024];
+
+fn message(size: usize) -> SyncMessage {
+    // SourceUpdate has no coalescing key, so every message is delivered
+    SyncMessage::new(
+        SyncMessageType::SourceUpdate,
+        SyncTargetType::Source,
+        serde_json::json!({ "data": "x".repeat(size) }),
+    )
+}
+
+fn unbounded() -> BackpressureConfig {
+    BackpressureConfig {
+        max_queue_messages: usize::MAX,
+        max_queue_bytes: usize::MAX,
+        slow_client_policy: SlowClientPolicy::Resync,
+    }
+}
+
+/// Serialize, enqueue for every client and pop each client's frame, i.e.
+/// everything up to the socket write
+#[tokio::test]
+#[ignore]
+async fn fanout_queueing() {
+    let clients: ClientMap = (0..CLIENTS)
+        .map(|i| (format!("client-{}", i), Arc::new(ClientQueue::new(unbounded()))))
+        .collect();
+
+    for size in PAYLOAD_SIZES {
+        let message = message(size);
+        let routing = Routing::of(&message);
+        let iterations = (64 * 1024 * 1024 / size).clamp(8, 2000);
+
+        // Previous behaviour: one String copy per client frame
+        let started = Instant::now();
+        for _ in 0..iterations {
+            let json = serde_json::to_string(&message).unwrap();
+            let frames: Vec<Message> = clients.keys().map(|_| Message::Text(json.clone().into())).collect();
+            std::hint::black_box(frames);
+        }
+        let per_client_copy = started.elapsed() / iterations as u32;
+
+        let started = Instant::now();
+        for _ in 0..iterations {
+            let json: Utf8Bytes = serde_json::to_string(&message).unwrap().into();
+            let frames = Frames::new(&routing, json);
+            fan_out(&clients, &routing, &frames);
+            for queue in clients.values() {
+                for _ in 0..frames.count() {
+                    std::hint::black_box(queue.pop().await);
+                }
+            }
+        }
+        let shared = started.elapsed() / iterations as u32;
+
+        println!(
+            "{:>8} bytes x {} clients: per-client copy {:?}/msg, shared {:?}/msg",
+            size, CLIENTS, per_client_copy, shared
+        );
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore]
+async fn fanout_end_to_end() {
+    let server = MasterServer::new(IpAddr::from([127, 0, 0, 1]), 0, unbounded());
+    let (sync_tx, sync_rx) = mpsc::unbounded_channel();
+    server.start(sync_rx).await.unwrap();
+    let port = server.local_addr().await.unwrap().port();
+
+    let mut receivers = Vec::with_capacity(CLIENTS);
+    let mut slaves = Vec::with_capacity(CLIENTS);
+    for _ in 0..CLIENTS {
+        let slave = SlaveClient::new("127.0.0.1".to_string(), port);
+        receivers.push(slave.connect().await.unwrap());
+        slaves.push(slave);
+    }
+    while server.get_connected_clients_count().await < CLIENTS {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    for size in PAYLOAD_SIZES {
+        let count = (16 * 1024 * 1024 / size).clamp(4, 200);
+        let started = Instant::now();
+        for _ in 0..count {
+            sync_tx.send(message(size)).unwrap();
+        }
+        for rx in receivers.iter_mut() {
+            for _ in 0..count {
+                rx.recv().await.unwrap();
+            }
+        }
+        let elapsed = started.elapsed();
+
+        println!(
+            "{:>8} bytes x {} clients: {} msgs in {:?} ({:?}/msg delivered to all)",
+            size,
+            CLIENTS,
+            count,
+            elapsed,
+            elapsed / count as u32
+        );
+    }
+
+    server.stop().await;
+}
@@ -1,2 +1,141 @@
 pub mod server;
 pub mod client;
+pub mod relay;
+pub mod queue;
+
+use crate::sync::protocol::SyncMessage;
+use anyhow::Result;
+use client::SlaveClient;
+use queue::ClientQueueMetrics;
+use relay::{RelayClient, RelayServer};
+use serde::{Deserialize, Serialize};
+use server::MasterServer;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+pub type ClientId = String;
+
+/// Produces the complete state for one slave, which is then sent to that
+/// slave only
+pub type InitialStateCallback =
+    Arc<dyn Fn(ClientId) -> Pin<Box<dyn Future<Output = Option<SyncMessage>> + Send>> + Send + Sync>;
+pub type ClientMessageCallback = Arc<dyn Fn(ClientId, SyncMessage) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync>;
+
+/// How sync messages travel between master and slaves
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum TransportConfig {
+    /// Direct WebSocket connection to the master's sync server
+    #[default]
+    WebSocket,
+    /// Custom events on an OBS instance both sides are connected to, for
+    /// sites where only the OBS WebSocket port is reachable
+    ObsRelay { instance: String },
+}
+
+/// Master side of the selected transport
+pub enum MasterTransport {
+    WebSocket(MasterServer),
+    ObsRelay(RelayServer),
+}
+
+impl MasterTransport {
+    pub async fn set_initial_state_callback<F, Fut>(&self, callback: F)
+    where
+        F: Fn(ClientId) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Option<SyncMessage>> + Send + 'static,
+    {
+        match self {
+            Self::WebSocket(server) => server.set_initial_state_callback(callback).await,
+            Self::ObsRelay(server) => server.set_initial_state_callback(callback).await,
+        }
+    }
+
+    pub async fn set_client_message_callback<F, Fut>(&self, callback: F)
+    where
+        F: Fn(ClientId, SyncMessage) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        match self {
+            Self::WebSocket(server) => server.set_client_message_callback(callback).await,
+            Self::ObsRelay(server) => server.set_client_message_callback(callback).await,
+        }
+    }
+
+    pub async fn start(&self, sync_rx: mpsc::UnboundedReceiver<SyncMessage>) -> Result<()> {
+        match self {
+            Self::WebSocket(server) => server.start(sync_rx).await,
+            Self::ObsRelay(server) => server.start(sync_rx).await,
+        }
+    }
+
+    pub async fn stop(&self) {
+        match self {
+            Self::WebSocket(server) => server.stop().await,
+            Self::ObsRelay(server) => server.stop().await,
+        }
+    }
+
+    /// Listening address of the WebSocket server (the relay has none)
+    pub async fn local_addr(&self) -> Option<SocketAddr> {
+        match self {
+            Self::WebSocket(server) => server.local_addr().await,
+            Self::ObsRelay(_) => None,
+        }
+    }
+
+    pub async fn get_connected_clients_count(&self) -> usize {
+        match self {
+            Self::WebSocket(server) => server.get_connected_clients_count().await,
+            Self::ObsRelay(server) => server.get_connected_clients_count().await,
+        }
+    }
+
+    pub async fn get_client_ids(&self) -> Vec<ClientId> {
+        match self {
+            Self::WebSocket(server) => server.get_client_ids().await,
+            Self::ObsRelay(server) => server.get_client_ids().await,
+        }
+    }
+
+    /// Send queue depth per slave (the relay queues nothing itself)
+    pub async fn get_queue_metrics(&self) -> Vec<ClientQueueMetrics> {
+        match self {
+            Self::WebSocket(server) => server.get_queue_metrics().await,
+            Self::ObsRelay(_) => Vec::new(),
+        }
+    }
+}
+
+/// Slave side of the selected transport
+pub enum SlaveTransport {
+    WebSocket(SlaveClient),
+    ObsRelay(RelayClient),
+}
+
+impl SlaveTransport {
+    /// Sender for messages to the master; they are queued until connected
+    pub fn sender(&self) -> mpsc::UnboundedSender<SyncMessage> {
+        match self {
+            Self::WebSocket(client) => client.sender(),
+            Self::ObsRelay(client) => client.sender(),
+        }
+    }
+
+    pub async fn connect(&self) -> Result<mpsc::UnboundedReceiver<SyncMessage>> {
+        match self {
+            Self::WebSocket(client) => client.connect().await,
+            Self::ObsRelay(client) => client.connect().await,
+        }
+    }
+
+    pub async fn disconnect(&self) {
+        match self {
+            Self::WebSocket(client) => client.disconnect().await,
+            Self::ObsRelay(client) => client.disconnect().await,
+        }
+    }
+}
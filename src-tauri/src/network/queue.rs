@@ -0,0 +1,632 @@
+use crate::sync::protocol::{SyncMessage, SyncMessageType, SyncTargetType};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::Notify;
+use tokio_tungstenite::tungstenite::{Message, Utf8Bytes};
+
+/// What the master does with a slave whose queue is full
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SlowClientPolicy {
+    /// Drop the queued backlog and send the slave a fresh full state
+    #[default]
+    Resync,
+    /// Close the connection; the slave has to reconnect
+    Disconnect,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackpressureConfig {
+    pub max_queue_messages: usize,
+    pub max_queue_bytes: usize,
+    pub slow_client_policy: SlowClientPolicy,
+}
+
+impl Default for BackpressureConfig {
+    fn default() -> Self {
+        Self {
+            max_queue_messages: 1024,
+            max_queue_bytes: 64 * 1024 * 1024,
+            slow_client_policy: SlowClientPolicy::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientQueueMetrics {
+    pub client_id: String,
+    /// Messages currently waiting to be written
+    pub depth: usize,
+    pub bytes: usize,
+    pub max_depth: usize,
+    /// Updates replaced by a newer one before they were sent
+    pub coalesced: u64,
+    /// Times the queue was full and the slow client policy applied
+    pub overflows: u64,
+}
+
+/// Messages larger than this go out as `BulkChunk`s: higher lanes get in
+/// between the chunks of an asset, and no frame (pong, close) waits behind a
+/// multi-megabyte write
+pub const BULK_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Send priority; a lane is only served while the lanes above it are empty
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lane {
+    Control = 0,
+    Transform = 1,
+    Asset = 2,
+}
+
+const LANE_COUNT: usize = 3;
+
+fn lane(message: &SyncMessage) -> Lane {
+    match message.message_type {
+        // Cuts and heartbeats, plus structure that later messages refer to
+        // (a scene has to exist before the switch to it). A full state stays
+        // in order with the structure it is followed by.
+        SyncMessageType::StateSync
+        | SyncMessageType::SceneChange
+        | SyncMessageType::SceneCreated
+        | SyncMessageType::SceneRemoved
+        | SyncMessageType::SceneRenamed
+        | SyncMessageType::SceneItemCreated
+        | SyncMessageType::FilterCreated
+        | SyncMessageType::FilterRemoved
+        | SyncMessageType::FilterRenamed
+        | SyncMessageType::StudioModeUpdate
+        | SyncMessageType::TransitionUpdate
+        | SyncMessageType::CollectionUpdate
+        | SyncMessageType::OutputStateUpdate
+        | SyncMessageType::ReplayBufferSave
+        | SyncMessageType::TriggerHotkey
+        | SyncMessageType::Heartbeat => Lane::Control,
+        SyncMessageType::SourceUpdate
+        | SyncMessageType::ImageUpdate
+        | SyncMessageType::InputSettingsUpdate => Lane::Asset,
+        _ => Lane::Transform,
+    }
+}
+
+/// Messages that may be written ahead of bulk transfers queued before them
+/// in their lane, so a multi-megabyte state does not hold back a cut. The
+/// slave keeps a cut that overtook an older full state (see `SlaveSync`).
+fn overtakes_bulk(message: &SyncMessage) -> bool {
+    matches!(
+        message.message_type,
+        SyncMessageType::SceneChange | SyncMessageType::Heartbeat
+    )
+}
+
+/// Key under which a newer message supersedes a queued one. Only messages
+/// carrying the complete state of their subject qualify; partial updates
+/// (audio, settings overlays, ...) must all be delivered.
+fn coalesce_key(message: &SyncMessage) -> Option<String> {
+    let payload = &message.payload;
+    let str_field = |name: &str| payload[name].as_str().unwrap_or_default();
+    match message.message_type {
+        SyncMessageType::TransformUpdate => Some(format!(
+            "transform/{}/{}",
+            str_field("scene_name"),
+            payload["scene_item_id"].as_i64().unwrap_or_default()
+        )),
+        SyncMessageType::SceneChange => Some(format!("scene/{:?}", message.target_type)),
+        SyncMessageType::ImageUpdate => Some(format!(
+            "image/{}/{}",
+            str_field("scene_name"),
+            str_field("source_name")
+        )),
+        SyncMessageType::TextUpdate => Some(format!("text/{}", str_field("input_name"))),
+        SyncMessageType::StateSync => Some("state_sync".to_string()),
+        _ => None,
+    }
+}
+
+/// How a message is queued, worked out once per broadcast
+#[derive(Debug, Clone)]
+pub struct Routing {
+    key: Option<String>,
+    lane: Lane,
+    overtakes_bulk: bool,
+    target_type: SyncTargetType,
+}
+
+impl Routing {
+    pub fn of(message: &SyncMessage) -> Self {
+        Self {
+            key: coalesce_key(message),
+            lane: lane(message),
+            overtakes_bulk: overtakes_bulk(message),
+            target_type: message.target_type.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PushOutcome {
+    Queued,
+    Coalesced,
+    Overflow,
+    /// The connection is being closed
+    Closed,
+}
+
+/// Transfer ids are unique across all connections of this master, so a
+/// transfer is cut into chunks once and shared by every queue
+static NEXT_TRANSFER_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Frames of one serialized sync message, built once per broadcast. Clones
+/// share the buffers, so every queue it was pushed to, and the frames
+/// finally written, refer to the same bytes.
+#[derive(Clone)]
+pub struct Frames {
+    /// Frame text and the number of message bytes it carries
+    frames: Arc<[(Utf8Bytes, usize)]>,
+}
+
+impl Frames {
+    /// Messages larger than `BULK_CHUNK_SIZE` become `BulkChunk` frames
+    pub fn new(routing: &Routing, text: Utf8Bytes) -> Self {
+        if text.len() <= BULK_CHUNK_SIZE {
+            let bytes = text.len();
+            return Self {
+                frames: Arc::new([(text, bytes)]),
+            };
+        }
+
+        let transfer_id = NEXT_TRANSFER_ID.fetch_add(1, Ordering::Relaxed);
+        let mut frames = Vec::with_capacity(text.len().div_ceil(BULK_CHUNK_SIZE));
+        let mut sent = 0;
+        while sent < text.len() {
+            let mut end = (sent + BULK_CHUNK_SIZE).min(text.len());
+            while !text.is_char_boundary(end) {
+                end -= 1;
+            }
+            let chunk = SyncMessage::new(
+                SyncMessageType::BulkChunk,
+                routing.target_type.clone(),
+                serde_json::json!({
+                    "transfer_id": transfer_id,
+                    "index": frames.len(),
+                    "last": end == text.len(),
+                    "data": &text.as_str()[sent..end],
+                }),
+            );
+            // Strings and numbers only, so serialization cannot fail
+            frames.push((serde_json::to_string(&chunk).unwrap_or_default().into(), end - sent));
+            sent = end;
+        }
+        Self { frames: frames.into() }
+    }
+
+    /// Number of frames written for the message
+    pub fn count(&self) -> usize {
+        self.frames.len()
+    }
+}
+
+enum QueuedBody {
+    /// Protocol frame (pong, close)
+    Frame(Message),
+    /// Sync message; `next` is its first frame not yet written
+    Data { frames: Frames, next: usize },
+}
+
+struct QueuedMessage {
+    key: Option<String>,
+    overtakes_bulk: bool,
+    body: QueuedBody,
+}
+
+impl QueuedMessage {
+    /// Bytes still to be written
+    fn bytes(&self) -> usize {
+        match &self.body {
+            QueuedBody::Frame(_) => 0,
+            QueuedBody::Data { frames, next } => frames.frames[*next..].iter().map(|(_, bytes)| bytes).sum(),
+        }
+    }
+
+    /// A transfer that is partly written can no longer be dropped
+    fn is_started(&self) -> bool {
+        matches!(&self.body, QueuedBody::Data { next, .. } if *next > 0)
+    }
+
+    fn is_bulk(&self) -> bool {
+        matches!(&self.body, QueuedBody::Data { frames, .. } if frames.count() > 1)
+    }
+}
+
+#[derive(Default)]
+struct QueueState {
+    lanes: [VecDeque<QueuedMessage>; LANE_COUNT],
+    bytes: usize,
+    closed: bool,
+    max_depth: usize,
+    coalesced: u64,
+    overflows: u64,
+    /// Resyncs since the queue last ran empty
+    resyncs: u32,
+}
+
+impl QueueState {
+    fn depth(&self) -> usize {
+        self.lanes.iter().map(VecDeque::len).sum()
+    }
+
+    /// Drop the messages of `lanes` that are not partly written yet
+    fn drop_backlog(&mut self, lanes: &[Lane]) {
+        for &lane in lanes {
+            self.lanes[lane as usize].retain(QueuedMessage::is_started);
+        }
+        self.bytes = self.lanes.iter().flatten().map(QueuedMessage::bytes).sum();
+    }
+
+    fn pop_frame(&mut self) -> Option<Message> {
+        for lane in self.lanes.iter_mut() {
+            // Serve the first message that may overtake the bulk transfers
+            // ahead of it, between their chunks
+            let position = lane
+                .iter()
+                .position(|queued| !queued.is_bulk())
+                .filter(|&position| position > 0 && lane[position].overtakes_bulk)
+                .unwrap_or(0);
+            let Some(head) = lane.get_mut(position) else {
+                continue;
+            };
+            if let QueuedBody::Data { frames, next } = &mut head.body {
+                let (text, bytes) = frames.frames[*next].clone();
+                *next += 1;
+                if *next == frames.count() {
+                    lane.remove(position);
+                }
+                self.bytes -= bytes;
+                return Some(Message::Text(text));
+            }
+            if let Some(QueuedMessage {
+                body: QueuedBody::Frame(frame),
+                ..
+            }) = lane.remove(position)
+            {
+                return Some(frame);
+            }
+        }
+        None
+    }
+}
+
+/// Outgoing queue of one slave connection, drained by its writer task
+pub struct ClientQueue {
+    state: Mutex<QueueState>,
+    notify: Notify,
+    config: BackpressureConfig,
+}
+
+impl ClientQueue {
+    pub fn new(config: BackpressureConfig) -> Self {
+        Self {
+            state: Mutex::new(QueueState::default()),
+            notify: Notify::new(),
+            config,
+        }
+    }
+
+    /// Queue a serialized sync message at the back of its lane. A queued
+    /// message with the same key is dropped first, so the newer one still
+    /// follows everything queued before it (a cut never overtakes the scene
+    /// it switches to).
+    pub fn push(&self, routing: &Routing, frames: &Frames) -> PushOutcome {
+        self.enqueue(routing, frames, false)
+    }
+
+    /// Queue a complete state for this slave. It replaces the backlog the
+    /// slave missed, so it is queued even when it exceeds the limits.
+    /// Transform and asset updates queued before it would be written after it
+    /// and undo it, so they are dropped.
+    pub fn push_state(&self, routing: &Routing, frames: &Frames) -> PushOutcome {
+        self.enqueue(routing, frames, true)
+    }
+
+    fn enqueue(&self, routing: &Routing, frames: &Frames, is_state: bool) -> PushOutcome {
+        let mut guard = self.state.lock().unwrap();
+        let state = &mut *guard;
+        if state.closed {
+            return PushOutcome::Closed;
+        }
+        if is_state {
+            state.drop_backlog(&[Lane::Transform, Lane::Asset]);
+        }
+
+        // The queued message with the same key is only dropped once the newer
+        // one is known to fit; it does not count against the limits
+        let lane = &state.lanes[routing.lane as usize];
+        let replaced = routing.key.as_ref().and_then(|key| {
+            lane.iter()
+                .position(|queued| queued.key.as_ref() == Some(key) && !queued.is_started())
+        });
+        let replaced_bytes = replaced.map_or(0, |position| lane[position].bytes());
+
+        let message = QueuedMessage {
+            key: routing.key.clone(),
+            overtakes_bulk: routing.overtakes_bulk,
+            body: QueuedBody::Data {
+                frames: frames.clone(),
+                next: 0,
+            },
+        };
+        let bytes = message.bytes();
+        let depth = state.depth() - usize::from(replaced.is_some());
+        if !is_state
+            && (depth >= self.config.max_queue_messages
+                || state.bytes - replaced_bytes + bytes > self.config.max_queue_bytes)
+        {
+            state.overflows += 1;
+            return PushOutcome::Overflow;
+        }
+
+        if let Some(position) = replaced {
+            state.lanes[routing.lane as usize].remove(position);
+            state.bytes -= replaced_bytes;
+            state.coalesced += 1;
+        }
+        state.lanes[routing.lane as usize].push_back(message);
+        state.bytes += bytes;
+        state.max_depth = state.max_depth.max(depth + 1);
+        drop(guard);
+        self.notify.notify_one();
+        if replaced.is_some() {
+            PushOutcome::Coalesced
+        } else {
+            PushOutcome::Queued
+        }
+    }
+
+    /// Queue a protocol frame (pong, close) ahead of data, outside the limits
+    pub fn push_control(&self, frame: Message) {
+        self.state.lock().unwrap().lanes[Lane::Control as usize].push_front(QueuedMessage {
+            key: None,
+            overtakes_bulk: false,
+            body: QueuedBody::Frame(frame),
+        });
+        self.notify.notify_one();
+    }
+
+    /// Next frame to write, from the highest non-empty lane; `None` once the
+    /// queue is closed and drained
+    pub async fn pop(&self) -> Option<Message> {
+        loop {
+            {
+                let mut state = self.state.lock().unwrap();
+                if let Some(frame) = state.pop_frame() {
+                    if state.depth() == 0 {
+                        state.resyncs = 0;
+                    }
+                    return Some(frame);
+                }
+                if state.closed {
+                    return None;
+                }
+            }
+            self.notify.notified().await;
+        }
+    }
+
+    /// Count a resync of this slave; returns the number of resyncs since its
+    /// queue last ran empty
+    pub fn note_resync(&self) -> u32 {
+        let mut state = self.state.lock().unwrap();
+        state.resyncs += 1;
+        state.resyncs
+    }
+
+    /// Drop the backlog (before a resync). Partly written transfers are
+    /// finished, so the slave is never left holding half of one.
+    pub fn clear(&self) {
+        self.state
+            .lock()
+            .unwrap()
+            .drop_backlog(&[Lane::Control, Lane::Transform, Lane::Asset]);
+    }
+
+    /// Stop accepting messages; the writer ends after the remaining frames
+    pub fn close(&self) {
+        self.state.lock().unwrap().closed = true;
+        self.notify.notify_one();
+    }
+
+    pub fn metrics(&self, client_id: &str) -> ClientQueueMetrics {
+        let state = self.state.lock().unwrap();
+        ClientQueueMetrics {
+            client_id: client_id.to_string(),
+            depth: state.depth(),
+            bytes: state.bytes,
+            max_depth: state.max_depth,
+            coalesced: state.coalesced,
+            overflows: state.overflows,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn message(message_type: SyncMessageType, payload: serde_json::Value) -> SyncMessage {
+        SyncMessage::new(message_type, SyncTargetType::Program, payload)
+    }
+
+    fn push(queue: &ClientQueue, message: &SyncMessage) -> PushOutcome {
+        let routing = Routing::of(message);
+        let frames = Frames::new(&routing, serde_json::to_string(message).unwrap().into());
+        queue.push(&routing, &frames)
+    }
+
+    fn cut(scene_name: &str) -> SyncMessage {
+        message(SyncMessageType::SceneChange, json!({ "scene_name": scene_name }))
+    }
+
+    fn large_state() -> SyncMessage {
+        message(SyncMessageType::StateSync, json!({ "blob": "x".repeat(4 * BULK_CHUNK_SIZE) }))
+    }
+
+    async fn next(queue: &ClientQueue) -> SyncMessage {
+        match queue.pop().await {
+            Some(Message::Text(text)) => serde_json::from_str(&text).unwrap(),
+            other => panic!("expected a text frame, got {:?}", other),
+        }
+    }
+
+    fn transform(scene_item_id: i64, x: f64) -> SyncMessage {
+        message(
+            SyncMessageType::TransformUpdate,
+            json!({ "scene_name": "Live", "scene_item_id": scene_item_id, "transform": { "position_x": x } }),
+        )
+    }
+
+    fn push_state(queue: &ClientQueue, message: &SyncMessage) -> PushOutcome {
+        let routing = Routing::of(message);
+        let frames = Frames::new(&routing, serde_json::to_string(message).unwrap().into());
+        queue.push_state(&routing, &frames)
+    }
+
+    #[tokio::test]
+    async fn serves_lanes_in_priority_order() {
+        let queue = ClientQueue::new(BackpressureConfig::default());
+        push(&queue, &message(SyncMessageType::ImageUpdate, json!({ "source_name": "Logo" })));
+        push(&queue, &transform(1, 0.0));
+        push(&queue, &transform(2, 0.0));
+        push(&queue, &cut("Live"));
+        queue.push_control(Message::Pong(Default::default()));
+
+        assert!(matches!(queue.pop().await, Some(Message::Pong(_))));
+        assert_eq!(next(&queue).await.message_type, SyncMessageType::SceneChange);
+        assert_eq!(next(&queue).await.payload["scene_item_id"], 1);
+        assert_eq!(next(&queue).await.payload["scene_item_id"], 2);
+        assert_eq!(next(&queue).await.message_type, SyncMessageType::ImageUpdate);
+        assert_eq!(queue.metrics("slave").depth, 0);
+    }
+
+    #[tokio::test]
+    async fn newer_update_replaces_queued_one() {
+        let queue = ClientQueue::new(BackpressureConfig::default());
+        assert_eq!(push(&queue, &transform(1, 10.0)), PushOutcome::Queued);
+        assert_eq!(push(&queue, &transform(2, 0.0)), PushOutcome::Queued);
+        assert_eq!(push(&queue, &transform(1, 20.0)), PushOutcome::Coalesced);
+
+        let metrics = queue.metrics("slave");
+        assert_eq!((metrics.depth, metrics.coalesced), (2, 1));
+
+        // The newer update is queued behind everything queued before it
+        assert_eq!(next(&queue).await.payload["scene_item_id"], 2);
+        let latest = next(&queue).await;
+        assert_eq!(latest.payload["scene_item_id"], 1);
+        assert_eq!(latest.payload["transform"]["position_x"], 20.0);
+        assert_eq!(queue.metrics("slave").bytes, 0);
+    }
+
+    #[tokio::test]
+    async fn overflow_keeps_queued_messages() {
+        let queue = ClientQueue::new(BackpressureConfig {
+            max_queue_messages: 2,
+            ..BackpressureConfig::default()
+        });
+        push(&queue, &transform(1, 10.0));
+        push(&queue, &transform(2, 0.0));
+        assert_eq!(push(&queue, &transform(3, 0.0)), PushOutcome::Overflow);
+
+        // Replacing a queued update does not grow the queue
+        assert_eq!(push(&queue, &transform(1, 20.0)), PushOutcome::Coalesced);
+        let metrics = queue.metrics("slave");
+        assert_eq!((metrics.depth, metrics.coalesced, metrics.overflows), (2, 1, 1));
+
+        let queue = ClientQueue::new(BackpressureConfig {
+            max_queue_bytes: 200,
+            ..BackpressureConfig::default()
+        });
+        push(&queue, &transform(1, 10.0));
+        let larger = message(
+            SyncMessageType::TransformUpdate,
+            json!({ "scene_name": "Live", "scene_item_id": 1, "padding": "x".repeat(200) }),
+        );
+        assert_eq!(push(&queue, &larger), PushOutcome::Overflow);
+
+        // The update that did not fit neither replaced nor counted as coalesced
+        let metrics = queue.metrics("slave");
+        assert_eq!((metrics.depth, metrics.coalesced, metrics.overflows), (1, 0, 1));
+        assert_eq!(next(&queue).await.payload["transform"]["position_x"], 10.0);
+    }
+
+    #[tokio::test]
+    async fn state_drops_older_updates() {
+        let queue = ClientQueue::new(BackpressureConfig::default());
+        let image = message(SyncMessageType::ImageUpdate, json!({ "data": "x".repeat(2 * BULK_CHUNK_SIZE) }));
+        push(&queue, &image);
+        assert_eq!(next(&queue).await.message_type, SyncMessageType::BulkChunk);
+        push(&queue, &transform(1, 0.0));
+        push(&queue, &cut("Live"));
+        push_state(&queue, &message(SyncMessageType::StateSync, json!({})));
+
+        // The cut stays in order with the state, the started image is finished
+        assert_eq!(next(&queue).await.message_type, SyncMessageType::SceneChange);
+        assert_eq!(next(&queue).await.message_type, SyncMessageType::StateSync);
+        let mut chunk = next(&queue).await;
+        while chunk.payload["last"] != true {
+            chunk = next(&queue).await;
+        }
+        assert_eq!(queue.metrics("slave").depth, 0);
+    }
+
+    #[tokio::test]
+    async fn resyncs_count_until_queue_runs_empty() {
+        let queue = ClientQueue::new(BackpressureConfig::default());
+        push(&queue, &transform(1, 0.0));
+        assert_eq!(queue.note_resync(), 1);
+        assert_eq!(queue.note_resync(), 2);
+
+        queue.clear();
+        push(&queue, &transform(1, 0.0));
+        assert_eq!(queue.metrics("slave").depth, 1);
+        assert_eq!(queue.note_resync(), 3);
+
+        next(&queue).await;
+        assert_eq!(queue.note_resync(), 1);
+    }
+
+    #[tokio::test]
+    async fn cut_overtakes_large_state() {
+        let queue = ClientQueue::new(BackpressureConfig::default());
+        push(&queue, &large_state());
+        push(&queue, &cut("Live"));
+
+        let first = next(&queue).await;
+        assert_eq!(first.message_type, SyncMessageType::SceneChange);
+        assert_eq!(first.payload["scene_name"], "Live");
+
+        // A cut queued while the state is being written goes between its chunks
+        assert_eq!(next(&queue).await.message_type, SyncMessageType::BulkChunk);
+        push(&queue, &cut("Break"));
+        assert_eq!(next(&queue).await.payload["scene_name"], "Break");
+
+        let mut chunks = 1;
+        while queue.metrics("slave").depth > 0 {
+            assert_eq!(next(&queue).await.message_type, SyncMessageType::BulkChunk);
+            chunks += 1;
+        }
+        assert_eq!(chunks, 5);
+    }
+
+    #[tokio::test]
+    async fn cut_stays_behind_structure() {
+        let queue = ClientQueue::new(BackpressureConfig::default());
+        push(&queue, &large_state());
+        push(&queue, &message(SyncMessageType::SceneCreated, json!({ "scene_name": "Live" })));
+        push(&queue, &cut("Live"));
+
+        assert_eq!(next(&queue).await.message_type, SyncMessageType::BulkChunk);
+    }
+}
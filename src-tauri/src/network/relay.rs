@@ -0,0 +1,343 @@
+use super::{ClientId, ClientMessageCallback, InitialStateCallback};
+use crate::obs::client::OBSClient;
+use crate::sync::protocol::{SyncMessage, SyncMessageType};
+use anyhow::{Context, Result};
+use futures::{Stream, StreamExt};
+use obws::events::Event;
+use obws::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, Mutex, RwLock};
+use tokio::task::JoinHandle;
+
+/// `obs_sync` tag of relayed sync messages, next to other custom events
+/// such as `trigger_hotkey`
+const RELAY_EVENT: &str = "sync_message";
+/// Slaves announce themselves at this interval; there is no connection to
+/// watch, so this is how the master counts them
+const RELAY_HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
+/// A slave that has not been heard from for this long is dropped
+const RELAY_CLIENT_TIMEOUT: Duration = Duration::from_secs(15);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum RelayDirection {
+    ToSlaves,
+    ToMaster,
+}
+
+/// Custom event data wrapping one sync message. Every client of the relay
+/// OBS receives every custom event, so the direction tells each side which
+/// ones are meant for it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RelayEnvelope {
+    obs_sync: String,
+    direction: RelayDirection,
+    /// Sending slave; on messages to slaves the one slave it is meant for
+    /// (empty for all of them)
+    #[serde(default)]
+    client_id: ClientId,
+    message: SyncMessage,
+}
+
+impl RelayEnvelope {
+    fn new(direction: RelayDirection, client_id: ClientId, message: SyncMessage) -> Self {
+        Self {
+            obs_sync: RELAY_EVENT.to_string(),
+            direction,
+            client_id,
+            message,
+        }
+    }
+
+    /// Relayed sync message in the given direction, if this event is one
+    fn parse(event_data: Value, direction: RelayDirection) -> Option<Self> {
+        if event_data.get("obs_sync").and_then(Value::as_str) != Some(RELAY_EVENT) {
+            return None;
+        }
+        match serde_json::from_value::<RelayEnvelope>(event_data) {
+            Ok(envelope) if envelope.direction == direction => Some(envelope),
+            Ok(_) => None,
+            Err(e) => {
+                eprintln!("Failed to parse relayed sync message: {}", e);
+                None
+            }
+        }
+    }
+}
+
+async fn broadcast(client_arc: &RwLock<Option<Arc<Client>>>, envelope: &RelayEnvelope) -> Result<()> {
+    let client_lock = client_arc.read().await;
+    let client = client_lock
+        .as_ref()
+        .context("Relay OBS instance is not connected")?;
+    client
+        .general()
+        .broadcast_custom_event(envelope)
+        .await
+        .context("Failed to broadcast custom event")?;
+    Ok(())
+}
+
+/// Custom event payloads of the relay OBS instance
+async fn custom_events(client_arc: &RwLock<Option<Arc<Client>>>) -> Result<impl Stream<Item = Value>> {
+    let client_lock = client_arc.read().await;
+    let client = client_lock
+        .as_ref()
+        .context("Relay OBS instance is not connected")?;
+    let events = client
+        .events()
+        .context("Failed to subscribe to relay OBS events")?;
+    Ok(events.filter_map(|event| async move {
+        match event {
+            Event::CustomEvent(event_data) => Some(event_data),
+            _ => None,
+        }
+    }))
+}
+
+/// Master side of the OBS relay: broadcasts sync messages as custom events
+/// on a shared OBS instance and picks up the slaves' messages from there
+pub struct RelayServer {
+    obs_client: Arc<OBSClient>,
+    /// Last time each slave was heard from
+    clients: Arc<RwLock<HashMap<ClientId, Instant>>>,
+    tasks: Arc<RwLock<Vec<JoinHandle<()>>>>,
+    initial_state_callback: Arc<RwLock<Option<InitialStateCallback>>>,
+    client_message_callback: Arc<RwLock<Option<ClientMessageCallback>>>,
+}
+
+impl RelayServer {
+    pub fn new(obs_client: Arc<OBSClient>) -> Self {
+        Self {
+            obs_client,
+            clients: Arc::new(RwLock::new(HashMap::new())),
+            tasks: Arc::new(RwLock::new(Vec::new())),
+            initial_state_callback: Arc::new(RwLock::new(None)),
+            client_message_callback: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    pub async fn set_initial_state_callback<F, Fut>(&self, callback: F)
+    where
+        F: Fn(ClientId) -> Fut + Send + Sync + 'static,
+        Fut: std::future::Future<Output = Option<SyncMessage>> + Send + 'static,
+    {
+        let wrapped = Arc::new(move |client_id: ClientId| {
+            Box::pin(callback(client_id)) as std::pin::Pin<Box<dyn std::future::Future<Output = Option<SyncMessage>> + Send>>
+        });
+        *self.initial_state_callback.write().await = Some(wrapped);
+    }
+
+    pub async fn set_client_message_callback<F, Fut>(&self, callback: F)
+    where
+        F: Fn(ClientId, SyncMessage) -> Fut + Send + Sync + 'static,
+        Fut: std::future::Future<Output = ()> + Send + 'static,
+    {
+        let wrapped = Arc::new(move |client_id: ClientId, message: SyncMessage| {
+            Box::pin(callback(client_id, message)) as std::pin::Pin<Box<dyn std::future::Future<Output = ()> + Send>>
+        });
+        *self.client_message_callback.write().await = Some(wrapped);
+    }
+
+    pub async fn stop(&self) {
+        let tasks = self.tasks.write().await;
+        for task in tasks.iter() {
+            task.abort();
+        }
+
+        self.clients.write().await.clear();
+
+        println!("OBS relay stopped");
+    }
+
+    pub async fn start(&self, mut sync_rx: mpsc::UnboundedReceiver<SyncMessage>) -> Result<()> {
+        let client_arc = self.obs_client.get_client_arc();
+        let mut events = Box::pin(custom_events(&client_arc).await?);
+
+        println!("Master relaying sync messages through OBS custom events");
+
+        // Broadcast sync messages to the relay
+        let client_for_broadcast = client_arc.clone();
+        let broadcast_task = tokio::spawn(async move {
+            while let Some(message) = sync_rx.recv().await {
+                let envelope = RelayEnvelope::new(RelayDirection::ToSlaves, String::new(), message);
+                if let Err(e) = broadcast(&client_for_broadcast, &envelope).await {
+                    eprintln!("Failed to relay sync message: {}", e);
+                }
+            }
+        });
+
+        // Messages from slaves; a state sync request doubles as the hello
+        let clients = self.clients.clone();
+        let callback = self.initial_state_callback.clone();
+        let message_callback = self.client_message_callback.clone();
+        let client_for_state = client_arc.clone();
+        let receive_task = tokio::spawn(async move {
+            while let Some(event_data) = events.next().await {
+                let Some(envelope) = RelayEnvelope::parse(event_data, RelayDirection::ToMaster) else {
+                    continue;
+                };
+                let client_id = envelope.client_id;
+                if clients
+                    .write()
+                    .await
+                    .insert(client_id.clone(), Instant::now())
+                    .is_none()
+                {
+                    println!("Relay client connected: {}", client_id);
+                }
+
+                match envelope.message.message_type {
+                    SyncMessageType::Heartbeat => {}
+                    SyncMessageType::StateSyncRequest => {
+                        if let Some(cb) = callback.read().await.as_ref() {
+                            let state = cb(client_id.clone());
+                            let client_arc = client_for_state.clone();
+                            println!("Triggered initial state sync for relay client: {}", client_id);
+                            tokio::spawn(async move {
+                                let Some(state) = state.await else {
+                                    return;
+                                };
+                                let envelope = RelayEnvelope::new(RelayDirection::ToSlaves, client_id, state);
+                                if let Err(e) = broadcast(&client_arc, &envelope).await {
+                                    eprintln!("Failed to relay initial state: {}", e);
+                                }
+                            });
+                        }
+                    }
+                    _ => {
+                        if let Some(cb) = message_callback.read().await.as_ref() {
+                            tokio::spawn(cb(client_id, envelope.message));
+                        }
+                    }
+                }
+            }
+            println!("Relay OBS event stream ended");
+        });
+
+        // Forget slaves that stopped sending heartbeats
+        let clients_for_prune = self.clients.clone();
+        let prune_task = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(RELAY_HEARTBEAT_INTERVAL);
+            loop {
+                interval.tick().await;
+                clients_for_prune.write().await.retain(|client_id, last_seen| {
+                    let alive = last_seen.elapsed() < RELAY_CLIENT_TIMEOUT;
+                    if !alive {
+                        println!("Relay client timed out: {}", client_id);
+                    }
+                    alive
+                });
+            }
+        });
+
+        let mut tasks = self.tasks.write().await;
+        tasks.push(broadcast_task);
+        tasks.push(receive_task);
+        tasks.push(prune_task);
+
+        Ok(())
+    }
+
+    pub async fn get_connected_clients_count(&self) -> usize {
+        self.clients.read().await.len()
+    }
+
+    pub async fn get_client_ids(&self) -> Vec<ClientId> {
+        self.clients.read().await.keys().cloned().collect()
+    }
+}
+
+/// Slave side of the OBS relay
+pub struct RelayClient {
+    obs_client: Arc<OBSClient>,
+    client_id: ClientId,
+    tasks: Mutex<Vec<JoinHandle<()>>>,
+    /// Messages to the master (status reports), broadcast by the connection task
+    outgoing_tx: mpsc::UnboundedSender<SyncMessage>,
+    outgoing_rx: Mutex<Option<mpsc::UnboundedReceiver<SyncMessage>>>,
+}
+
+impl RelayClient {
+    pub fn new(obs_client: Arc<OBSClient>) -> Self {
+        let (outgoing_tx, outgoing_rx) = mpsc::unbounded_channel();
+        Self {
+            obs_client,
+            client_id: format!("relay-{}", uuid::Uuid::new_v4()),
+            tasks: Mutex::new(Vec::new()),
+            outgoing_tx,
+            outgoing_rx: Mutex::new(Some(outgoing_rx)),
+        }
+    }
+
+    /// Sender for messages to the master; they are queued until connected
+    pub fn sender(&self) -> mpsc::UnboundedSender<SyncMessage> {
+        self.outgoing_tx.clone()
+    }
+
+    pub async fn connect(&self) -> Result<mpsc::UnboundedReceiver<SyncMessage>> {
+        let client_arc = self.obs_client.get_client_arc();
+        let mut events = Box::pin(custom_events(&client_arc).await?);
+
+        println!("Connected to OBS relay as {}", self.client_id);
+
+        let (tx, rx) = mpsc::unbounded_channel();
+        let mut tasks = self.tasks.lock().await;
+
+        if let Some(mut outgoing_rx) = self.outgoing_rx.lock().await.take() {
+            let client_arc = client_arc.clone();
+            let client_id = self.client_id.clone();
+            tasks.push(tokio::spawn(async move {
+                while let Some(message) = outgoing_rx.recv().await {
+                    let envelope = RelayEnvelope::new(RelayDirection::ToMaster, client_id.clone(), message);
+                    if let Err(e) = broadcast(&client_arc, &envelope).await {
+                        eprintln!("Failed to relay message to master: {}", e);
+                    }
+                }
+            }));
+        }
+
+        // Handle incoming messages, skipping those addressed to other slaves
+        let client_id = self.client_id.clone();
+        tasks.push(tokio::spawn(async move {
+            while let Some(event_data) = events.next().await {
+                let Some(envelope) = RelayEnvelope::parse(event_data, RelayDirection::ToSlaves) else {
+                    continue;
+                };
+                if !envelope.client_id.is_empty() && envelope.client_id != client_id {
+                    continue;
+                }
+                if tx.send(envelope.message).is_err() {
+                    break;
+                }
+            }
+            println!("Relay OBS event stream ended");
+        }));
+
+        // Ask for the initial state, then keep the master's client list fresh
+        let _ = self.outgoing_tx.send(SyncMessage::state_sync_request());
+        let outgoing_tx = self.outgoing_tx.clone();
+        tasks.push(tokio::spawn(async move {
+            let mut interval = tokio::time::interval(RELAY_HEARTBEAT_INTERVAL);
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                if outgoing_tx.send(SyncMessage::heartbeat()).is_err() {
+                    break;
+                }
+            }
+        }));
+
+        Ok(rx)
+    }
+
+    pub async fn disconnect(&self) {
+        for task in self.tasks.lock().await.drain(..) {
+            task.abort();
+        }
+    }
+}
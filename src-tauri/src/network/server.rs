@@ -1,143 +1,330 @@
+use super::queue::{BackpressureConfig, ClientQueue, ClientQueueMetrics, Frames, PushOutcome, Routing, SlowClientPolicy};
+use super::{ClientId, ClientMessageCallback, InitialStateCallback};
 use crate::sync::protocol::SyncMessage;
 use anyhow::{Context, Result};
+use futures::future::{self, Either};
 use futures::{SinkExt, StreamExt};
 use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
 use std::sync::Arc;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 use tokio::net::{TcpListener, TcpStream};
-use tokio::sync::{mpsc, RwLock};
-use tokio::task::JoinHandle;
-use tokio_tungstenite::{accept_async, tungstenite::Message, WebSocketStream};
+use tokio::sync::{mpsc, watch, Mutex, RwLock};
+use tokio::task::JoinSet;
+use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+use tokio_tungstenite::tungstenite::protocol::CloseFrame;
+use tokio_tungstenite::tungstenite::{Message, Utf8Bytes};
+use tokio_tungstenite::{accept_async, WebSocketStream};
 
-type ClientId = String;
 type ClientConnection = WebSocketStream<TcpStream>;
+type ClientMap = HashMap<ClientId, Arc<ClientQueue>>;
+/// Copy-on-write: connections replace the map, the broadcast task works on
+/// a snapshot and never holds the lock while queueing
+type ClientRegistry = Arc<RwLock<Arc<ClientMap>>>;
 
-type InitialStateCallback = Arc<dyn Fn(ClientId) -> std::pin::Pin<Box<dyn std::future::Future<Output = ()> + Send>> + Send + Sync>;
+/// How long `stop` waits for slaves to acknowledge the Close frame
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(3);
+/// How long a connection waits for the slave to answer its Close frame
+const CLOSE_ACK_TIMEOUT: Duration = Duration::from_secs(1);
+/// Pause after a failed accept (e.g. out of file descriptors) before the next
+const ACCEPT_RETRY_DELAY: Duration = Duration::from_millis(100);
+/// A slave that overflows this many times in a row, without its queue ever
+/// running empty in between, cannot keep up even with a resync and is
+/// disconnected instead
+const MAX_CONSECUTIVE_RESYNCS: u32 = 3;
 
 pub struct MasterServer {
-    clients: Arc<RwLock<HashMap<ClientId, mpsc::UnboundedSender<Message>>>>,
+    clients: ClientRegistry,
+    backpressure: BackpressureConfig,
+    bind_address: IpAddr,
+    /// 0 lets the OS pick a free port; see `local_addr`
     port: u16,
-    shutdown: Arc<AtomicBool>,
-    tasks: Arc<RwLock<Vec<JoinHandle<()>>>>,
+    local_addr: RwLock<Option<SocketAddr>>,
+    shutdown: watch::Sender<bool>,
+    /// Broadcast and accept tasks plus one task per slave connection
+    tasks: Arc<Mutex<JoinSet<()>>>,
     initial_state_callback: Arc<RwLock<Option<InitialStateCallback>>>,
+    client_message_callback: Arc<RwLock<Option<ClientMessageCallback>>>,
 }
 
 impl MasterServer {
-    pub fn new(port: u16) -> Self {
+    pub fn new(bind_address: IpAddr, port: u16, backpressure: BackpressureConfig) -> Self {
         Self {
-            clients: Arc::new(RwLock::new(HashMap::new())),
+            clients: Arc::new(RwLock::new(Arc::new(HashMap::new()))),
+            backpressure,
+            bind_address,
             port,
-            shutdown: Arc::new(AtomicBool::new(false)),
-            tasks: Arc::new(RwLock::new(Vec::new())),
+            local_addr: RwLock::new(None),
+            shutdown: watch::channel(false).0,
+            tasks: Arc::new(Mutex::new(JoinSet::new())),
             initial_state_callback: Arc::new(RwLock::new(None)),
+            client_message_callback: Arc::new(RwLock::new(None)),
         }
     }
 
     pub async fn set_initial_state_callback<F, Fut>(&self, callback: F)
     where
         F: Fn(ClientId) -> Fut + Send + Sync + 'static,
-        Fut: std::future::Future<Output = ()> + Send + 'static,
+        Fut: std::future::Future<Output = Option<SyncMessage>> + Send + 'static,
     {
         let wrapped = Arc::new(move |client_id: ClientId| {
-            Box::pin(callback(client_id)) as std::pin::Pin<Box<dyn std::future::Future<Output = ()> + Send>>
+            Box::pin(callback(client_id)) as std::pin::Pin<Box<dyn std::future::Future<Output = Option<SyncMessage>> + Send>>
         });
         *self.initial_state_callback.write().await = Some(wrapped);
     }
     
+    /// Handle messages sent by slaves (status reports)
+    pub async fn set_client_message_callback<F, Fut>(&self, callback: F)
+    where
+        F: Fn(ClientId, SyncMessage) -> Fut + Send + Sync + 'static,
+        Fut: std::future::Future<Output = ()> + Send + 'static,
+    {
+        let wrapped = Arc::new(move |client_id: ClientId, message: SyncMessage| {
+            Box::pin(callback(client_id, message)) as std::pin::Pin<Box<dyn std::future::Future<Output = ()> + Send>>
+        });
+        *self.client_message_callback.write().await = Some(wrapped);
+    }
+    
     pub async fn stop(&self) {
-        // Signal shutdown
-        self.shutdown.store(true, Ordering::SeqCst);
-        
-        // Abort all tasks
-        let tasks = self.tasks.write().await;
-        for task in tasks.iter() {
-            task.abort();
+        // Stops the accept loop (releasing the port) and the broadcast task;
+        // connections still registering see it and close themselves
+        self.shutdown.send_replace(true);
+
+        // Replace any backlog with a Close frame; each writer ends once it is out
+        for queue in self.clients.read().await.values() {
+            queue.clear();
+            close_queue(queue, CloseCode::Away, "Master server stopped");
         }
-        
+
+        // Connections end when the slave answers the Close frame. The accept
+        // loop may add a last connection while the first set is joined.
+        let deadline = tokio::time::Instant::now() + SHUTDOWN_TIMEOUT;
+        loop {
+            let mut tasks = std::mem::take(&mut *self.tasks.lock().await);
+            if tasks.is_empty() {
+                break;
+            }
+            let joined = tokio::time::timeout_at(deadline, async {
+                while tasks.join_next().await.is_some() {}
+            })
+            .await;
+            if joined.is_err() {
+                eprintln!("Master server tasks did not finish within {:?}, aborting", SHUTDOWN_TIMEOUT);
+                tasks.shutdown().await;
+            }
+        }
+
         // Clear clients
-        self.clients.write().await.clear();
-        
+        *self.clients.write().await = Arc::new(HashMap::new());
+        *self.local_addr.write().await = None;
+
         println!("Master server stopped");
     }
 
     pub async fn start(&self, mut sync_rx: mpsc::UnboundedReceiver<SyncMessage>) -> Result<()> {
-        let addr = format!("0.0.0.0:{}", self.port);
-        let listener = TcpListener::bind(&addr)
+        // `::` accepts IPv4 too where the OS defaults to dual-stack sockets
+        let addr = SocketAddr::new(self.bind_address, self.port);
+        let listener = TcpListener::bind(addr)
             .await
             .context(format!("Failed to bind to {}", addr))?;
+        let local_addr = listener
+            .local_addr()
+            .context("Failed to get listening address")?;
+        *self.local_addr.write().await = Some(local_addr);
+
+        println!("Master server listening on: {}", local_addr);
 
-        println!("Master server listening on: {}", addr);
+        // Subscribed before spawning, so a `stop` racing with `start` is seen
+        self.shutdown.send_replace(false);
+        let mut shutdown = self.shutdown.subscribe();
 
         let clients = self.clients.clone();
-        let shutdown = self.shutdown.clone();
+        let slow_client_policy = self.backpressure.slow_client_policy;
+        let callback_for_resync = self.initial_state_callback.clone();
 
         // Broadcast sync messages to all connected clients
-        let broadcast_task = tokio::spawn(async move {
-            while let Some(message) = sync_rx.recv().await {
-                if shutdown.load(Ordering::SeqCst) {
-                    break;
-                }
-                
-                let json = match serde_json::to_string(&message) {
-                    Ok(j) => j,
+        let broadcast_task = async move {
+            loop {
+                let message = tokio::select! {
+                    _ = shutdown.changed() => break,
+                    message = sync_rx.recv() => match message {
+                        Some(message) => message,
+                        None => break,
+                    },
+                };
+
+                // Serialized and chunked once; every queue shares the frames
+                let json: Utf8Bytes = match serde_json::to_string(&message) {
+                    Ok(j) => j.into(),
                     Err(e) => {
                         eprintln!("Failed to serialize sync message: {}", e);
                         continue;
                     }
                 };
 
-                let clients_lock = clients.read().await;
-                for (client_id, tx) in clients_lock.iter() {
-                    if let Err(e) = tx.send(Message::Text(json.clone())) {
-                        eprintln!("Failed to send message to client {}: {}", client_id, e);
-                    }
+                let snapshot = clients.read().await.clone();
+                let routing = Routing::of(&message);
+                let behind = fan_out(&snapshot, &routing, &Frames::new(&routing, json));
+                for (client_id, queue) in behind {
+                    handle_slow_client(&client_id, &queue, slow_client_policy, &callback_for_resync).await;
                 }
             }
-        });
+        };
 
         // Accept incoming connections
         let clients_for_accept = self.clients.clone();
-        let shutdown_for_accept = self.shutdown.clone();
+        let backpressure = self.backpressure;
+        let mut shutdown_for_accept = self.shutdown.subscribe();
+        let tasks_for_accept = self.tasks.clone();
         let callback_for_accept = self.initial_state_callback.clone();
-        let accept_task = tokio::spawn(async move {
+        let message_callback_for_accept = self.client_message_callback.clone();
+        let accept_task = async move {
             loop {
-                if shutdown_for_accept.load(Ordering::SeqCst) {
-                    break;
-                }
-                
-                match listener.accept().await {
+                let accepted = tokio::select! {
+                    _ = shutdown_for_accept.changed() => break,
+                    accepted = listener.accept() => accepted,
+                };
+
+                match accepted {
                     Ok((stream, addr)) => {
                         println!("New connection from: {}", addr);
-                        let clients = clients_for_accept.clone();
-                        let callback = callback_for_accept.clone();
-                        tokio::spawn(handle_connection(stream, addr.to_string(), clients, callback));
+                        let connection = handle_connection(
+                            stream,
+                            addr.to_string(),
+                            clients_for_accept.clone(),
+                            backpressure,
+                            shutdown_for_accept.clone(),
+                            callback_for_accept.clone(),
+                            message_callback_for_accept.clone(),
+                        );
+
+                        let mut tasks = tasks_for_accept.lock().await;
+                        while tasks.try_join_next().is_some() {}
+                        tasks.spawn(connection);
                     }
                     Err(e) => {
+                        // Accept errors concern the one connection or are
+                        // transient; the listener keeps running until `stop`
                         eprintln!("Failed to accept connection: {}", e);
-                        break;
+                        tokio::select! {
+                            _ = shutdown_for_accept.changed() => break,
+                            _ = tokio::time::sleep(ACCEPT_RETRY_DELAY) => {}
+                        }
                     }
                 }
             }
-        });
+            // The listener is dropped here, so the port can be bound again
+        };
 
-        // Store task handles
-        let mut tasks = self.tasks.write().await;
-        tasks.push(broadcast_task);
-        tasks.push(accept_task);
+        let mut tasks = self.tasks.lock().await;
+        tasks.spawn(broadcast_task);
+        tasks.spawn(accept_task);
 
         Ok(())
     }
 
+    /// Address actually listened on, including an auto-selected port
+    pub async fn local_addr(&self) -> Option<SocketAddr> {
+        *self.local_addr.read().await
+    }
+
     pub async fn get_connected_clients_count(&self) -> usize {
         self.clients.read().await.len()
     }
+
+    pub async fn get_client_ids(&self) -> Vec<ClientId> {
+        self.clients.read().await.keys().cloned().collect()
+    }
+
+    pub async fn get_queue_metrics(&self) -> Vec<ClientQueueMetrics> {
+        let mut metrics: Vec<ClientQueueMetrics> = self
+            .clients
+            .read()
+            .await
+            .iter()
+            .map(|(client_id, queue)| queue.metrics(client_id))
+            .collect();
+        metrics.sort_by(|a, b| a.client_id.cmp(&b.client_id));
+        metrics
+    }
+}
+
+/// Queue a serialized message for every client; returns the clients whose
+/// queue overflowed
+fn fan_out(clients: &ClientMap, routing: &Routing, frames: &Frames) -> Vec<(ClientId, Arc<ClientQueue>)> {
+    clients
+        .iter()
+        .filter(|(_, queue)| queue.push(routing, frames) == PushOutcome::Overflow)
+        .map(|(client_id, queue)| (client_id.clone(), queue.clone()))
+        .collect()
+}
+
+/// Apply the slow client policy to a slave whose queue overflowed
+async fn handle_slow_client(
+    client_id: &str,
+    queue: &Arc<ClientQueue>,
+    policy: SlowClientPolicy,
+    callback: &RwLock<Option<InitialStateCallback>>,
+) {
+    queue.clear();
+    let policy = match policy {
+        SlowClientPolicy::Resync if queue.note_resync() > MAX_CONSECUTIVE_RESYNCS => {
+            eprintln!("Client {} keeps falling behind after resyncs", client_id);
+            SlowClientPolicy::Disconnect
+        }
+        policy => policy,
+    };
+    match policy {
+        SlowClientPolicy::Resync => {
+            // The backlog is gone, so the slave needs a complete state again
+            eprintln!("Client {} fell behind, resyncing", client_id);
+            spawn_initial_state(client_id.to_string(), queue.clone(), callback.read().await.clone());
+        }
+        SlowClientPolicy::Disconnect => {
+            eprintln!("Client {} fell behind, disconnecting", client_id);
+            close_queue(queue, CloseCode::Again, "Send queue overflow");
+        }
+    }
+}
+
+/// Queue a Close frame ahead of anything left and stop accepting messages;
+/// the connection's writer ends once the frame is out
+fn close_queue(queue: &ClientQueue, code: CloseCode, reason: &'static str) {
+    queue.push_control(Message::Close(Some(CloseFrame {
+        code,
+        reason: reason.into(),
+    })));
+    queue.close();
+}
+
+/// Collect the complete state and queue it for this one slave; the others
+/// are up to date
+fn spawn_initial_state(client_id: ClientId, queue: Arc<ClientQueue>, callback: Option<InitialStateCallback>) {
+    let Some(cb) = callback else {
+        return;
+    };
+    tokio::spawn(async move {
+        let Some(state) = cb(client_id.clone()).await else {
+            return;
+        };
+        match serde_json::to_string(&state) {
+            Ok(json) => {
+                let routing = Routing::of(&state);
+                queue.push_state(&routing, &Frames::new(&routing, json.into()));
+            }
+            Err(e) => eprintln!("Failed to serialize initial state for {}: {}", client_id, e),
+        }
+    });
 }
 
 async fn handle_connection(
     stream: TcpStream,
     client_id: ClientId,
-    clients: Arc<RwLock<HashMap<ClientId, mpsc::UnboundedSender<Message>>>>,
+    clients: ClientRegistry,
+    backpressure: BackpressureConfig,
+    shutdown: watch::Receiver<bool>,
     callback: Arc<RwLock<Option<InitialStateCallback>>>,
+    message_callback: Arc<RwLock<Option<ClientMessageCallback>>>,
 ) {
     let ws_stream = match accept_async(stream).await {
         Ok(ws) => ws,
@@ -148,52 +335,130 @@ async fn handle_connection(
     };
 
     let (mut ws_sender, mut ws_receiver) = ws_stream.split();
-    let (tx, mut rx) = mpsc::unbounded_channel();
+    let queue = Arc::new(ClientQueue::new(backpressure));
 
     // Add client to the list
-    clients.write().await.insert(client_id.clone(), tx);
-    
-    println!("Client connected: {}", client_id);
-
-    // Call initial state callback for new client
-    let callback_lock = callback.read().await;
-    if let Some(cb) = callback_lock.as_ref() {
-        let client_id_clone = client_id.clone();
-        let future = cb(client_id_clone);
-        drop(callback_lock); // Release lock before awaiting
-        tokio::spawn(future);
+    Arc::make_mut(&mut *clients.write().await).insert(client_id.clone(), queue.clone());
+
+    // `stop` closes the queues it finds registered; one registered after
+    // that is closed here, so every slave that got through the handshake
+    // receives a Close frame
+    if *shutdown.borrow() {
+        close_queue(&queue, CloseCode::Away, "Master server stopped");
+    } else {
+        println!("Client connected: {}", client_id);
+        spawn_initial_state(client_id.clone(), queue.clone(), callback.read().await.clone());
         println!("Triggered initial state sync for client: {}", client_id);
     }
 
-    // Forward messages from tx to WebSocket
-    let send_task = tokio::spawn(async move {
-        while let Some(message) = rx.recv().await {
+    // Drain the queue into the WebSocket
+    let writer = std::pin::pin!(async {
+        while let Some(message) = queue.pop().await {
             if ws_sender.send(message).await.is_err() {
                 break;
             }
         }
     });
 
-    // Handle incoming messages from client (heartbeats, etc.)
-    while let Some(msg) = ws_receiver.next().await {
-        match msg {
-            Ok(Message::Close(_)) => break,
-            Ok(Message::Ping(data)) => {
-                // Send pong
-                if let Some(tx) = clients.read().await.get(&client_id) {
-                    let _ = tx.send(Message::Pong(data));
+    // Handle incoming messages from client (heartbeats, status reports, etc.)
+    let reader = std::pin::pin!(async {
+        while let Some(msg) = ws_receiver.next().await {
+            match msg {
+                Ok(Message::Text(text)) => {
+                    match serde_json::from_str::<SyncMessage>(&text) {
+                        Ok(sync_msg) => {
+                            if let Some(cb) = message_callback.read().await.as_ref() {
+                                tokio::spawn(cb(client_id.clone(), sync_msg));
+                            }
+                        }
+                        Err(e) => {
+                            eprintln!("Failed to parse message from {}: {}", client_id, e);
+                        }
+                    }
                 }
+                Ok(Message::Close(_)) => break,
+                Ok(Message::Ping(data)) => {
+                    // Send pong
+                    queue.push_control(Message::Pong(data));
+                }
+                Err(e) => {
+                    eprintln!("WebSocket error for {}: {}", client_id, e);
+                    break;
+                }
+                _ => {}
             }
-            Err(e) => {
-                eprintln!("WebSocket error for {}: {}", client_id, e);
-                break;
-            }
-            _ => {}
+        }
+    });
+
+    // Both halves run in this task. Once the writer has sent a Close frame,
+    // keep reading until the slave acknowledges it, or gives up on it.
+    if let Either::Left(((), reader)) = future::select(writer, reader).await {
+        if tokio::time::timeout(CLOSE_ACK_TIMEOUT, reader).await.is_err() {
+            eprintln!("Client {} did not acknowledge the Close frame", client_id);
         }
     }
 
     // Remove client from the list
-    clients.write().await.remove(&client_id);
-    send_task.abort();
+    Arc::make_mut(&mut *clients.write().await).remove(&client_id);
+    queue.close();
     println!("Client disconnected: {}", client_id);
 }
+
+#[cfg(test)]
+#[path = "fanout_bench.rs"]
+mod fanout_bench;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::protocol::{SyncMessageType, SyncTargetType};
+
+    #[tokio::test]
+    async fn disconnects_after_consecutive_resyncs() {
+        let queue = Arc::new(ClientQueue::new(BackpressureConfig::default()));
+        let callback = RwLock::new(None);
+        for _ in 0..MAX_CONSECUTIVE_RESYNCS {
+            handle_slow_client("slave", &queue, SlowClientPolicy::Resync, &callback).await;
+        }
+        let heartbeat = SyncMessage::new(
+            SyncMessageType::Heartbeat,
+            SyncTargetType::Program,
+            serde_json::json!({}),
+        );
+        let routing = Routing::of(&heartbeat);
+        let frames = Frames::new(&routing, serde_json::to_string(&heartbeat).unwrap().into());
+        assert_eq!(queue.push(&routing, &frames), PushOutcome::Queued);
+
+        handle_slow_client("slave", &queue, SlowClientPolicy::Resync, &callback).await;
+        match queue.pop().await {
+            Some(Message::Close(Some(frame))) => assert_eq!(frame.code, CloseCode::Again),
+            other => panic!("expected a close frame, got {:?}", other),
+        }
+        assert!(queue.pop().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn restarts_on_same_port_with_unresponsive_slave() {
+        // A port that is free right now; the server has to bind it twice
+        let port = TcpListener::bind("127.0.0.1:0").await.unwrap().local_addr().unwrap().port();
+        let server = MasterServer::new(IpAddr::from([127, 0, 0, 1]), port, BackpressureConfig::default());
+        server.start(mpsc::unbounded_channel().1).await.unwrap();
+
+        // Completes the handshake, then never reads, so never answers the Close frame
+        let (_slave, _) = tokio_tungstenite::connect_async(format!("ws://127.0.0.1:{}", port))
+            .await
+            .unwrap();
+        while server.get_connected_clients_count().await == 0 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        let started = tokio::time::Instant::now();
+        server.stop().await;
+        assert!(started.elapsed() < SHUTDOWN_TIMEOUT);
+        assert_eq!(server.get_connected_clients_count().await, 0);
+
+        server.start(mpsc::unbounded_channel().1).await.unwrap();
+        assert_eq!(server.local_addr().await.map(|addr| addr.port()), Some(port));
+        server.stop().await;
+    }
+}
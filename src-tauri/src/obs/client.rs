@@ -1,9 +1,16 @@
 use anyhow::{Context, Result};
+use obws::client::ConnectConfig;
+use obws::requests::EventSubscription;
 use obws::Client;
 use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
+/// Events obws buffers per subscriber. A subscriber that falls further
+/// behind loses its stream and has to subscribe again.
+const EVENT_BUFFER: usize = 1024;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OBSConnectionConfig {
     pub host: String,
@@ -12,17 +19,74 @@ pub struct OBSConnectionConfig {
     pub password: Option<String>,
 }
 
+/// Canvas (base) and output (scaled) resolution with the configured frame rate
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CanvasInfo {
+    pub base_width: u32,
+    pub base_height: u32,
+    pub output_width: u32,
+    pub output_height: u32,
+    pub fps_numerator: u32,
+    pub fps_denominator: u32,
+}
+
+impl CanvasInfo {
+    pub fn fps(&self) -> f64 {
+        if self.fps_denominator == 0 {
+            return 0.0;
+        }
+        f64::from(self.fps_numerator) / f64::from(self.fps_denominator)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct OBSConnectionStatus {
     pub connected: bool,
     pub obs_version: Option<String>,
+    #[serde(rename = "obsWebSocketVersion")]
     pub obs_websocket_version: Option<String>,
+    pub rpc_version: Option<u32>,
+    pub platform: Option<String>,
+    pub platform_description: Option<String>,
+    /// Request types the connected OBS WebSocket server accepts
+    #[serde(default)]
+    pub available_requests: Vec<String>,
+    /// Image formats accepted by screenshot requests
+    #[serde(default)]
+    pub supported_image_formats: Vec<String>,
+    pub canvas: Option<CanvasInfo>,
+}
+
+impl OBSConnectionStatus {
+    fn disconnected() -> Self {
+        Self {
+            connected: false,
+            obs_version: None,
+            obs_websocket_version: None,
+            rpc_version: None,
+            platform: None,
+            platform_description: None,
+            available_requests: Vec::new(),
+            supported_image_formats: Vec::new(),
+            canvas: None,
+        }
+    }
+
+    pub fn supports_request(&self, request_type: &str) -> bool {
+        self.available_requests.iter().any(|r| r == request_type)
+    }
 }
 
 #[derive(Clone)]
 pub struct OBSClient {
-    client: Arc<RwLock<Option<Client>>>,
+    /// Shared handle, so requests can run without holding the lock
+    client: Arc<RwLock<Option<Arc<Client>>>>,
     config: Arc<RwLock<Option<OBSConnectionConfig>>>,
+    /// Bumped on every connect, so data cached per connection can tell a
+    /// reconnect (possibly to a different OBS version) apart
+    connection_id: Arc<AtomicU64>,
 }
 
 impl OBSClient {
@@ -30,6 +94,7 @@ impl OBSClient {
         Self {
             client: Arc::new(RwLock::new(None)),
             config: Arc::new(RwLock::new(None)),
+            connection_id: Arc::new(AtomicU64::new(0)),
         }
     }
 
@@ -38,12 +103,20 @@ impl OBSClient {
         let port = config.port;
         let password = config.password.clone();
         
-        let client = Client::connect(host, port, password)
-            .await
-            .context("Failed to connect to OBS WebSocket")?;
+        // Transform changes are a high-volume event OBS only sends on request
+        let client = Client::connect_with_config(ConnectConfig {
+            host,
+            port,
+            password,
+            event_subscriptions: Some(EventSubscription::ALL | EventSubscription::SCENE_ITEM_TRANSFORM_CHANGED),
+            broadcast_capacity: Some(EVENT_BUFFER),
+        })
+        .await
+        .context("Failed to connect to OBS WebSocket")?;
 
-        *self.client.write().await = Some(client);
+        *self.client.write().await = Some(Arc::new(client));
         *self.config.write().await = Some(config);
+        self.connection_id.fetch_add(1, Ordering::Relaxed);
 
         Ok(())
     }
@@ -67,24 +140,50 @@ impl OBSClient {
         if let Some(client) = client_lock.as_ref() {
             // Try to get version info
             if let Ok(version) = client.general().version().await {
+                let canvas = client.config().video_settings().await.ok().map(|video| CanvasInfo {
+                    base_width: video.base_width,
+                    base_height: video.base_height,
+                    output_width: video.output_width,
+                    output_height: video.output_height,
+                    fps_numerator: video.fps_numerator,
+                    fps_denominator: video.fps_denominator,
+                });
+
                 return OBSConnectionStatus {
                     connected: true,
                     obs_version: Some(version.obs_version.to_string()),
                     obs_websocket_version: Some(version.obs_web_socket_version.to_string()),
+                    rpc_version: Some(version.rpc_version),
+                    platform: Some(version.platform),
+                    platform_description: Some(version.platform_description),
+                    available_requests: version.available_requests,
+                    supported_image_formats: version.supported_image_formats,
+                    canvas,
                 };
             }
         }
 
-        OBSConnectionStatus {
-            connected: false,
-            obs_version: None,
-            obs_websocket_version: None,
-        }
+        OBSConnectionStatus::disconnected()
+    }
+
+    pub async fn get_config(&self) -> Option<OBSConnectionConfig> {
+        self.config.read().await.clone()
     }
 
-    pub fn get_client_arc(&self) -> Arc<RwLock<Option<Client>>> {
+    /// Identifies the current connection; changes with every connect
+    pub fn connection_id(&self) -> u64 {
+        self.connection_id.load(Ordering::Relaxed)
+    }
+
+    pub fn get_client_arc(&self) -> Arc<RwLock<Option<Arc<Client>>>> {
         self.client.clone()
     }
+
+    /// Handle on the current connection, for work that spans many requests
+    /// and must not block a reconnect meanwhile
+    pub async fn client(&self) -> Option<Arc<Client>> {
+        self.client.read().await.clone()
+    }
 }
 
 impl Default for OBSClient {
@@ -1,7 +1,26 @@
 use anyhow::{Context, Result};
+use futures::stream::{self, StreamExt};
+use obws::common::{BoundsType, MediaAction, MonitorType};
+use obws::requests::hotkeys::KeyModifiers;
+use obws::requests::sources::TakeScreenshot;
+use obws::responses::media_inputs::MediaState;
+use obws::responses::scene_items::{SceneItem, SourceType};
 use obws::Client;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::future::Future;
+
+/// Requests kept in flight while collecting state. obws matches responses by
+/// request id, so requests on one connection can overlap.
+pub const STATE_REQUEST_CONCURRENCY: usize = 16;
+
+/// Run `requests` with `STATE_REQUEST_CONCURRENCY` in flight; results keep
+/// the order of the requests. The futures are collected up front, so the
+/// returned future stays `Send` when they borrow from the caller.
+pub fn buffered_requests<F: Future>(requests: impl IntoIterator<Item = F>) -> impl Future<Output = Vec<F::Output>> {
+    let requests: Vec<F> = requests.into_iter().collect();
+    stream::iter(requests).buffered(STATE_REQUEST_CONCURRENCY).collect()
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SceneItemTransform {
@@ -10,8 +29,46 @@ pub struct SceneItemTransform {
     pub rotation: f64,
     pub scale_x: f64,
     pub scale_y: f64,
+    /// Rendered size; read-only in OBS
     pub width: f64,
     pub height: f64,
+    /// Crop and bounds are missing from transforms sent by older masters and
+    /// are left unchanged then
+    #[serde(default)]
+    pub crop_left: Option<u32>,
+    #[serde(default)]
+    pub crop_right: Option<u32>,
+    #[serde(default)]
+    pub crop_top: Option<u32>,
+    #[serde(default)]
+    pub crop_bottom: Option<u32>,
+    #[serde(default)]
+    pub bounds_type: Option<BoundsType>,
+    #[serde(default)]
+    pub bounds_width: Option<f64>,
+    #[serde(default)]
+    pub bounds_height: Option<f64>,
+}
+
+impl From<&obws::responses::scene_items::SceneItemTransform> for SceneItemTransform {
+    fn from(t: &obws::responses::scene_items::SceneItemTransform) -> Self {
+        Self {
+            position_x: t.position_x.into(),
+            position_y: t.position_y.into(),
+            rotation: t.rotation.into(),
+            scale_x: t.scale_x.into(),
+            scale_y: t.scale_y.into(),
+            width: t.width.into(),
+            height: t.height.into(),
+            crop_left: Some(t.crop_left),
+            crop_right: Some(t.crop_right),
+            crop_top: Some(t.crop_top),
+            crop_bottom: Some(t.crop_bottom),
+            bounds_type: Some(t.bounds_type),
+            bounds_width: Some(t.bounds_width.into()),
+            bounds_height: Some(t.bounds_height.into()),
+        }
+    }
 }
 
 pub struct OBSCommands;
@@ -42,30 +99,831 @@ impl OBSCommands {
         Ok(())
     }
 
-    #[allow(dead_code)]
+    pub async fn set_current_preview_scene(client: &Client, scene_name: &str) -> Result<()> {
+        client
+            .scenes()
+            .set_current_preview_scene(scene_name)
+            .await
+            .context("Failed to set current preview scene")?;
+        Ok(())
+    }
+
+    pub async fn get_scene_names(client: &Client) -> Result<Vec<String>> {
+        let scenes = client
+            .scenes()
+            .list()
+            .await
+            .context("Failed to get scene list")?;
+        Ok(scenes.scenes.into_iter().map(|scene| scene.name).collect())
+    }
+
+    pub async fn create_scene(client: &Client, scene_name: &str) -> Result<()> {
+        client
+            .scenes()
+            .create(scene_name)
+            .await
+            .context(format!("Failed to create scene {}", scene_name))?;
+        Ok(())
+    }
+
+    pub async fn remove_scene(client: &Client, scene_name: &str) -> Result<()> {
+        client
+            .scenes()
+            .remove(scene_name)
+            .await
+            .context(format!("Failed to remove scene {}", scene_name))?;
+        Ok(())
+    }
+
+    pub async fn rename_scene(client: &Client, scene_name: &str, new_scene_name: &str) -> Result<()> {
+        client
+            .scenes()
+            .set_name(scene_name, new_scene_name)
+            .await
+            .context(format!("Failed to rename scene {}", scene_name))?;
+        Ok(())
+    }
+
+    /// Get the current scene collection and all available ones
+    pub async fn get_scene_collections(client: &Client) -> Result<(String, Vec<String>)> {
+        let collections = client
+            .scene_collections()
+            .list()
+            .await
+            .context("Failed to get scene collections")?;
+        Ok((collections.current, collections.collections))
+    }
+
+    pub async fn set_current_scene_collection(client: &Client, collection_name: &str) -> Result<()> {
+        client
+            .scene_collections()
+            .set_current(collection_name)
+            .await
+            .context(format!("Failed to switch scene collection to {}", collection_name))?;
+        Ok(())
+    }
+
+    /// Get the current profile and all available ones
+    pub async fn get_profiles(client: &Client) -> Result<(String, Vec<String>)> {
+        let profiles = client
+            .profiles()
+            .list()
+            .await
+            .context("Failed to get profiles")?;
+        Ok((profiles.current, profiles.profiles))
+    }
+
+    pub async fn set_current_profile(client: &Client, profile_name: &str) -> Result<()> {
+        client
+            .profiles()
+            .set_current(profile_name)
+            .await
+            .context(format!("Failed to switch profile to {}", profile_name))?;
+        Ok(())
+    }
+
+    pub async fn get_studio_mode_enabled(client: &Client) -> Result<bool> {
+        let enabled = client
+            .ui()
+            .studio_mode_enabled()
+            .await
+            .context("Failed to get studio mode state")?;
+        Ok(enabled)
+    }
+
+    pub async fn set_studio_mode_enabled(client: &Client, enabled: bool) -> Result<()> {
+        client
+            .ui()
+            .set_studio_mode_enabled(enabled)
+            .await
+            .context("Failed to set studio mode state")?;
+        Ok(())
+    }
+
+    /// Get the current scene transition as `{ name, kind, fixed, duration_ms, settings }`
+    pub async fn get_current_transition(client: &Client) -> Result<Value> {
+        let transition = client
+            .transitions()
+            .current()
+            .await
+            .context("Failed to get current scene transition")?;
+        Ok(serde_json::json!({
+            "name": transition.name,
+            "kind": transition.kind,
+            "fixed": transition.fixed,
+            "duration_ms": transition.duration.map(|d| d.whole_milliseconds() as i64),
+            "settings": transition.settings,
+        }))
+    }
+
+    pub async fn set_current_transition(client: &Client, transition_name: &str) -> Result<()> {
+        client
+            .transitions()
+            .set_current(transition_name)
+            .await
+            .context(format!("Failed to set scene transition {}", transition_name))?;
+        Ok(())
+    }
+
+    pub async fn set_current_transition_duration(client: &Client, duration_ms: i64) -> Result<()> {
+        client
+            .transitions()
+            .set_current_duration(time::Duration::milliseconds(duration_ms))
+            .await
+            .context("Failed to set scene transition duration")?;
+        Ok(())
+    }
+
+    pub async fn set_current_transition_settings(client: &Client, settings: &Value) -> Result<()> {
+        client
+            .transitions()
+            .set_current_settings(settings, Some(true))
+            .await
+            .context("Failed to set scene transition settings")?;
+        Ok(())
+    }
+
+    /// Transition preview to program, like the studio mode `Transition` button
+    pub async fn trigger_studio_mode_transition(client: &Client) -> Result<()> {
+        client
+            .transitions()
+            .trigger()
+            .await
+            .context("Failed to trigger studio mode transition")?;
+        Ok(())
+    }
+
     pub async fn set_scene_item_transform(
-        _client: &Client,
-        _scene_name: &str,
-        _scene_item_id: i64,
-        _transform: SceneItemTransform,
+        client: &Client,
+        scene_name: &str,
+        scene_item_id: i64,
+        transform: &SceneItemTransform,
+    ) -> Result<()> {
+        use obws::requests::scene_items::{self, Bounds, Crop, Position, Scale};
+
+        let crop = [transform.crop_left, transform.crop_right, transform.crop_top, transform.crop_bottom];
+        let bounds = [transform.bounds_width, transform.bounds_height];
+        client
+            .scene_items()
+            .set_transform(scene_items::SetTransform {
+                scene: scene_name,
+                item_id: scene_item_id,
+                transform: scene_items::SceneItemTransform {
+                    position: Some(Position {
+                        x: Some(transform.position_x as f32),
+                        y: Some(transform.position_y as f32),
+                    }),
+                    rotation: Some(transform.rotation as f32),
+                    scale: Some(Scale {
+                        x: Some(transform.scale_x as f32),
+                        y: Some(transform.scale_y as f32),
+                    }),
+                    crop: crop.iter().any(Option::is_some).then_some(Crop {
+                        left: transform.crop_left,
+                        right: transform.crop_right,
+                        top: transform.crop_top,
+                        bottom: transform.crop_bottom,
+                    }),
+                    bounds: (transform.bounds_type.is_some() || bounds.iter().any(Option::is_some)).then_some(Bounds {
+                        r#type: transform.bounds_type,
+                        width: transform.bounds_width.map(|w| w as f32),
+                        height: transform.bounds_height.map(|h| h as f32),
+                        alignment: None,
+                    }),
+                    alignment: None,
+                },
+            })
+            .await
+            .context("Failed to set scene item transform")?;
+        Ok(())
+    }
+
+    pub async fn get_scene_item_enabled(client: &Client, scene_name: &str, scene_item_id: i64) -> Result<bool> {
+        let enabled = client
+            .scene_items()
+            .enabled(scene_name, scene_item_id)
+            .await
+            .context("Failed to get scene item enable state")?;
+        Ok(enabled)
+    }
+
+    pub async fn set_scene_item_enabled(
+        client: &Client,
+        scene_name: &str,
+        scene_item_id: i64,
+        enabled: bool,
+    ) -> Result<()> {
+        client
+            .scene_items()
+            .set_enabled(obws::requests::scene_items::SetEnabled {
+                scene: scene_name,
+                item_id: scene_item_id,
+                enabled,
+            })
+            .await
+            .context("Failed to set scene item enable state")?;
+        Ok(())
+    }
+
+    pub async fn get_scene_item_locked(client: &Client, scene_name: &str, scene_item_id: i64) -> Result<bool> {
+        let locked = client
+            .scene_items()
+            .locked(scene_name, scene_item_id)
+            .await
+            .context("Failed to get scene item lock state")?;
+        Ok(locked)
+    }
+
+    pub async fn set_scene_item_locked(
+        client: &Client,
+        scene_name: &str,
+        scene_item_id: i64,
+        locked: bool,
+    ) -> Result<()> {
+        client
+            .scene_items()
+            .set_locked(obws::requests::scene_items::SetLocked {
+                scene: scene_name,
+                item_id: scene_item_id,
+                locked,
+            })
+            .await
+            .context("Failed to set scene item lock state")?;
+        Ok(())
+    }
+
+    pub async fn set_scene_item_index(
+        client: &Client,
+        scene_name: &str,
+        scene_item_id: i64,
+        index: u32,
+    ) -> Result<()> {
+        client
+            .scene_items()
+            .set_index(obws::requests::scene_items::SetIndex {
+                scene: scene_name,
+                item_id: scene_item_id,
+                index,
+            })
+            .await
+            .context("Failed to set scene item index")?;
+        Ok(())
+    }
+
+    /// Get the filter list of a source as `{ name, kind, index, enabled, settings }` objects
+    pub async fn get_source_filters(client: &Client, source_name: &str) -> Result<Vec<Value>> {
+        let filters = client
+            .filters()
+            .list(source_name)
+            .await
+            .context(format!("Failed to get filters for {}", source_name))?;
+        Ok(filters
+            .into_iter()
+            .map(|f| serde_json::json!({
+                "name": f.name,
+                "kind": f.kind,
+                "index": f.index,
+                "enabled": f.enabled,
+                "settings": f.settings,
+            }))
+            .collect())
+    }
+
+    pub async fn get_source_filter_settings(
+        client: &Client,
+        source_name: &str,
+        filter_name: &str,
+    ) -> Result<Value> {
+        let filter = client
+            .filters()
+            .get(source_name, filter_name)
+            .await
+            .context(format!("Failed to get filter {} on {}", filter_name, source_name))?;
+        Ok(filter.settings)
+    }
+
+    pub async fn create_source_filter(
+        client: &Client,
+        source_name: &str,
+        filter_name: &str,
+        filter_kind: &str,
+        settings: &Value,
+    ) -> Result<()> {
+        client
+            .filters()
+            .create(obws::requests::filters::Create {
+                source: source_name,
+                filter: filter_name,
+                kind: filter_kind,
+                settings: Some(settings),
+            })
+            .await
+            .context(format!("Failed to create filter {} on {}", filter_name, source_name))?;
+        Ok(())
+    }
+
+    pub async fn remove_source_filter(client: &Client, source_name: &str, filter_name: &str) -> Result<()> {
+        client
+            .filters()
+            .remove(source_name, filter_name)
+            .await
+            .context(format!("Failed to remove filter {} from {}", filter_name, source_name))?;
+        Ok(())
+    }
+
+    pub async fn rename_source_filter(
+        client: &Client,
+        source_name: &str,
+        filter_name: &str,
+        new_name: &str,
+    ) -> Result<()> {
+        client
+            .filters()
+            .set_name(obws::requests::filters::SetName {
+                source: source_name,
+                filter: filter_name,
+                new_name,
+            })
+            .await
+            .context(format!("Failed to rename filter {} on {}", filter_name, source_name))?;
+        Ok(())
+    }
+
+    pub async fn set_source_filter_index(
+        client: &Client,
+        source_name: &str,
+        filter_name: &str,
+        index: u32,
     ) -> Result<()> {
-        // Transform setting would be implemented based on the actual obws API
+        client
+            .filters()
+            .set_index(obws::requests::filters::SetIndex {
+                source: source_name,
+                filter: filter_name,
+                index,
+            })
+            .await
+            .context(format!("Failed to move filter {} on {}", filter_name, source_name))?;
+        Ok(())
+    }
+
+    pub async fn set_source_filter_settings(
+        client: &Client,
+        source_name: &str,
+        filter_name: &str,
+        settings: &Value,
+    ) -> Result<()> {
+        client
+            .filters()
+            .set_settings(obws::requests::filters::SetSettings {
+                source: source_name,
+                filter: filter_name,
+                settings,
+                overlay: Some(true),
+            })
+            .await
+            .context(format!("Failed to set settings of filter {} on {}", filter_name, source_name))?;
+        Ok(())
+    }
+
+    pub async fn set_source_filter_enabled(
+        client: &Client,
+        source_name: &str,
+        filter_name: &str,
+        enabled: bool,
+    ) -> Result<()> {
+        client
+            .filters()
+            .set_enabled(obws::requests::filters::SetEnabled {
+                source: source_name,
+                filter: filter_name,
+                enabled,
+            })
+            .await
+            .context(format!("Failed to toggle filter {} on {}", filter_name, source_name))?;
+        Ok(())
+    }
+
+    pub fn monitor_type_to_str(monitor_type: MonitorType) -> &'static str {
+        match monitor_type {
+            MonitorType::None => "none",
+            MonitorType::MonitorOnly => "monitor_only",
+            MonitorType::MonitorAndOutput => "monitor_and_output",
+            _ => "none",
+        }
+    }
+
+    pub fn monitor_type_from_str(monitor_type: &str) -> Option<MonitorType> {
+        match monitor_type {
+            "none" => Some(MonitorType::None),
+            "monitor_only" => Some(MonitorType::MonitorOnly),
+            "monitor_and_output" => Some(MonitorType::MonitorAndOutput),
+            _ => None,
+        }
+    }
+
+    /// Get the mixer state of an input. Fails for inputs without audio.
+    pub async fn get_input_audio_state(client: &Client, input_name: &str) -> Result<Value> {
+        let inputs = client.inputs();
+        let volume = inputs
+            .volume(input_name)
+            .await
+            .context(format!("Failed to get volume of {}", input_name))?;
+        let muted = inputs
+            .muted(input_name)
+            .await
+            .context(format!("Failed to get mute state of {}", input_name))?;
+        let balance = inputs
+            .audio_balance(input_name)
+            .await
+            .context(format!("Failed to get audio balance of {}", input_name))?;
+        let sync_offset = inputs
+            .audio_sync_offset(input_name)
+            .await
+            .context(format!("Failed to get sync offset of {}", input_name))?;
+        let monitor_type = inputs
+            .audio_monitor_type(input_name)
+            .await
+            .context(format!("Failed to get monitor type of {}", input_name))?;
+        let tracks = inputs
+            .audio_tracks(input_name)
+            .await
+            .context(format!("Failed to get audio tracks of {}", input_name))?;
+
+        Ok(serde_json::json!({
+            "volume_db": volume.db,
+            "muted": muted,
+            "balance": balance,
+            "sync_offset_ms": sync_offset.whole_milliseconds() as i64,
+            "monitor_type": Self::monitor_type_to_str(monitor_type),
+            "tracks": tracks.to_vec(),
+        }))
+    }
+
+    pub async fn set_input_volume_db(client: &Client, input_name: &str, volume_db: f64) -> Result<()> {
+        client
+            .inputs()
+            .set_volume(input_name, obws::requests::inputs::Volume::Db(volume_db as f32))
+            .await
+            .context(format!("Failed to set volume of {}", input_name))?;
+        Ok(())
+    }
+
+    pub async fn set_input_muted(client: &Client, input_name: &str, muted: bool) -> Result<()> {
+        client
+            .inputs()
+            .set_muted(input_name, muted)
+            .await
+            .context(format!("Failed to set mute state of {}", input_name))?;
+        Ok(())
+    }
+
+    pub async fn set_input_audio_balance(client: &Client, input_name: &str, balance: f64) -> Result<()> {
+        client
+            .inputs()
+            .set_audio_balance(input_name, balance as f32)
+            .await
+            .context(format!("Failed to set audio balance of {}", input_name))?;
+        Ok(())
+    }
+
+    pub async fn set_input_audio_sync_offset(client: &Client, input_name: &str, offset_ms: i64) -> Result<()> {
+        client
+            .inputs()
+            .set_audio_sync_offset(input_name, time::Duration::milliseconds(offset_ms))
+            .await
+            .context(format!("Failed to set sync offset of {}", input_name))?;
+        Ok(())
+    }
+
+    pub async fn set_input_audio_monitor_type(client: &Client, input_name: &str, monitor_type: MonitorType) -> Result<()> {
+        client
+            .inputs()
+            .set_audio_monitor_type(input_name, monitor_type)
+            .await
+            .context(format!("Failed to set monitor type of {}", input_name))?;
+        Ok(())
+    }
+
+    pub async fn set_input_audio_tracks(client: &Client, input_name: &str, tracks: &[bool]) -> Result<()> {
+        let mut routing = [None; 6];
+        for (slot, enabled) in routing.iter_mut().zip(tracks) {
+            *slot = Some(*enabled);
+        }
+        client
+            .inputs()
+            .set_audio_tracks(input_name, routing)
+            .await
+            .context(format!("Failed to set audio tracks of {}", input_name))?;
+        Ok(())
+    }
+
+    pub fn media_state_to_str(state: MediaState) -> &'static str {
+        match state {
+            MediaState::Playing => "playing",
+            MediaState::Opening => "opening",
+            MediaState::Buffering => "buffering",
+            MediaState::Paused => "paused",
+            MediaState::Stopped => "stopped",
+            MediaState::Ended => "ended",
+            MediaState::Error => "error",
+            _ => "none",
+        }
+    }
+
+    pub fn media_action_to_str(action: MediaAction) -> Option<&'static str> {
+        match action {
+            MediaAction::Play => Some("play"),
+            MediaAction::Pause => Some("pause"),
+            MediaAction::Stop => Some("stop"),
+            MediaAction::Restart => Some("restart"),
+            MediaAction::Next => Some("next"),
+            MediaAction::Previous => Some("previous"),
+            _ => None,
+        }
+    }
+
+    pub fn media_action_from_str(action: &str) -> Option<MediaAction> {
+        match action {
+            "play" => Some(MediaAction::Play),
+            "pause" => Some(MediaAction::Pause),
+            "stop" => Some(MediaAction::Stop),
+            "restart" => Some(MediaAction::Restart),
+            "next" => Some(MediaAction::Next),
+            "previous" => Some(MediaAction::Previous),
+            _ => None,
+        }
+    }
+
+    /// Get the playback state of a media input. Fails for inputs that are not media.
+    pub async fn get_media_input_status(client: &Client, input_name: &str) -> Result<Value> {
+        let status = client
+            .media_inputs()
+            .status(input_name)
+            .await
+            .context(format!("Failed to get media status of {}", input_name))?;
+
+        Ok(serde_json::json!({
+            "state": Self::media_state_to_str(status.state),
+            "cursor_ms": status.cursor.map(|c| c.whole_milliseconds() as i64),
+            "duration_ms": status.duration.map(|d| d.whole_milliseconds() as i64),
+            "captured_at": chrono::Utc::now().timestamp_millis(),
+        }))
+    }
+
+    pub async fn set_media_input_cursor(client: &Client, input_name: &str, cursor_ms: i64) -> Result<()> {
+        client
+            .media_inputs()
+            .set_cursor(input_name, time::Duration::milliseconds(cursor_ms))
+            .await
+            .context(format!("Failed to seek media {}", input_name))?;
+        Ok(())
+    }
+
+    pub async fn trigger_media_input_action(client: &Client, input_name: &str, action: MediaAction) -> Result<()> {
+        client
+            .media_inputs()
+            .trigger_action(input_name, action)
+            .await
+            .context(format!("Failed to trigger media action on {}", input_name))?;
+        Ok(())
+    }
+
+    pub async fn get_input_names(client: &Client) -> Result<Vec<String>> {
+        let inputs = client
+            .inputs()
+            .list(None)
+            .await
+            .context("Failed to get input list")?;
+        Ok(inputs.into_iter().map(|input| input.name).collect())
+    }
+
+    /// Create an input and add it to `scene_name`, returning the new scene item id
+    pub async fn create_input(
+        client: &Client,
+        scene_name: &str,
+        input_name: &str,
+        input_kind: &str,
+        settings: &Value,
+        enabled: Option<bool>,
+    ) -> Result<i64> {
+        let scene_item_id = client
+            .inputs()
+            .create(obws::requests::inputs::Create {
+                scene: scene_name,
+                input: input_name,
+                kind: input_kind,
+                settings: Some(settings),
+                enabled,
+            })
+            .await
+            .context(format!("Failed to create input {}", input_name))?;
+        Ok(scene_item_id)
+    }
+
+    /// Add an existing input or scene to `scene_name`, returning the new scene item id
+    pub async fn create_scene_item(
+        client: &Client,
+        scene_name: &str,
+        source_name: &str,
+        enabled: Option<bool>,
+    ) -> Result<i64> {
+        let scene_item_id = client
+            .scene_items()
+            .create(obws::requests::scene_items::CreateSceneItem {
+                scene: scene_name,
+                source: source_name,
+                enabled,
+            })
+            .await
+            .context(format!("Failed to add {} to scene {}", source_name, scene_name))?;
+        Ok(scene_item_id)
+    }
+
+    /// Active state of every output as `{ stream, record, replay_buffer, virtualcam }`.
+    /// Outputs that cannot be queried (e.g. replay buffer disabled) are `null`.
+    pub async fn get_output_status(client: &Client) -> Result<Value> {
+        let stream = client
+            .streaming()
+            .status()
+            .await
+            .context("Failed to get stream status")?;
+        let record = client
+            .recording()
+            .status()
+            .await
+            .context("Failed to get record status")?;
+        let replay_buffer = client.replay_buffer().status().await.ok();
+        let virtualcam = client.virtual_cam().status().await.ok();
+
+        Ok(serde_json::json!({
+            "stream": { "active": stream.active },
+            "record": { "active": record.active, "paused": record.paused },
+            "replay_buffer": replay_buffer.map(|active| serde_json::json!({ "active": active })),
+            "virtualcam": virtualcam.map(|active| serde_json::json!({ "active": active })),
+        }))
+    }
+
+    /// Start or stop an output by its sync name (`stream`, `record`, `replay_buffer`, `virtualcam`)
+    pub async fn set_output_active(client: &Client, output: &str, active: bool) -> Result<()> {
+        let result = match (output, active) {
+            ("stream", true) => client.streaming().start().await,
+            ("stream", false) => client.streaming().stop().await,
+            ("record", true) => client.recording().start().await,
+            ("record", false) => client.recording().stop().await.map(|_| ()),
+            ("replay_buffer", true) => client.replay_buffer().start().await,
+            ("replay_buffer", false) => client.replay_buffer().stop().await,
+            ("virtualcam", true) => client.virtual_cam().start().await,
+            ("virtualcam", false) => client.virtual_cam().stop().await,
+            _ => return Err(anyhow::anyhow!("Unknown output '{}'", output)),
+        };
+        result.context(format!(
+            "Failed to {} {}",
+            if active { "start" } else { "stop" },
+            output
+        ))?;
+        Ok(())
+    }
+
+    pub async fn set_record_paused(client: &Client, paused: bool) -> Result<()> {
+        if paused {
+            client.recording().pause().await
+        } else {
+            client.recording().resume().await
+        }
+        .context(format!("Failed to {} recording", if paused { "pause" } else { "resume" }))?;
+        Ok(())
+    }
+
+    pub async fn save_replay_buffer(client: &Client) -> Result<()> {
+        client
+            .replay_buffer()
+            .save()
+            .await
+            .context("Failed to save replay buffer")?;
+        Ok(())
+    }
+
+    pub async fn get_hotkey_names(client: &Client) -> Result<Vec<String>> {
+        let hotkeys = client
+            .hotkeys()
+            .list()
+            .await
+            .context("Failed to get hotkey list")?;
+        Ok(hotkeys)
+    }
+
+    pub async fn trigger_hotkey_by_name(client: &Client, hotkey_name: &str) -> Result<()> {
+        client
+            .hotkeys()
+            .trigger_by_name(hotkey_name)
+            .await
+            .context(format!("Failed to trigger hotkey: {}", hotkey_name))?;
+        Ok(())
+    }
+
+    pub async fn trigger_hotkey_by_sequence(
+        client: &Client,
+        key_id: &str,
+        shift: bool,
+        control: bool,
+        alt: bool,
+        command: bool,
+    ) -> Result<()> {
+        client
+            .hotkeys()
+            .trigger_by_sequence(key_id, KeyModifiers { shift, control, alt, command })
+            .await
+            .context(format!("Failed to trigger key sequence: {}", key_id))?;
         Ok(())
     }
 
-    #[allow(dead_code)]
-    pub async fn get_scene_item_list(_client: &Client, _scene_name: &str) -> Result<Vec<Value>> {
-        // Scene item list retrieval would be implemented based on the actual obws API
-        Ok(vec![])
+    /// Kind of source behind a scene item: the input kind, or `scene` / `group`
+    pub fn scene_item_kind(item: &SceneItem) -> String {
+        if item.is_group == Some(true) {
+            "group".to_string()
+        } else if matches!(item.source_type, SourceType::Scene) {
+            "scene".to_string()
+        } else {
+            item.input_kind.clone().unwrap_or_else(|| "unknown".to_string())
+        }
     }
 
-    #[allow(dead_code)]
+    /// List the items of a scene or group as `{ scene_item_id, source_name, source_type, index }`
+    pub async fn get_scene_item_list(client: &Client, scene_name: &str) -> Result<Vec<Value>> {
+        let items = match client.scene_items().list(scene_name).await {
+            Ok(items) => items,
+            // Groups are only listed by GetGroupSceneItemList
+            Err(_) => client
+                .scene_items()
+                .list_group(scene_name)
+                .await
+                .context(format!("Failed to get items of {}", scene_name))?,
+        };
+
+        Ok(items
+            .iter()
+            .map(|item| serde_json::json!({
+                "scene_item_id": item.id,
+                "source_name": item.source_name,
+                "source_type": Self::scene_item_kind(item),
+                "index": item.index,
+            }))
+            .collect())
+    }
+
+    /// Render a source (or scene) to an image and return the encoded bytes
+    pub async fn get_source_screenshot(
+        client: &Client,
+        source_name: &str,
+        format: &str,
+        width: u32,
+        height: u32,
+    ) -> Result<Vec<u8>> {
+        let data_uri = client
+            .sources()
+            .take_screenshot(TakeScreenshot {
+                source: source_name,
+                format,
+                width: Some(width),
+                height: Some(height),
+                compression_quality: None,
+            })
+            .await
+            .context(format!("Failed to take screenshot of {}", source_name))?;
+
+        // `data:image/<format>;base64,<data>`
+        let encoded = data_uri.split_once(',').map(|(_, data)| data).unwrap_or(&data_uri);
+        let data = base64::Engine::decode(&base64::engine::general_purpose::STANDARD, encoded)
+            .context("Failed to decode screenshot data")?;
+        Ok(data)
+    }
+
+    /// Get the input kind and full settings object of an input
+    pub async fn get_input_settings(client: &Client, input_name: &str) -> Result<(String, Value)> {
+        let settings = client
+            .inputs()
+            .settings::<Value>(input_name)
+            .await
+            .context(format!("Failed to get settings for input {}", input_name))?;
+        Ok((settings.kind, settings.settings))
+    }
+
+    /// Apply settings on top of the input's current settings (overlay)
     pub async fn set_input_settings(
-        _client: &Client,
-        _input_name: &str,
-        _settings: &Value,
+        client: &Client,
+        input_name: &str,
+        settings: &Value,
     ) -> Result<()> {
-        // Input settings would be implemented based on the actual obws API
+        client
+            .inputs()
+            .set_settings(obws::requests::inputs::SetSettings {
+                input: input_name,
+                settings,
+                overlay: Some(true),
+            })
+            .await
+            .context(format!("Failed to set settings for input {}", input_name))?;
         Ok(())
     }
 }
@@ -1,48 +1,394 @@
-use obws::Client;
+use super::client::{OBSClient, OBSConnectionConfig};
+use super::commands::OBSCommands;
+use anyhow::{bail, Context, Result};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use futures::{SinkExt, Stream, StreamExt};
+use obws::events::{Event, OutputState};
+use obws::requests::EventSubscription;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::net::TcpStream;
 use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
+
+type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// Wait before subscribing again while OBS is unreachable
+const RESUBSCRIBE_DELAY: Duration = Duration::from_secs(1);
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", content = "payload")]
 pub enum OBSEvent {
     SceneChanged { scene_name: String },
+    SceneCreated { scene_name: String, is_group: bool },
+    SceneRemoved { scene_name: String, is_group: bool },
+    SceneNameChanged { old_scene_name: String, scene_name: String },
     SceneItemTransformChanged { scene_name: String, scene_item_id: i64 },
+    SceneItemCreated { scene_name: String, source_name: String, scene_item_id: i64, scene_item_index: u32 },
+    SceneItemEnableStateChanged { scene_name: String, scene_item_id: i64, enabled: bool },
+    SceneItemLockStateChanged { scene_name: String, scene_item_id: i64, locked: bool },
+    SceneItemListReindexed { scene_name: String },
     SourceCreated { source_name: String },
     SourceDestroyed { source_name: String },
-    InputSettingsChanged { input_name: String },
+    /// `settings` carries the full settings object when the event provides it,
+    /// letting the master skip a `GetInputSettings` round trip
+    InputSettingsChanged { input_name: String, settings: Option<serde_json::Value> },
     CurrentPreviewSceneChanged { scene_name: String },
+    CurrentSceneCollectionChanged { scene_collection_name: String },
+    CurrentProfileChanged { profile_name: String },
+    StudioModeStateChanged { enabled: bool },
+    CurrentSceneTransitionChanged { transition_name: String },
+    CurrentSceneTransitionDurationChanged { duration_ms: i64 },
+    SourceFilterCreated {
+        source_name: String,
+        filter_name: String,
+        filter_kind: String,
+        filter_index: u32,
+        filter_settings: serde_json::Value,
+    },
+    SourceFilterRemoved { source_name: String, filter_name: String },
+    SourceFilterNameChanged { source_name: String, old_filter_name: String, filter_name: String },
+    SourceFilterListReindexed { source_name: String },
+    SourceFilterSettingsChanged { source_name: String, filter_name: String, settings: Option<serde_json::Value> },
+    SourceFilterEnableStateChanged { source_name: String, filter_name: String, enabled: bool },
+    InputVolumeChanged { input_name: String, volume_db: f64 },
+    InputMuteStateChanged { input_name: String, muted: bool },
+    InputAudioBalanceChanged { input_name: String, balance: f64 },
+    InputAudioSyncOffsetChanged { input_name: String, sync_offset_ms: i64 },
+    /// `monitor_type` is one of `none`, `monitor_only`, `monitor_and_output`
+    InputAudioMonitorTypeChanged { input_name: String, monitor_type: String },
+    /// Track routing for tracks 1-6
+    InputAudioTracksChanged { input_name: String, tracks: Vec<bool> },
+    /// `output_state` is one of `starting`, `started`, `stopping`, `stopped`,
+    /// `paused`, `resumed`, `reconnecting`, `reconnected`
+    StreamStateChanged { output_active: bool, output_state: String },
+    RecordStateChanged { output_active: bool, output_state: String },
+    ReplayBufferStateChanged { output_active: bool, output_state: String },
+    VirtualcamStateChanged { output_active: bool, output_state: String },
+    ReplayBufferSaved { saved_replay_path: String },
+    MediaInputPlaybackStarted { input_name: String },
+    MediaInputPlaybackEnded { input_name: String },
+    /// `media_action` is one of `play`, `pause`, `stop`, `restart`, `next`, `previous`
+    MediaInputActionTriggered { input_name: String, media_action: String },
+    /// Sent by any WebSocket client through `BroadcastCustomEvent`
+    CustomEvent { event_data: Value },
+}
+
+impl OBSEvent {
+    /// The sync-relevant part of an obws event
+    fn from_obws(event: Event) -> Option<Self> {
+        let event = match event {
+            Event::CurrentProgramSceneChanged { name } => Self::SceneChanged { scene_name: name },
+            Event::SceneCreated { name, is_group } => Self::SceneCreated { scene_name: name, is_group },
+            Event::SceneRemoved { name, is_group } => Self::SceneRemoved { scene_name: name, is_group },
+            Event::SceneNameChanged { old_name, new_name } => Self::SceneNameChanged {
+                old_scene_name: old_name,
+                scene_name: new_name,
+            },
+            Event::SceneItemTransformChanged { scene, item_id, .. } => Self::SceneItemTransformChanged {
+                scene_name: scene,
+                scene_item_id: item_id as i64,
+            },
+            Event::SceneItemCreated {
+                scene,
+                source,
+                item_id,
+                index,
+            } => Self::SceneItemCreated {
+                scene_name: scene,
+                source_name: source,
+                scene_item_id: item_id as i64,
+                scene_item_index: index,
+            },
+            Event::SceneItemEnableStateChanged { scene, item_id, enabled } => Self::SceneItemEnableStateChanged {
+                scene_name: scene,
+                scene_item_id: item_id as i64,
+                enabled,
+            },
+            Event::SceneItemLockStateChanged { scene, item_id, locked } => Self::SceneItemLockStateChanged {
+                scene_name: scene,
+                scene_item_id: item_id as i64,
+                locked,
+            },
+            Event::SceneItemListReindexed { scene, .. } => Self::SceneItemListReindexed { scene_name: scene },
+            Event::InputCreated { name, .. } => Self::SourceCreated { source_name: name },
+            Event::InputRemoved { name } => Self::SourceDestroyed { source_name: name },
+            Event::CurrentPreviewSceneChanged { name } => Self::CurrentPreviewSceneChanged { scene_name: name },
+            Event::CurrentSceneCollectionChanged { name } => Self::CurrentSceneCollectionChanged {
+                scene_collection_name: name,
+            },
+            Event::CurrentProfileChanged { name } => Self::CurrentProfileChanged { profile_name: name },
+            Event::StudioModeStateChanged { enabled } => Self::StudioModeStateChanged { enabled },
+            Event::CurrentSceneTransitionChanged { name } => Self::CurrentSceneTransitionChanged { transition_name: name },
+            Event::CurrentSceneTransitionDurationChanged { duration } => Self::CurrentSceneTransitionDurationChanged {
+                duration_ms: duration.whole_milliseconds() as i64,
+            },
+            Event::SourceFilterCreated {
+                source,
+                filter,
+                kind,
+                index,
+                settings,
+                ..
+            } => Self::SourceFilterCreated {
+                source_name: source,
+                filter_name: filter,
+                filter_kind: kind,
+                filter_index: index,
+                filter_settings: settings,
+            },
+            Event::SourceFilterRemoved { source, filter } => Self::SourceFilterRemoved {
+                source_name: source,
+                filter_name: filter,
+            },
+            Event::SourceFilterNameChanged {
+                source,
+                old_name,
+                new_name,
+            } => Self::SourceFilterNameChanged {
+                source_name: source,
+                old_filter_name: old_name,
+                filter_name: new_name,
+            },
+            Event::SourceFilterListReindexed { source, .. } => Self::SourceFilterListReindexed { source_name: source },
+            Event::SourceFilterEnableStateChanged { source, filter, enabled } => Self::SourceFilterEnableStateChanged {
+                source_name: source,
+                filter_name: filter,
+                enabled,
+            },
+            Event::InputVolumeChanged { name, db, .. } => Self::InputVolumeChanged {
+                input_name: name,
+                volume_db: db,
+            },
+            Event::InputMuteStateChanged { name, muted } => Self::InputMuteStateChanged { input_name: name, muted },
+            Event::InputAudioBalanceChanged { name, audio_balance } => Self::InputAudioBalanceChanged {
+                input_name: name,
+                balance: audio_balance,
+            },
+            Event::InputAudioSyncOffsetChanged { name, offset } => Self::InputAudioSyncOffsetChanged {
+                input_name: name,
+                sync_offset_ms: offset.whole_milliseconds() as i64,
+            },
+            Event::InputAudioMonitorTypeChanged { name, monitor_type } => Self::InputAudioMonitorTypeChanged {
+                input_name: name,
+                monitor_type: OBSCommands::monitor_type_to_str(monitor_type).to_string(),
+            },
+            // Keyed by track number as a string ("1" to "6")
+            Event::InputAudioTracksChanged { name, tracks } => Self::InputAudioTracksChanged {
+                input_name: name,
+                tracks: (1..=6)
+                    .map(|track| tracks.get(&track.to_string()).copied().unwrap_or(false))
+                    .collect(),
+            },
+            Event::StreamStateChanged { active, state } => Self::StreamStateChanged {
+                output_active: active,
+                output_state: output_state_to_str(state)?.to_string(),
+            },
+            Event::RecordStateChanged { active, state, .. } => Self::RecordStateChanged {
+                output_active: active,
+                output_state: output_state_to_str(state)?.to_string(),
+            },
+            Event::ReplayBufferStateChanged { active, state } => Self::ReplayBufferStateChanged {
+                output_active: active,
+                output_state: output_state_to_str(state)?.to_string(),
+            },
+            Event::VirtualcamStateChanged { active, state } => Self::VirtualcamStateChanged {
+                output_active: active,
+                output_state: output_state_to_str(state)?.to_string(),
+            },
+            Event::ReplayBufferSaved { path } => Self::ReplayBufferSaved {
+                saved_replay_path: path.to_string_lossy().into_owned(),
+            },
+            Event::MediaInputPlaybackStarted { name } => Self::MediaInputPlaybackStarted { input_name: name },
+            Event::MediaInputPlaybackEnded { name } => Self::MediaInputPlaybackEnded { input_name: name },
+            Event::MediaInputActionTriggered { name, media_action } => Self::MediaInputActionTriggered {
+                input_name: name,
+                media_action: OBSCommands::media_action_to_str(media_action)?.to_string(),
+            },
+            Event::CustomEvent(event_data) => Self::CustomEvent { event_data },
+            _ => return None,
+        };
+        Some(event)
+    }
+
+    /// Settings events of obs-websocket 5.4, which obws 0.11 does not know
+    /// and drops as `Event::Unknown`. `data` is the `d` of an op 5 message.
+    fn from_settings_event(data: &Value) -> Option<Self> {
+        let event_data = &data["eventData"];
+        let str_field = |name: &str| event_data[name].as_str().map(String::from);
+        match data["eventType"].as_str()? {
+            "InputSettingsChanged" => Some(Self::InputSettingsChanged {
+                input_name: str_field("inputName")?,
+                settings: event_data.get("inputSettings").cloned(),
+            }),
+            "SourceFilterSettingsChanged" => Some(Self::SourceFilterSettingsChanged {
+                source_name: str_field("sourceName")?,
+                filter_name: str_field("filterName")?,
+                settings: event_data.get("filterSettings").cloned(),
+            }),
+            _ => None,
+        }
+    }
+}
+
+fn output_state_to_str(state: OutputState) -> Option<&'static str> {
+    match state {
+        OutputState::Starting => Some("starting"),
+        OutputState::Started => Some("started"),
+        OutputState::Stopping => Some("stopping"),
+        OutputState::Stopped => Some("stopped"),
+        OutputState::Paused => Some("paused"),
+        OutputState::Resumed => Some("resumed"),
+        OutputState::Reconnecting => Some("reconnecting"),
+        OutputState::Reconnected => Some("reconnected"),
+        _ => None,
+    }
+}
+
+async fn subscribe(obs_client: &OBSClient) -> Result<impl Stream<Item = Event>> {
+    let client_arc = obs_client.get_client_arc();
+    let client_lock = client_arc.read().await;
+    let client = client_lock.as_ref().context("OBS is not connected")?;
+    client.events().context("Failed to subscribe to OBS events")
+}
+
+/// Answer to the authentication challenge of obs-websocket's `Hello`
+fn authentication(password: &str, salt: &str, challenge: &str) -> String {
+    let secret = BASE64.encode(Sha256::digest(format!("{}{}", password, salt)));
+    BASE64.encode(Sha256::digest(format!("{}{}", secret, challenge)))
+}
+
+/// Data of the next message with opcode `op`; other messages are skipped
+async fn next_op(ws: &mut WsStream, op: u64) -> Result<Value> {
+    while let Some(message) = ws.next().await {
+        if let Message::Text(text) = message.context("OBS event connection failed")? {
+            let mut message: Value = serde_json::from_str(&text).context("Invalid message from OBS")?;
+            if message["op"].as_u64() == Some(op) {
+                return Ok(message["d"].take());
+            }
+        }
+    }
+    bail!("OBS closed the event connection")
+}
+
+/// Forward settings events from a connection of our own, subscribed to
+/// input and filter events only. Returns once `event_tx` is closed.
+async fn forward_settings_events(config: &OBSConnectionConfig, event_tx: &mpsc::UnboundedSender<OBSEvent>) -> Result<()> {
+    let url = format!("ws://{}:{}", config.host, config.port);
+    let (mut ws, _) = connect_async(&url)
+        .await
+        .context(format!("Failed to connect to {}", url))?;
+
+    let hello = next_op(&mut ws, 0).await?;
+    let mut identify = serde_json::json!({
+        "rpcVersion": hello["rpcVersion"].as_u64().unwrap_or(1),
+        "eventSubscriptions": (EventSubscription::INPUTS | EventSubscription::FILTERS).bits(),
+    });
+    if let Some(auth) = hello.get("authentication") {
+        let password = config.password.as_deref().context("OBS requires a password")?;
+        identify["authentication"] = Value::String(authentication(
+            password,
+            auth["salt"].as_str().unwrap_or_default(),
+            auth["challenge"].as_str().unwrap_or_default(),
+        ));
+    }
+    let identify = serde_json::json!({ "op": 1, "d": identify });
+    ws.send(Message::Text(identify.to_string().into()))
+        .await
+        .context("Failed to identify with OBS")?;
+    next_op(&mut ws, 2).await?;
+
+    loop {
+        let event = next_op(&mut ws, 5).await?;
+        if let Some(event) = OBSEvent::from_settings_event(&event) {
+            if event_tx.send(event).is_err() {
+                return Ok(());
+            }
+        }
+    }
 }
 
 pub struct OBSEventHandler {
     event_tx: mpsc::UnboundedSender<OBSEvent>,
+    tasks: Mutex<Vec<JoinHandle<()>>>,
 }
 
 impl OBSEventHandler {
     pub fn new() -> (Self, mpsc::UnboundedReceiver<OBSEvent>) {
         let (tx, rx) = mpsc::unbounded_channel();
-        (Self { event_tx: tx }, rx)
+        (
+            Self {
+                event_tx: tx,
+                tasks: Mutex::new(Vec::new()),
+            },
+            rx,
+        )
     }
 
-    pub async fn start_listening(&self, _client: &Client) -> anyhow::Result<()> {
-        let _tx = self.event_tx.clone();
+    /// Forward the events of `obs_client`'s OBS, following reconnects, until
+    /// the handler is dropped
+    pub async fn start_listening(&self, obs_client: &OBSClient) -> Result<()> {
+        let mut events = Some(subscribe(obs_client).await?);
+        let mut tasks = self.tasks.lock().unwrap();
+
+        let tx = self.event_tx.clone();
+        let client = obs_client.clone();
+        tasks.push(tokio::spawn(async move {
+            loop {
+                // Ends when OBS disconnects or this listener fell behind
+                if let Some(stream) = events.take() {
+                    let mut stream = Box::pin(stream);
+                    while let Some(event) = stream.next().await {
+                        if let Some(event) = OBSEvent::from_obws(event) {
+                            if tx.send(event).is_err() {
+                                return;
+                            }
+                        }
+                    }
+                    eprintln!("OBS event stream ended, subscribing again");
+                }
+                match subscribe(&client).await {
+                    Ok(stream) => events = Some(stream),
+                    Err(_) if tx.is_closed() => return,
+                    Err(_) => tokio::time::sleep(RESUBSCRIBE_DELAY).await,
+                }
+            }
+        }));
 
-        // Note: Event listening implementation depends on obws library version
-        // This is a placeholder that keeps the event listener task alive
-        // TODO: Implement actual event subscription based on obws version in use
-        tokio::spawn(async move {
-            println!("Started OBS event listening (placeholder implementation)");
-            println!("Note: Full event integration requires obws events API configuration");
-            
-            // Keep task alive
+        let tx = self.event_tx.clone();
+        let client = obs_client.clone();
+        tasks.push(tokio::spawn(async move {
             loop {
-                tokio::time::sleep(tokio::time::Duration::from_secs(60)).await;
+                if let Some(config) = client.get_config().await {
+                    if let Err(e) = forward_settings_events(&config, &tx).await {
+                        eprintln!("OBS settings events unavailable: {:#}", e);
+                    }
+                }
+                if tx.is_closed() {
+                    return;
+                }
+                tokio::time::sleep(RESUBSCRIBE_DELAY).await;
             }
-        });
+        }));
 
+        println!("Started OBS event listening");
         Ok(())
     }
 }
 
+impl Drop for OBSEventHandler {
+    fn drop(&mut self) {
+        for task in self.tasks.lock().unwrap().drain(..) {
+            task.abort();
+        }
+    }
+}
+
 impl Default for OBSEventHandler {
     fn default() -> Self {
         Self::new().0
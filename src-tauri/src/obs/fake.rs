@@ -0,0 +1,172 @@
+//! Minimal OBS WebSocket server for tests: completes the handshake, answers
+//! `GetVersion`, accepts `Set*` requests and reports them, fails every other
+//! request and pushes events to every identified client
+
+use super::client::OBSConnectionConfig;
+use futures::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{broadcast, mpsc, watch};
+use tokio_tungstenite::tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use tokio_tungstenite::tungstenite::Message;
+
+pub struct FakeObs {
+    pub port: u16,
+    /// `Set*` request types with their data, in the order they arrived
+    pub requests: mpsc::UnboundedReceiver<(String, Value)>,
+    events: broadcast::Sender<Value>,
+    identified: watch::Receiver<usize>,
+    available_requests: Arc<Mutex<Vec<String>>>,
+}
+
+impl FakeObs {
+    pub async fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let (request_tx, requests) = mpsc::unbounded_channel();
+        let (events, _) = broadcast::channel(64);
+        let (identified_tx, identified) = watch::channel(0);
+        let available_requests = Arc::new(Mutex::new(Vec::new()));
+
+        let events_for_accept = events.clone();
+        let available_for_accept = available_requests.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(serve(
+                    stream,
+                    request_tx.clone(),
+                    events_for_accept.subscribe(),
+                    identified_tx.clone(),
+                    available_for_accept.clone(),
+                ));
+            }
+        });
+
+        Self {
+            port,
+            requests,
+            events,
+            identified,
+            available_requests,
+        }
+    }
+
+    pub fn config(&self) -> OBSConnectionConfig {
+        OBSConnectionConfig {
+            host: "127.0.0.1".to_string(),
+            port: self.port,
+            password: None,
+        }
+    }
+
+    /// Wait until `count` clients have identified
+    pub async fn wait_identified(&mut self, count: usize) {
+        self.identified.wait_for(|identified| *identified >= count).await.unwrap();
+    }
+
+    /// Request types `GetVersion` reports from now on; empty (the default)
+    /// leaves them unknown
+    pub fn set_available_requests(&self, requests: &[&str]) {
+        *self.available_requests.lock().unwrap() = requests.iter().map(|r| r.to_string()).collect();
+    }
+
+    pub fn send_event(&self, event_type: &str, event_data: Value) {
+        let event = json!({
+            "op": 5,
+            "d": { "eventType": event_type, "eventIntent": 0, "eventData": event_data },
+        });
+        let _ = self.events.send(event);
+    }
+}
+
+// The handshake callback's error type is tungstenite's
+#[allow(clippy::result_large_err)]
+async fn serve(
+    stream: TcpStream,
+    request_tx: mpsc::UnboundedSender<(String, Value)>,
+    mut events: broadcast::Receiver<Value>,
+    identified: watch::Sender<usize>,
+    available_requests: Arc<Mutex<Vec<String>>>,
+) {
+    let echo_protocol = |request: &Request, mut response: Response| -> Result<Response, ErrorResponse> {
+        if let Some(protocol) = request.headers().get("Sec-WebSocket-Protocol") {
+            response.headers_mut().insert("Sec-WebSocket-Protocol", protocol.clone());
+        }
+        Ok(response)
+    };
+    let Ok(mut ws) = tokio_tungstenite::accept_hdr_async(stream, echo_protocol).await else {
+        return;
+    };
+
+    let hello = json!({ "op": 0, "d": { "obsWebSocketVersion": "5.5.2", "rpcVersion": 1 } });
+    if ws.send(Message::Text(hello.to_string().into())).await.is_err() {
+        return;
+    }
+
+    let mut is_identified = false;
+    loop {
+        let reply = tokio::select! {
+            event = events.recv(), if is_identified => match event {
+                Ok(event) => event,
+                Err(_) => return,
+            },
+            message = ws.next() => {
+                let Some(Ok(Message::Text(text))) = message else {
+                    return;
+                };
+                let message: Value = serde_json::from_str(&text).unwrap();
+                match message["op"].as_u64() {
+                    // Identify
+                    Some(1) => {
+                        is_identified = true;
+                        identified.send_modify(|count| *count += 1);
+                        json!({ "op": 2, "d": { "negotiatedRpcVersion": 1 } })
+                    }
+                    // Request
+                    Some(6) => response(&message["d"], &request_tx, &available_requests),
+                    _ => continue,
+                }
+            }
+        };
+        if ws.send(Message::Text(reply.to_string().into())).await.is_err() {
+            return;
+        }
+    }
+}
+
+fn response(
+    request: &Value,
+    request_tx: &mpsc::UnboundedSender<(String, Value)>,
+    available_requests: &Mutex<Vec<String>>,
+) -> Value {
+    let request_type = request["requestType"].as_str().unwrap_or_default();
+    let (status, data) = match request_type {
+        "GetVersion" => (
+            json!({ "result": true, "code": 100 }),
+            json!({
+                "obsVersion": "30.2.3",
+                "obsWebSocketVersion": "5.5.2",
+                "rpcVersion": 1,
+                "availableRequests": *available_requests.lock().unwrap(),
+                "supportedImageFormats": [],
+                "platform": "test",
+                "platformDescription": "test",
+            }),
+        ),
+        _ if request_type.starts_with("Set") => {
+            let _ = request_tx.send((request_type.to_string(), request["requestData"].clone()));
+            (json!({ "result": true, "code": 100 }), Value::Null)
+        }
+        _ => (json!({ "result": false, "code": 600, "comment": "not faked" }), Value::Null),
+    };
+    json!({
+        "op": 7,
+        "d": {
+            "requestType": request_type,
+            "requestId": request["requestId"],
+            "requestStatus": status,
+            "responseData": data,
+        },
+    })
+}
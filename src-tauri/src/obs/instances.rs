@@ -0,0 +1,78 @@
+use super::client::{OBSClient, OBSConnectionStatus};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// Name of the instance used when a command does not name one
+pub const DEFAULT_INSTANCE: &str = "default";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OBSInstanceStatus {
+    pub name: String,
+    pub status: OBSConnectionStatus,
+}
+
+/// Named OBS connections of this node (e.g. several portable installs on
+/// different WebSocket ports). The default instance always exists.
+pub struct OBSInstances {
+    clients: RwLock<HashMap<String, Arc<OBSClient>>>,
+}
+
+impl OBSInstances {
+    pub fn new() -> Self {
+        let mut clients = HashMap::new();
+        clients.insert(DEFAULT_INSTANCE.to_string(), Arc::new(OBSClient::new()));
+        Self {
+            clients: RwLock::new(clients),
+        }
+    }
+
+    pub async fn get(&self, name: &str) -> Option<Arc<OBSClient>> {
+        self.clients.read().await.get(name).cloned()
+    }
+
+    pub async fn get_or_create(&self, name: &str) -> Arc<OBSClient> {
+        self.clients
+            .write()
+            .await
+            .entry(name.to_string())
+            .or_insert_with(|| Arc::new(OBSClient::new()))
+            .clone()
+    }
+
+    /// Remove a named instance; the default instance can only be disconnected
+    pub async fn remove(&self, name: &str) -> Option<Arc<OBSClient>> {
+        if name == DEFAULT_INSTANCE {
+            return None;
+        }
+        self.clients.write().await.remove(name)
+    }
+
+    pub async fn statuses(&self) -> Vec<OBSInstanceStatus> {
+        let clients: Vec<(String, Arc<OBSClient>)> = self
+            .clients
+            .read()
+            .await
+            .iter()
+            .map(|(name, client)| (name.clone(), client.clone()))
+            .collect();
+
+        let mut statuses = Vec::with_capacity(clients.len());
+        for (name, client) in clients {
+            statuses.push(OBSInstanceStatus {
+                name,
+                status: client.get_status().await,
+            });
+        }
+        statuses.sort_by(|a, b| a.name.cmp(&b.name));
+        statuses
+    }
+}
+
+impl Default for OBSInstances {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,5 +1,8 @@
 pub mod client;
 pub mod events;
 pub mod commands;
+pub mod instances;
+#[cfg(test)]
+pub mod fake;
 
 pub use client::OBSClient;
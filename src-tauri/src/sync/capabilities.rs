@@ -0,0 +1,161 @@
+use super::protocol::SyncTargetType;
+use super::visual;
+use crate::obs::client::OBSConnectionStatus;
+
+/// OBS WebSocket requests each sync target relies on
+pub fn required_requests(target: &SyncTargetType) -> &'static [&'static str] {
+    match target {
+        SyncTargetType::Program => &["GetCurrentProgramScene", "SetCurrentProgramScene"],
+        SyncTargetType::Preview => &["GetCurrentPreviewScene", "SetCurrentPreviewScene", "SetStudioModeEnabled"],
+        SyncTargetType::Source => &[
+            "GetSceneItemList",
+            "SetSceneItemTransform",
+            "SetSceneItemEnabled",
+            "GetInputSettings",
+            "SetInputSettings",
+        ],
+        SyncTargetType::Filter => &[
+            "GetSourceFilterList",
+            "CreateSourceFilter",
+            "SetSourceFilterSettings",
+            "SetSourceFilterEnabled",
+            "SetSourceFilterIndex",
+        ],
+        SyncTargetType::Audio => &[
+            "SetInputVolume",
+            "SetInputMute",
+            "SetInputAudioBalance",
+            "SetInputAudioSyncOffset",
+            "SetInputAudioMonitorType",
+            "SetInputAudioTracks",
+        ],
+        SyncTargetType::Media => &["GetMediaInputStatus", "SetMediaInputCursor", "TriggerMediaInputAction"],
+        SyncTargetType::Outputs => &[
+            "GetStreamStatus",
+            "GetRecordStatus",
+            "StartStream",
+            "StartRecord",
+            "PauseRecord",
+            "SaveReplayBuffer",
+            "StartVirtualCam",
+        ],
+        SyncTargetType::Hotkeys => &["TriggerHotkeyByName", "TriggerHotkeyByKeySequence"],
+        SyncTargetType::Visual => &["GetSourceScreenshot"],
+    }
+}
+
+/// Requests of `target` the connected OBS does not offer. Nothing is reported
+/// while the request list is unknown (not connected).
+pub fn missing_requests(status: &OBSConnectionStatus, target: &SyncTargetType) -> Vec<&'static str> {
+    if status.available_requests.is_empty() {
+        return Vec::new();
+    }
+    required_requests(target)
+        .iter()
+        .copied()
+        .filter(|request| !status.supports_request(request))
+        .collect()
+}
+
+/// Why `target` cannot be synced with this OBS, if it cannot
+pub fn unsupported_reason(status: &OBSConnectionStatus, target: &SyncTargetType) -> Option<String> {
+    if *target == SyncTargetType::Visual
+        && !status.supported_image_formats.is_empty()
+        && !status.supported_image_formats.iter().any(|f| f == visual::SCREENSHOT_FORMAT)
+    {
+        return Some(format!(
+            "Visual sync needs {} screenshots, which this OBS does not support",
+            visual::SCREENSHOT_FORMAT
+        ));
+    }
+
+    let missing = missing_requests(status, target);
+    if missing.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "{:?} sync is not supported by OBS {} (WebSocket {}, RPC {}): missing {}",
+        target,
+        status.obs_version.as_deref().unwrap_or("?"),
+        status.obs_websocket_version.as_deref().unwrap_or("?"),
+        status.rpc_version.map(|v| v.to_string()).unwrap_or_else(|| "?".to_string()),
+        missing.join(", "),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TARGETS: [SyncTargetType; 9] = [
+        SyncTargetType::Program,
+        SyncTargetType::Preview,
+        SyncTargetType::Source,
+        SyncTargetType::Filter,
+        SyncTargetType::Audio,
+        SyncTargetType::Media,
+        SyncTargetType::Outputs,
+        SyncTargetType::Hotkeys,
+        SyncTargetType::Visual,
+    ];
+
+    fn status(available_requests: Vec<String>) -> OBSConnectionStatus {
+        serde_json::from_value(serde_json::json!({
+            "connected": true,
+            "obsVersion": "30.2.3",
+            "obsWebSocketVersion": "5.5.2",
+            "rpcVersion": 1,
+            "availableRequests": available_requests,
+        }))
+        .unwrap()
+    }
+
+    fn all_requests() -> Vec<String> {
+        TARGETS
+            .iter()
+            .flat_map(required_requests)
+            .map(|request| request.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn unknown_requests_allow_every_target() {
+        let status = status(Vec::new());
+        for target in &TARGETS {
+            assert!(missing_requests(&status, target).is_empty());
+            assert_eq!(unsupported_reason(&status, target), None);
+        }
+    }
+
+    #[test]
+    fn offered_requests_allow_every_target() {
+        let status = status(all_requests());
+        for target in &TARGETS {
+            assert_eq!(unsupported_reason(&status, target), None, "{:?}", target);
+        }
+    }
+
+    #[test]
+    fn missing_request_refuses_its_target() {
+        for target in &TARGETS {
+            let missing = required_requests(target)[0];
+            let status = status(all_requests().into_iter().filter(|r| r != missing).collect());
+
+            assert_eq!(missing_requests(&status, target), vec![missing]);
+            let reason = unsupported_reason(&status, target).unwrap();
+            assert!(reason.contains(missing), "{}", reason);
+            assert!(reason.contains("OBS 30.2.3 (WebSocket 5.5.2, RPC 1)"), "{}", reason);
+        }
+    }
+
+    #[test]
+    fn visual_needs_screenshot_format() {
+        let mut status = status(all_requests());
+        status.supported_image_formats = vec!["jpg".to_string()];
+        assert!(unsupported_reason(&status, &SyncTargetType::Visual).is_some());
+        assert_eq!(unsupported_reason(&status, &SyncTargetType::Program), None);
+
+        status.supported_image_formats.push(visual::SCREENSHOT_FORMAT.to_string());
+        assert_eq!(unsupported_reason(&status, &SyncTargetType::Visual), None);
+    }
+}
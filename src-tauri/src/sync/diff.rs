@@ -1,4 +1,5 @@
 use serde_json::Value;
+use std::collections::HashSet;
 
 #[derive(Debug, Clone)]
 pub struct StateDifference {
@@ -11,10 +12,16 @@ pub struct StateDifference {
 
 #[derive(Debug, Clone)]
 pub enum DiffCategory {
+    CollectionMismatch,
     SceneMismatch,
     SourceMissing,
     TransformMismatch,
     SettingsMismatch,
+    VisibilityMismatch,
+    LockMismatch,
+    ZOrderMismatch,
+    AudioMismatch,
+    MediaMismatch,
 }
 
 #[derive(Debug, Clone)]
@@ -28,10 +35,37 @@ pub struct DiffDetector;
 
 impl DiffDetector {
     const TRANSFORM_TOLERANCE: f64 = 0.5; // Tolerance for position/scale differences
+    const VOLUME_TOLERANCE_DB: f64 = 0.1;
+    const BALANCE_TOLERANCE: f64 = 0.01;
+    const MEDIA_DRIFT_TOLERANCE_MS: i64 = 500;
 
     pub fn detect_differences(local_state: &Value, expected_state: &Value) -> Vec<StateDifference> {
         let mut diffs = Vec::new();
 
+        // Compare scene collection and profile; with the wrong collection loaded
+        // every other difference is a consequence of it
+        for (key, label, severity) in [
+            ("scene_collection", "Scene collection", DiffSeverity::Critical),
+            ("profile", "Profile", DiffSeverity::Warning),
+        ] {
+            let local = local_state.get(key).and_then(|v| v.as_str());
+            let expected = expected_state.get(key).and_then(|v| v.as_str());
+            if let (Some(local), Some(expected)) = (local, expected) {
+                if local != expected {
+                    diffs.push(StateDifference {
+                        category: DiffCategory::CollectionMismatch,
+                        scene_name: String::new(),
+                        source_name: String::new(),
+                        description: format!(
+                            "{} mismatch: local='{}', expected='{}'",
+                            label, local, expected
+                        ),
+                        severity,
+                    });
+                }
+            }
+        }
+
         // Compare current scene
         let local_scene = local_state.get("current_scene").and_then(|v| v.as_str()).unwrap_or("");
         let expected_scene = expected_state.get("current_scene").and_then(|v| v.as_str()).unwrap_or("");
@@ -49,39 +83,50 @@ impl DiffDetector {
             });
         }
 
-        // Compare sources in current scene
+        // Expected sources come either as a flat list or from the per-scene map
+        let expected_sources = expected_state
+            .get("sources")
+            .or_else(|| expected_state.get("scenes").and_then(|scenes| scenes.get(expected_scene)))
+            .and_then(|v| v.as_array());
+
+        // Compare sources in current scene, descending into groups and nested scenes
         if let (Some(local_sources), Some(expected_sources)) = (
             local_state.get("sources").and_then(|v| v.as_array()),
-            expected_state.get("sources").and_then(|v| v.as_array())
+            expected_sources
         ) {
-            // Check for missing sources
-            for expected_source in expected_sources {
-                let expected_name = expected_source.get("name").and_then(|v| v.as_str()).unwrap_or("");
-                
-                if !local_sources.iter().any(|s| {
-                    s.get("name").and_then(|v| v.as_str()).unwrap_or("") == expected_name
-                }) {
-                    diffs.push(StateDifference {
-                        category: DiffCategory::SourceMissing,
-                        scene_name: local_scene.to_string(),
-                        source_name: expected_name.to_string(),
-                        description: format!("Source '{}' is missing", expected_name),
-                        severity: DiffSeverity::Warning,
-                    });
-                } else {
-                    // Source exists, check transform
-                    if let Some(local_source) = local_sources.iter().find(|s| {
-                        s.get("name").and_then(|v| v.as_str()).unwrap_or("") == expected_name
-                    }) {
-                        if let Some(transform_diffs) = Self::compare_transforms(
-                            local_source,
-                            expected_source,
-                            local_scene,
-                            expected_name
-                        ) {
-                            diffs.extend(transform_diffs);
-                        }
-                    }
+            let mut visited = HashSet::new();
+            visited.insert(local_scene.to_string());
+            Self::compare_scene_items(
+                local_state,
+                expected_state,
+                local_sources,
+                expected_sources,
+                local_scene,
+                &mut visited,
+                &mut diffs,
+            );
+        }
+
+        // Compare mixer state of inputs the master reported
+        if let (Some(local_audio), Some(expected_audio)) = (
+            local_state.get("audio").and_then(|v| v.as_object()),
+            expected_state.get("audio").and_then(|v| v.as_object())
+        ) {
+            for (input_name, expected_input) in expected_audio {
+                if let Some(local_input) = local_audio.get(input_name) {
+                    diffs.extend(Self::compare_audio(local_input, expected_input, input_name));
+                }
+            }
+        }
+
+        // Compare playback state and cursor drift of media inputs the master reported
+        if let (Some(local_media), Some(expected_media)) = (
+            local_state.get("media").and_then(|v| v.as_object()),
+            expected_state.get("media").and_then(|v| v.as_object())
+        ) {
+            for (input_name, expected_input) in expected_media {
+                if let Some(local_input) = local_media.get(input_name) {
+                    diffs.extend(Self::compare_media(local_input, expected_input, input_name));
                 }
             }
         }
@@ -89,6 +134,225 @@ impl DiffDetector {
         diffs
     }
 
+    /// Compare the items of one scene or group, then walk into every group and
+    /// nested scene present on both sides. Their items are looked up in the
+    /// `scenes` map of each state; `visited` keeps a scene used twice from being
+    /// reported twice.
+    fn compare_scene_items(
+        local_state: &Value,
+        expected_state: &Value,
+        local_sources: &[Value],
+        expected_sources: &[Value],
+        scene_name: &str,
+        visited: &mut HashSet<String>,
+        diffs: &mut Vec<StateDifference>,
+    ) {
+        // Check for missing sources
+        for expected_source in expected_sources {
+            let expected_name = expected_source.get("name").and_then(|v| v.as_str()).unwrap_or("");
+            
+            let Some(local_source) = local_sources.iter().find(|s| {
+                s.get("name").and_then(|v| v.as_str()).unwrap_or("") == expected_name
+            }) else {
+                diffs.push(StateDifference {
+                    category: DiffCategory::SourceMissing,
+                    scene_name: scene_name.to_string(),
+                    source_name: expected_name.to_string(),
+                    description: format!("Source '{}' is missing", expected_name),
+                    severity: DiffSeverity::Warning,
+                });
+                continue;
+            };
+
+            // Source exists, check transform
+            if let Some(transform_diffs) = Self::compare_transforms(
+                local_source,
+                expected_source,
+                scene_name,
+                expected_name
+            ) {
+                diffs.extend(transform_diffs);
+            }
+            diffs.extend(Self::compare_item_state(
+                local_source,
+                expected_source,
+                scene_name,
+                expected_name
+            ));
+
+            let is_container = matches!(
+                expected_source.get("source_type").and_then(|v| v.as_str()),
+                Some("scene") | Some("group")
+            );
+            if is_container && visited.insert(expected_name.to_string()) {
+                let children = |state: &Value| {
+                    state
+                        .get("scenes")
+                        .and_then(|scenes| scenes.get(expected_name))
+                        .and_then(|v| v.as_array())
+                        .cloned()
+                };
+                if let (Some(local_children), Some(expected_children)) =
+                    (children(local_state), children(expected_state))
+                {
+                    Self::compare_scene_items(
+                        local_state,
+                        expected_state,
+                        &local_children,
+                        &expected_children,
+                        expected_name,
+                        visited,
+                        diffs,
+                    );
+                }
+            }
+        }
+
+        if let Some(order_diff) = Self::compare_order(local_sources, expected_sources, scene_name) {
+            diffs.push(order_diff);
+        }
+    }
+
+    /// Where the master's cursor should be at `at_ms` (wall clock), extrapolating
+    /// from the last reported position while playing. Media past its duration is
+    /// assumed to loop.
+    pub fn expected_media_cursor(expected: &Value, at_ms: i64) -> Option<i64> {
+        let cursor_ms = expected.get("cursor_ms")?.as_i64()?;
+        if expected.get("state").and_then(|v| v.as_str()) != Some("playing") {
+            return Some(cursor_ms);
+        }
+
+        let captured_at = expected.get("captured_at").and_then(|v| v.as_i64()).unwrap_or(at_ms);
+        let cursor_ms = cursor_ms + (at_ms - captured_at).max(0);
+        match expected.get("duration_ms").and_then(|v| v.as_i64()) {
+            Some(duration_ms) if duration_ms > 0 => Some(cursor_ms % duration_ms),
+            _ => Some(cursor_ms),
+        }
+    }
+
+    fn compare_media(local: &Value, expected: &Value, input_name: &str) -> Vec<StateDifference> {
+        let local_state = local.get("state").and_then(|v| v.as_str()).unwrap_or("");
+        let expected_state = expected.get("state").and_then(|v| v.as_str()).unwrap_or("");
+
+        // Transient states (opening, buffering, ended) are not worth an alert
+        if !matches!(expected_state, "playing" | "paused") {
+            return Vec::new();
+        }
+
+        if local_state != expected_state {
+            return vec![StateDifference {
+                category: DiffCategory::MediaMismatch,
+                scene_name: String::new(),
+                source_name: input_name.to_string(),
+                description: format!(
+                    "Media state mismatch: local={}, expected={}",
+                    local_state, expected_state
+                ),
+                severity: DiffSeverity::Warning,
+            }];
+        }
+
+        let local_cursor = local.get("cursor_ms").and_then(|v| v.as_i64());
+        let local_at = local.get("captured_at").and_then(|v| v.as_i64()).unwrap_or(0);
+        if let (Some(local_cursor), Some(expected_cursor)) =
+            (local_cursor, Self::expected_media_cursor(expected, local_at))
+        {
+            let drift = local_cursor - expected_cursor;
+            if drift.abs() > Self::MEDIA_DRIFT_TOLERANCE_MS {
+                return vec![StateDifference {
+                    category: DiffCategory::MediaMismatch,
+                    scene_name: String::new(),
+                    source_name: input_name.to_string(),
+                    description: format!(
+                        "Media cursor drift: local={}ms, expected={}ms ({:+}ms)",
+                        local_cursor, expected_cursor, drift
+                    ),
+                    severity: DiffSeverity::Warning,
+                }];
+            }
+        }
+
+        Vec::new()
+    }
+
+    fn compare_audio(local: &Value, expected: &Value, input_name: &str) -> Vec<StateDifference> {
+        let mut mismatches = Vec::new();
+
+        let f64_field = |v: &Value, key: &str| v.get(key).and_then(|v| v.as_f64());
+
+        if let (Some(l), Some(e)) = (f64_field(local, "volume_db"), f64_field(expected, "volume_db")) {
+            if (l - e).abs() > Self::VOLUME_TOLERANCE_DB {
+                mismatches.push(format!("volume local={:.1}dB expected={:.1}dB", l, e));
+            }
+        }
+        if let (Some(l), Some(e)) = (f64_field(local, "balance"), f64_field(expected, "balance")) {
+            if (l - e).abs() > Self::BALANCE_TOLERANCE {
+                mismatches.push(format!("balance local={:.2} expected={:.2}", l, e));
+            }
+        }
+        for key in ["muted", "sync_offset_ms", "monitor_type", "tracks"] {
+            if let (Some(l), Some(e)) = (local.get(key), expected.get(key)) {
+                if !e.is_null() && l != e {
+                    mismatches.push(format!("{} local={} expected={}", key, l, e));
+                }
+            }
+        }
+
+        if mismatches.is_empty() {
+            return Vec::new();
+        }
+
+        vec![StateDifference {
+            category: DiffCategory::AudioMismatch,
+            scene_name: String::new(),
+            source_name: input_name.to_string(),
+            description: format!("Audio mismatch: {}", mismatches.join(", ")),
+            severity: DiffSeverity::Warning,
+        }]
+    }
+
+    /// Compare the relative stacking order of sources present on both sides.
+    /// Extra local sources are ignored so they don't mask the comparison.
+    fn compare_order(
+        local_sources: &[Value],
+        expected_sources: &[Value],
+        scene_name: &str,
+    ) -> Option<StateDifference> {
+        let name_of = |s: &Value| s.get("name").and_then(|v| v.as_str()).unwrap_or("").to_string();
+        let sorted_names = |sources: &[Value], keep: &dyn Fn(&str) -> bool| -> Vec<String> {
+            let mut indexed: Vec<(u64, String)> = sources
+                .iter()
+                .filter_map(|s| Some((s.get("index")?.as_u64()?, name_of(s))))
+                .filter(|(_, name)| keep(name.as_str()))
+                .collect();
+            // Top-most first, as shown in the OBS sources dock
+            indexed.sort_by(|a, b| b.0.cmp(&a.0));
+            indexed.into_iter().map(|(_, name)| name).collect()
+        };
+
+        let local_names: Vec<String> = local_sources.iter().map(name_of).collect();
+        let expected_names: Vec<String> = expected_sources.iter().map(name_of).collect();
+
+        let expected_order = sorted_names(expected_sources, &|name| local_names.iter().any(|n| n == name));
+        let local_order = sorted_names(local_sources, &|name| expected_names.iter().any(|n| n == name));
+
+        if expected_order.len() < 2 || local_order == expected_order {
+            return None;
+        }
+
+        Some(StateDifference {
+            category: DiffCategory::ZOrderMismatch,
+            scene_name: scene_name.to_string(),
+            source_name: String::new(),
+            description: format!(
+                "Z-order mismatch (top first): local=[{}], expected=[{}]",
+                local_order.join(", "),
+                expected_order.join(", ")
+            ),
+            severity: DiffSeverity::Warning,
+        })
+    }
+
     fn compare_transforms(
         local_source: &Value,
         expected_source: &Value,
@@ -149,6 +413,56 @@ impl DiffDetector {
         }
     }
 
+    fn compare_item_state(
+        local_source: &Value,
+        expected_source: &Value,
+        scene_name: &str,
+        source_name: &str,
+    ) -> Vec<StateDifference> {
+        let mut diffs = Vec::new();
+
+        // Compare visibility (only when both sides report it)
+        if let (Some(local_enabled), Some(expected_enabled)) = (
+            local_source.get("enabled").and_then(|v| v.as_bool()),
+            expected_source.get("enabled").and_then(|v| v.as_bool()),
+        ) {
+            if local_enabled != expected_enabled {
+                diffs.push(StateDifference {
+                    category: DiffCategory::VisibilityMismatch,
+                    scene_name: scene_name.to_string(),
+                    source_name: source_name.to_string(),
+                    description: format!(
+                        "Visibility mismatch: local={}, expected={}",
+                        if local_enabled { "shown" } else { "hidden" },
+                        if expected_enabled { "shown" } else { "hidden" }
+                    ),
+                    severity: DiffSeverity::Warning,
+                });
+            }
+        }
+
+        // Compare lock state
+        if let (Some(local_locked), Some(expected_locked)) = (
+            local_source.get("locked").and_then(|v| v.as_bool()),
+            expected_source.get("locked").and_then(|v| v.as_bool()),
+        ) {
+            if local_locked != expected_locked {
+                diffs.push(StateDifference {
+                    category: DiffCategory::LockMismatch,
+                    scene_name: scene_name.to_string(),
+                    source_name: source_name.to_string(),
+                    description: format!(
+                        "Lock mismatch: local={}, expected={}",
+                        local_locked, expected_locked
+                    ),
+                    severity: DiffSeverity::Info,
+                });
+            }
+        }
+
+        diffs
+    }
+
     pub fn is_synced(diffs: &[StateDifference]) -> bool {
         diffs.is_empty()
     }
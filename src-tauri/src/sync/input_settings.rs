@@ -0,0 +1,126 @@
+use serde_json::Value;
+
+/// Settings keys that point at hardware, windows or displays of the local machine.
+/// They never leave the master and are never applied on a slave, because the
+/// same value would select a different (or no) device on another machine.
+const MACHINE_SPECIFIC_KEYS: &[(&str, &[&str])] = &[
+    // Windows
+    ("dshow_input", &["video_device_id", "audio_device_id", "last_video_device_id", "last_resolution"]),
+    ("wasapi_input_capture", &["device_id"]),
+    ("wasapi_output_capture", &["device_id"]),
+    ("wasapi_process_output_capture", &["window"]),
+    ("game_capture", &["window"]),
+    ("monitor_capture", &["monitor", "monitor_id"]),
+    // macOS
+    ("av_capture_input", &["device", "device_name"]),
+    ("coreaudio_input_capture", &["device_id"]),
+    ("coreaudio_output_capture", &["device_id"]),
+    ("display_capture", &["display", "display_uuid"]),
+    ("screen_capture", &["display", "display_uuid", "window", "application"]),
+    // Linux
+    ("v4l2_input", &["device_id"]),
+    ("pulse_input_capture", &["device_id"]),
+    ("pulse_output_capture", &["device_id"]),
+    ("alsa_input_capture", &["device_id"]),
+    ("xcomposite_input", &["capture_window"]),
+    ("xshm_input", &["screen"]),
+    ("pipewire-desktop-capture-source", &["RestoreToken"]),
+    ("pipewire-window-capture-source", &["RestoreToken"]),
+    // Cross-platform
+    ("window_capture", &["window", "owner_name", "window_name"]),
+    ("decklink-input", &["device_hash", "device_name"]),
+];
+
+/// Strip the version suffix OBS appends to input kinds (`text_gdiplus_v3` -> `text_gdiplus`).
+pub fn unversioned_kind(kind: &str) -> &str {
+    match kind.rfind("_v") {
+        Some(pos) if pos + 2 < kind.len() && kind[pos + 2..].chars().all(|c| c.is_ascii_digit()) => {
+            &kind[..pos]
+        }
+        _ => kind,
+    }
+}
+
+/// Whether this kind is synced through `ImageUpdate` (file contents) instead of plain settings
+pub fn is_image_kind(kind: &str) -> bool {
+    unversioned_kind(kind) == "image_source"
+}
+
+/// Whether this kind is a text source eligible for the low-latency `TextUpdate` path
+pub fn is_text_kind(kind: &str) -> bool {
+    matches!(unversioned_kind(kind), "text_gdiplus" | "text_ft2_source")
+}
+
+/// Whether a settings key must never be synced for the given input kind
+pub fn is_denied_key(kind: &str, key: &str) -> bool {
+    let kind = unversioned_kind(kind);
+    MACHINE_SPECIFIC_KEYS
+        .iter()
+        .filter(|(k, _)| *k == kind)
+        .any(|(_, keys)| keys.contains(&key))
+}
+
+/// Return a copy of `settings` with all machine-specific keys for `kind` removed
+pub fn filter_settings(kind: &str, settings: &Value) -> Value {
+    match settings.as_object() {
+        Some(map) => Value::Object(
+            map.iter()
+                .filter(|(key, _)| !is_denied_key(kind, key))
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect(),
+        ),
+        None => Value::Object(serde_json::Map::new()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn strips_version_suffix() {
+        assert_eq!(unversioned_kind("text_gdiplus_v2"), "text_gdiplus");
+        assert_eq!(unversioned_kind("text_gdiplus_v3"), "text_gdiplus");
+        assert_eq!(unversioned_kind("text_gdiplus"), "text_gdiplus");
+        assert_eq!(unversioned_kind("dshow_input"), "dshow_input");
+        // Only a trailing `_v` with digits is a version
+        assert_eq!(unversioned_kind("browser_v"), "browser_v");
+        assert_eq!(unversioned_kind("my_video_source"), "my_video_source");
+    }
+
+    #[test]
+    fn classifies_kinds() {
+        assert!(is_image_kind("image_source"));
+        assert!(is_image_kind("image_source_v2"));
+        assert!(!is_image_kind("slideshow"));
+        assert!(is_text_kind("text_gdiplus_v2"));
+        assert!(is_text_kind("text_ft2_source_v2"));
+        assert!(!is_text_kind("image_source"));
+    }
+
+    #[test]
+    fn removes_machine_specific_keys() {
+        let settings = json!({
+            "video_device_id": "USB Camera:\\\\?\\usb#vid_046d",
+            "last_resolution": "1920x1080",
+            "resolution": "1920x1080",
+            "active": true,
+        });
+        assert_eq!(
+            filter_settings("dshow_input", &settings),
+            json!({ "resolution": "1920x1080", "active": true })
+        );
+
+        assert!(is_denied_key("window_capture_v2", "window"));
+        assert!(!is_denied_key("window_capture", "cursor"));
+    }
+
+    #[test]
+    fn keeps_keys_of_other_kinds() {
+        // `window` is only machine-specific for capture sources
+        let settings = json!({ "window": "main", "file": "/media/logo.png" });
+        assert_eq!(filter_settings("image_source", &settings), settings);
+        assert_eq!(filter_settings("browser_source", &Value::Null), json!({}));
+    }
+}
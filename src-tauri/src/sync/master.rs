@@ -1,17 +1,80 @@
-use super::protocol::{SyncMessage, SyncMessageType, SyncTargetType};
-use crate::obs::{events::OBSEvent, OBSClient};
+use super::capabilities;
+use super::input_settings;
+use super::visual;
+use super::protocol::{KeySequence, SyncMessage, SyncMessageType, SyncTargetType};
+use crate::obs::commands::{buffered_requests, OBSCommands, SceneItemTransform};
+use crate::obs::{events::OBSEvent, instances::DEFAULT_INSTANCE, OBSClient};
 use anyhow::Result;
+use obws::responses::scene_items::SceneItem;
+use serde_json::Value;
+use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::{mpsc, RwLock};
+use std::time::Instant;
+use tokio::sync::{mpsc, Mutex, Notify, RwLock};
+
+/// Minimum gap between two text flushes; bursts inside it collapse to the latest value
+const TEXT_FLUSH_INTERVAL_MS: u64 = 16;
+
+/// How often the program scene fingerprint is sent while `Visual` is active
+const VISUAL_CHECK_INTERVAL_MS: u64 = 2000;
+
+/// Latest pending text per input, waiting for the next flush
+struct PendingText {
+    text: String,
+    captured_at: i64,
+}
+
+/// Output state last reported by a slave
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlaveOutputReport {
+    pub client_id: String,
+    pub instance: String,
+    pub outputs: Value,
+    pub reported_at: i64,
+}
+
+/// Hotkeys that are forwarded to slaves when triggered on the master
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HotkeyForwarding {
+    /// OBS hotkey names, e.g. `OBSBasic.Transition`
+    pub hotkey_names: Vec<String>,
+    pub key_sequences: Vec<KeySequence>,
+}
+
+impl HotkeyForwarding {
+    fn forwards(&self, hotkey_name: Option<&str>, key_sequence: Option<&KeySequence>) -> bool {
+        match (hotkey_name, key_sequence) {
+            (Some(name), _) => self.hotkey_names.iter().any(|n| n == name),
+            (None, Some(sequence)) => self.key_sequences.contains(sequence),
+            (None, None) => false,
+        }
+    }
+}
 
 pub struct MasterSync {
     obs_client: Arc<OBSClient>,
     message_tx: mpsc::UnboundedSender<SyncMessage>,
     active_targets: Arc<RwLock<Vec<SyncTargetType>>>,
+    /// Input kind per input name, so settings events can be routed without a fetch
+    input_kinds: Arc<RwLock<HashMap<String, String>>>,
+    /// Last synced settings of text inputs, without the `text` key
+    text_snapshots: Arc<RwLock<HashMap<String, Value>>>,
+    pending_text: Arc<Mutex<HashMap<String, PendingText>>>,
+    text_notify: Arc<Notify>,
+    /// Current scene transition, attached to SceneChange without a request on the hot path
+    current_transition: Arc<RwLock<Option<Value>>>,
+    /// Output state reported by each slave instance, keyed by client id and instance name
+    slave_outputs: Arc<RwLock<HashMap<(String, String), SlaveOutputReport>>>,
+    hotkey_forwarding: Arc<RwLock<HotkeyForwarding>>,
 }
 
 impl MasterSync {
-    pub fn new(obs_client: Arc<OBSClient>) -> (Self, mpsc::UnboundedReceiver<SyncMessage>) {
+    pub fn new(
+        obs_client: Arc<OBSClient>,
+        hotkey_forwarding: Arc<RwLock<HotkeyForwarding>>,
+    ) -> (Self, mpsc::UnboundedReceiver<SyncMessage>) {
         let (tx, rx) = mpsc::unbounded_channel();
         (
             Self {
@@ -21,19 +84,163 @@ impl MasterSync {
                     SyncTargetType::Program,
                     SyncTargetType::Source,
                 ])),
+                input_kinds: Arc::new(RwLock::new(HashMap::new())),
+                text_snapshots: Arc::new(RwLock::new(HashMap::new())),
+                pending_text: Arc::new(Mutex::new(HashMap::new())),
+                text_notify: Arc::new(Notify::new()),
+                current_transition: Arc::new(RwLock::new(None)),
+                slave_outputs: Arc::new(RwLock::new(HashMap::new())),
+                hotkey_forwarding,
             },
             rx,
         )
     }
 
-    pub async fn set_active_targets(&self, targets: Vec<SyncTargetType>) {
-        *self.active_targets.write().await = targets;
+    /// Set the sync targets, refusing those the source OBS cannot support.
+    /// Supported targets are applied even when some are refused.
+    pub async fn set_active_targets(&self, targets: Vec<SyncTargetType>) -> Result<()> {
+        let status = self.obs_client.get_status().await;
+        let (supported, refused): (Vec<_>, Vec<_>) = targets
+            .into_iter()
+            .map(|target| {
+                let reason = capabilities::unsupported_reason(&status, &target);
+                (target, reason)
+            })
+            .partition(|(_, reason)| reason.is_none());
+
+        *self.active_targets.write().await = supported.into_iter().map(|(target, _)| target).collect();
+
+        if refused.is_empty() {
+            Ok(())
+        } else {
+            let reasons: Vec<String> = refused.into_iter().filter_map(|(_, reason)| reason).collect();
+            Err(anyhow::anyhow!(reasons.join("; ")))
+        }
+    }
+
+    /// Handle a message sent by a slave
+    pub async fn handle_client_message(&self, client_id: String, message: SyncMessage) {
+        if message.message_type == SyncMessageType::OutputStatus {
+            let instance = message.payload["instance"]
+                .as_str()
+                .unwrap_or(DEFAULT_INSTANCE)
+                .to_string();
+            let report = SlaveOutputReport {
+                client_id: client_id.clone(),
+                instance: instance.clone(),
+                outputs: message.payload["outputs"].clone(),
+                reported_at: message.timestamp,
+            };
+            self.slave_outputs.write().await.insert((client_id, instance), report);
+        }
+    }
+
+    /// Latest output reports of the given (connected) slaves
+    pub async fn get_slave_outputs(&self, client_ids: &[String]) -> Vec<SlaveOutputReport> {
+        let reports = self.slave_outputs.read().await;
+        let mut connected: Vec<SlaveOutputReport> = reports
+            .values()
+            .filter(|report| client_ids.contains(&report.client_id))
+            .cloned()
+            .collect();
+        connected.sort_by(|a, b| (&a.client_id, &a.instance).cmp(&(&b.client_id, &b.instance)));
+        connected
+    }
+
+    /// Save the replay buffer on the master; the resulting `ReplayBufferSaved`
+    /// event is what slaves follow
+    pub async fn save_replay_buffer(&self) -> Result<()> {
+        let client_arc = self.obs_client.get_client_arc();
+        let client_lock = client_arc.read().await;
+        let client = client_lock
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("OBS client not connected"))?;
+        OBSCommands::save_replay_buffer(client).await
+    }
+
+    /// Trigger a hotkey on the master and forward it to slaves if it is configured
+    pub async fn trigger_hotkey(
+        &self,
+        hotkey_name: Option<String>,
+        key_sequence: Option<KeySequence>,
+    ) -> Result<()> {
+        {
+            let client_arc = self.obs_client.get_client_arc();
+            let client_lock = client_arc.read().await;
+            let client = client_lock
+                .as_ref()
+                .ok_or_else(|| anyhow::anyhow!("OBS client not connected"))?;
+
+            match (&hotkey_name, &key_sequence) {
+                (Some(name), _) => OBSCommands::trigger_hotkey_by_name(client, name).await?,
+                (None, Some(sequence)) => {
+                    OBSCommands::trigger_hotkey_by_sequence(
+                        client,
+                        &sequence.key,
+                        sequence.shift,
+                        sequence.control,
+                        sequence.alt,
+                        sequence.command,
+                    )
+                    .await?
+                }
+                (None, None) => return Err(anyhow::anyhow!("No hotkey name or key sequence given")),
+            }
+        }
+
+        Self::forward_hotkey(&self.hotkey_forwarding, &self.message_tx, hotkey_name, key_sequence).await;
+        Ok(())
+    }
+
+    /// Broadcast `TriggerHotkey` when the hotkey is in the forwarding list
+    async fn forward_hotkey(
+        hotkey_forwarding: &RwLock<HotkeyForwarding>,
+        message_tx: &mpsc::UnboundedSender<SyncMessage>,
+        hotkey_name: Option<String>,
+        key_sequence: Option<KeySequence>,
+    ) {
+        if !hotkey_forwarding
+            .read()
+            .await
+            .forwards(hotkey_name.as_deref(), key_sequence.as_ref())
+        {
+            return;
+        }
+
+        println!("Forwarding hotkey {:?} {:?}", hotkey_name, key_sequence);
+        let msg = SyncMessage::new(
+            SyncMessageType::TriggerHotkey,
+            SyncTargetType::Hotkeys,
+            serde_json::json!({
+                "hotkey_name": hotkey_name,
+                "key_sequence": key_sequence,
+            }),
+        );
+        let _ = message_tx.send(msg);
     }
 
-    pub async fn start_monitoring(&self, mut obs_event_rx: mpsc::UnboundedReceiver<OBSEvent>) {
+    pub async fn start_monitoring(self: &Arc<Self>, mut obs_event_rx: mpsc::UnboundedReceiver<OBSEvent>) {
+        // Weak, so the event loop doesn't keep a stopped master alive
+        let this = Arc::downgrade(self);
         let message_tx = self.message_tx.clone();
         let active_targets = self.active_targets.clone();
         let obs_client = self.obs_client.clone();
+        let input_kinds = self.input_kinds.clone();
+        let text_snapshots = self.text_snapshots.clone();
+        let pending_text = self.pending_text.clone();
+        let text_notify = self.text_notify.clone();
+        let current_transition = self.current_transition.clone();
+        let hotkey_forwarding = self.hotkey_forwarding.clone();
+
+        self.start_text_flush();
+        self.start_visual_check();
+
+        // Prime the transition cache
+        let obs_client_for_transition = obs_client.clone();
+        let transition_for_refresh = current_transition.clone();
+        tokio::spawn(async move {
+            Self::refresh_transition(&obs_client_for_transition, &transition_for_refresh).await;
+        });
 
         tokio::spawn(async move {
             while let Some(event) = obs_event_rx.recv().await {
@@ -43,7 +250,8 @@ impl MasterSync {
                     OBSEvent::SceneChanged { scene_name } => {
                         if targets.contains(&SyncTargetType::Program) {
                             let payload = serde_json::json!({
-                                "scene_name": scene_name
+                                "scene_name": scene_name,
+                                "transition": current_transition.read().await.clone(),
                             });
                             let msg = SyncMessage::new(
                                 SyncMessageType::SceneChange,
@@ -53,6 +261,64 @@ impl MasterSync {
                             let _ = message_tx.send(msg);
                         }
                     }
+                    OBSEvent::SceneCreated { scene_name, is_group } => {
+                        // Groups are created together with their scene item, not on their own
+                        if targets.contains(&SyncTargetType::Program) && !is_group {
+                            let payload = serde_json::json!({
+                                "scene_name": scene_name
+                            });
+                            let msg = SyncMessage::new(
+                                SyncMessageType::SceneCreated,
+                                SyncTargetType::Program,
+                                payload,
+                            );
+                            let _ = message_tx.send(msg);
+                        }
+                    }
+                    OBSEvent::SceneRemoved { scene_name, is_group } => {
+                        if targets.contains(&SyncTargetType::Program) && !is_group {
+                            let payload = serde_json::json!({
+                                "scene_name": scene_name
+                            });
+                            let msg = SyncMessage::new(
+                                SyncMessageType::SceneRemoved,
+                                SyncTargetType::Program,
+                                payload,
+                            );
+                            let _ = message_tx.send(msg);
+                        }
+                    }
+                    OBSEvent::SceneNameChanged { old_scene_name, scene_name } => {
+                        if targets.contains(&SyncTargetType::Program) {
+                            let payload = serde_json::json!({
+                                "scene_name": old_scene_name,
+                                "new_scene_name": scene_name,
+                            });
+                            let msg = SyncMessage::new(
+                                SyncMessageType::SceneRenamed,
+                                SyncTargetType::Program,
+                                payload,
+                            );
+                            let _ = message_tx.send(msg);
+                        }
+                    }
+                    OBSEvent::CurrentSceneCollectionChanged { scene_collection_name } => {
+                        println!("Scene collection changed to {}", scene_collection_name);
+                        Self::spawn_collection_update(&obs_client, &message_tx);
+
+                        // Everything synced so far belonged to the old collection
+                        if let Some(this) = this.upgrade() {
+                            tokio::spawn(async move {
+                                if let Err(e) = this.send_initial_state().await {
+                                    eprintln!("Failed to resend state after collection change: {}", e);
+                                }
+                            });
+                        }
+                    }
+                    OBSEvent::CurrentProfileChanged { profile_name } => {
+                        println!("Profile changed to {}", profile_name);
+                        Self::spawn_collection_update(&obs_client, &message_tx);
+                    }
                     OBSEvent::CurrentPreviewSceneChanged { scene_name } => {
                         if targets.contains(&SyncTargetType::Preview) {
                             let payload = serde_json::json!({
@@ -66,6 +332,35 @@ impl MasterSync {
                             let _ = message_tx.send(msg);
                         }
                     }
+                    OBSEvent::CurrentSceneTransitionChanged { transition_name } => {
+                        // Cache the name alone right away so an immediate cut uses
+                        // it; the cached kind, duration and settings belong to the
+                        // previous transition and must not travel with the new name.
+                        // The refresh below sends the complete transition.
+                        *current_transition.write().await = Some(serde_json::json!({
+                            "name": transition_name,
+                        }));
+                        Self::spawn_transition_update(&obs_client, &current_transition, &message_tx, targets.contains(&SyncTargetType::Program));
+                    }
+                    OBSEvent::CurrentSceneTransitionDurationChanged { duration_ms } => {
+                        if let Some(transition) = current_transition.write().await.as_mut() {
+                            transition["duration_ms"] = serde_json::json!(duration_ms);
+                        }
+                        Self::spawn_transition_update(&obs_client, &current_transition, &message_tx, targets.contains(&SyncTargetType::Program));
+                    }
+                    OBSEvent::StudioModeStateChanged { enabled } => {
+                        if targets.contains(&SyncTargetType::Preview) {
+                            let payload = serde_json::json!({
+                                "enabled": enabled
+                            });
+                            let msg = SyncMessage::new(
+                                SyncMessageType::StudioModeUpdate,
+                                SyncTargetType::Preview,
+                                payload,
+                            );
+                            let _ = message_tx.send(msg);
+                        }
+                    }
                     OBSEvent::SceneItemTransformChanged {
                         scene_name,
                         scene_item_id,
@@ -86,15 +381,7 @@ impl MasterSync {
                                             let payload = serde_json::json!({
                                                 "scene_name": scene_name_clone,
                                                 "scene_item_id": scene_item_id,
-                                                "transform": {
-                                                    "position_x": transform.position_x,
-                                                    "position_y": transform.position_y,
-                                                    "rotation": transform.rotation,
-                                                    "scale_x": transform.scale_x,
-                                                    "scale_y": transform.scale_y,
-                                                    "width": transform.width,
-                                                    "height": transform.height,
-                                                }
+                                                "transform": SceneItemTransform::from(&transform),
                                             });
                                             
                                             let msg = SyncMessage::new(
@@ -113,74 +400,753 @@ impl MasterSync {
                             });
                         }
                     }
-                    OBSEvent::InputSettingsChanged { input_name } => {
+                    OBSEvent::SceneItemCreated {
+                        scene_name,
+                        source_name,
+                        scene_item_id,
+                        scene_item_index,
+                    } => {
                         if targets.contains(&SyncTargetType::Source) {
                             let obs_client_clone = obs_client.clone();
                             let message_tx_clone = message_tx.clone();
-                            let input_name_clone = input_name.clone();
-                            
-                            // Spawn task to get image data
+                            let input_kinds_clone = input_kinds.clone();
+
+                            // Spawn task to collect what a slave needs to provision the item
                             tokio::spawn(async move {
                                 let client_arc = obs_client_clone.get_client_arc();
                                 let client_lock = client_arc.read().await;
-                                
+                                let Some(client) = client_lock.as_ref() else {
+                                    return;
+                                };
+
+                                let item = Self::collect_item_data(
+                                    client,
+                                    &input_kinds_clone,
+                                    &scene_name,
+                                    &source_name,
+                                    scene_item_id,
+                                    scene_item_index,
+                                    None,
+                                ).await;
+                                let payload = serde_json::json!({
+                                    "scene_name": scene_name,
+                                    "item": item,
+                                });
+                                let msg = SyncMessage::new(
+                                    SyncMessageType::SceneItemCreated,
+                                    SyncTargetType::Source,
+                                    payload,
+                                );
+                                let _ = message_tx_clone.send(msg);
+                                println!("Sent new scene item {} in {}", source_name, scene_name);
+                            });
+                        }
+                    }
+                    OBSEvent::SceneItemEnableStateChanged {
+                        scene_name,
+                        scene_item_id,
+                        enabled,
+                    } => {
+                        if targets.contains(&SyncTargetType::Source) {
+                            let payload = serde_json::json!({
+                                "scene_name": scene_name,
+                                "scene_item_id": scene_item_id,
+                                "enabled": enabled,
+                            });
+                            let msg = SyncMessage::new(
+                                SyncMessageType::SceneItemEnabledUpdate,
+                                SyncTargetType::Source,
+                                payload,
+                            );
+                            let _ = message_tx.send(msg);
+                        }
+                    }
+                    OBSEvent::SceneItemLockStateChanged {
+                        scene_name,
+                        scene_item_id,
+                        locked,
+                    } => {
+                        if targets.contains(&SyncTargetType::Source) {
+                            let payload = serde_json::json!({
+                                "scene_name": scene_name,
+                                "scene_item_id": scene_item_id,
+                                "locked": locked,
+                            });
+                            let msg = SyncMessage::new(
+                                SyncMessageType::SceneItemLockedUpdate,
+                                SyncTargetType::Source,
+                                payload,
+                            );
+                            let _ = message_tx.send(msg);
+                        }
+                    }
+                    OBSEvent::SceneItemListReindexed { scene_name } => {
+                        if targets.contains(&SyncTargetType::Source) {
+                            let obs_client_clone = obs_client.clone();
+                            let message_tx_clone = message_tx.clone();
+
+                            // Spawn task to read the new order with source names
+                            tokio::spawn(async move {
+                                let client_arc = obs_client_clone.get_client_arc();
+                                let client_lock = client_arc.read().await;
+
                                 if let Some(client) = client_lock.as_ref() {
-                                    // Get input settings
-                                    match client.inputs().settings::<serde_json::Value>(&input_name_clone).await {
-                                        Ok(settings) => {
-                                            let file_path = settings.settings
-                                                .get("file")
-                                                .and_then(|v| v.as_str())
-                                                .unwrap_or("");
-                                            
-                                            // Read and encode image if file path exists
-                                            let image_data = if !file_path.is_empty() {
-                                                match tokio::fs::read(file_path).await {
-                                                    Ok(data) => {
-                                                        let encoded = base64::Engine::encode(
-                                                            &base64::engine::general_purpose::STANDARD,
-                                                            &data
-                                                        );
-                                                        println!("Encoded image: {} ({} bytes)", file_path, data.len());
-                                                        Some(encoded)
-                                                    }
-                                                    Err(e) => {
-                                                        eprintln!("Failed to read image: {}", e);
-                                                        None
-                                                    }
-                                                }
-                                            } else {
-                                                None
-                                            };
-                                            
+                                    // Also handles groups, whose reindex events carry the group name
+                                    match OBSCommands::get_scene_item_list(client, &scene_name).await {
+                                        Ok(items) => {
                                             let payload = serde_json::json!({
-                                                "scene_name": "",
-                                                "source_name": input_name_clone,
-                                                "file": file_path,
-                                                "image_data": image_data
+                                                "scene_name": scene_name,
+                                                "items": items,
                                             });
-                                            
                                             let msg = SyncMessage::new(
-                                                SyncMessageType::ImageUpdate,
+                                                SyncMessageType::SceneItemOrderUpdate,
                                                 SyncTargetType::Source,
                                                 payload,
                                             );
                                             let _ = message_tx_clone.send(msg);
+                                            println!("Sent z-order update for scene {}", scene_name);
                                         }
                                         Err(e) => {
-                                            eprintln!("Failed to get input settings: {}", e);
+                                            eprintln!("Failed to get scene items for {}: {}", scene_name, e);
                                         }
                                     }
                                 }
                             });
                         }
                     }
+                    OBSEvent::SourceFilterCreated {
+                        source_name,
+                        filter_name,
+                        filter_kind,
+                        filter_index,
+                        filter_settings,
+                    } => {
+                        if targets.contains(&SyncTargetType::Filter) {
+                            let payload = serde_json::json!({
+                                "source_name": source_name,
+                                "filter": {
+                                    "name": filter_name,
+                                    "kind": filter_kind,
+                                    "index": filter_index,
+                                    "enabled": true,
+                                    "settings": filter_settings,
+                                },
+                            });
+                            let msg = SyncMessage::new(
+                                SyncMessageType::FilterCreated,
+                                SyncTargetType::Filter,
+                                payload,
+                            );
+                            let _ = message_tx.send(msg);
+                        }
+                    }
+                    OBSEvent::SourceFilterRemoved { source_name, filter_name } => {
+                        if targets.contains(&SyncTargetType::Filter) {
+                            let payload = serde_json::json!({
+                                "source_name": source_name,
+                                "filter_name": filter_name,
+                            });
+                            let msg = SyncMessage::new(
+                                SyncMessageType::FilterRemoved,
+                                SyncTargetType::Filter,
+                                payload,
+                            );
+                            let _ = message_tx.send(msg);
+                        }
+                    }
+                    OBSEvent::SourceFilterNameChanged {
+                        source_name,
+                        old_filter_name,
+                        filter_name,
+                    } => {
+                        if targets.contains(&SyncTargetType::Filter) {
+                            let payload = serde_json::json!({
+                                "source_name": source_name,
+                                "filter_name": old_filter_name,
+                                "new_filter_name": filter_name,
+                            });
+                            let msg = SyncMessage::new(
+                                SyncMessageType::FilterRenamed,
+                                SyncTargetType::Filter,
+                                payload,
+                            );
+                            let _ = message_tx.send(msg);
+                        }
+                    }
+                    OBSEvent::SourceFilterEnableStateChanged {
+                        source_name,
+                        filter_name,
+                        enabled,
+                    } => {
+                        if targets.contains(&SyncTargetType::Filter) {
+                            let payload = serde_json::json!({
+                                "source_name": source_name,
+                                "filter_name": filter_name,
+                                "enabled": enabled,
+                            });
+                            let msg = SyncMessage::new(
+                                SyncMessageType::FilterEnabledUpdate,
+                                SyncTargetType::Filter,
+                                payload,
+                            );
+                            let _ = message_tx.send(msg);
+                        }
+                    }
+                    OBSEvent::SourceFilterSettingsChanged {
+                        source_name,
+                        filter_name,
+                        settings,
+                    } => {
+                        if targets.contains(&SyncTargetType::Filter) {
+                            let obs_client_clone = obs_client.clone();
+                            let message_tx_clone = message_tx.clone();
+
+                            // Only fetch the settings when the event didn't carry them
+                            tokio::spawn(async move {
+                                let settings = match settings {
+                                    Some(settings) => settings,
+                                    None => {
+                                        let client_arc = obs_client_clone.get_client_arc();
+                                        let client_lock = client_arc.read().await;
+                                        let Some(client) = client_lock.as_ref() else {
+                                            return;
+                                        };
+                                        match OBSCommands::get_source_filter_settings(client, &source_name, &filter_name).await {
+                                            Ok(settings) => settings,
+                                            Err(e) => {
+                                                eprintln!("Failed to get filter settings: {}", e);
+                                                return;
+                                            }
+                                        }
+                                    }
+                                };
+
+                                let payload = serde_json::json!({
+                                    "source_name": source_name,
+                                    "filter_name": filter_name,
+                                    "settings": settings,
+                                });
+                                let msg = SyncMessage::new(
+                                    SyncMessageType::FilterSettingsUpdate,
+                                    SyncTargetType::Filter,
+                                    payload,
+                                );
+                                let _ = message_tx_clone.send(msg);
+                            });
+                        }
+                    }
+                    OBSEvent::SourceFilterListReindexed { source_name } => {
+                        if targets.contains(&SyncTargetType::Filter) {
+                            let obs_client_clone = obs_client.clone();
+                            let message_tx_clone = message_tx.clone();
+
+                            tokio::spawn(async move {
+                                let client_arc = obs_client_clone.get_client_arc();
+                                let client_lock = client_arc.read().await;
+
+                                if let Some(client) = client_lock.as_ref() {
+                                    match OBSCommands::get_source_filters(client, &source_name).await {
+                                        Ok(filters) => {
+                                            let payload = serde_json::json!({
+                                                "source_name": source_name,
+                                                "filters": filters,
+                                            });
+                                            let msg = SyncMessage::new(
+                                                SyncMessageType::FilterOrderUpdate,
+                                                SyncTargetType::Filter,
+                                                payload,
+                                            );
+                                            let _ = message_tx_clone.send(msg);
+                                        }
+                                        Err(e) => {
+                                            eprintln!("Failed to get filter list: {}", e);
+                                        }
+                                    }
+                                }
+                            });
+                        }
+                    }
+                    OBSEvent::InputVolumeChanged { input_name, volume_db } => {
+                        if targets.contains(&SyncTargetType::Audio) {
+                            let _ = message_tx.send(Self::audio_message(
+                                &input_name,
+                                serde_json::json!({ "volume_db": volume_db }),
+                            ));
+                        }
+                    }
+                    OBSEvent::InputMuteStateChanged { input_name, muted } => {
+                        if targets.contains(&SyncTargetType::Audio) {
+                            let _ = message_tx.send(Self::audio_message(
+                                &input_name,
+                                serde_json::json!({ "muted": muted }),
+                            ));
+                        }
+                    }
+                    OBSEvent::InputAudioBalanceChanged { input_name, balance } => {
+                        if targets.contains(&SyncTargetType::Audio) {
+                            let _ = message_tx.send(Self::audio_message(
+                                &input_name,
+                                serde_json::json!({ "balance": balance }),
+                            ));
+                        }
+                    }
+                    OBSEvent::InputAudioSyncOffsetChanged { input_name, sync_offset_ms } => {
+                        if targets.contains(&SyncTargetType::Audio) {
+                            let _ = message_tx.send(Self::audio_message(
+                                &input_name,
+                                serde_json::json!({ "sync_offset_ms": sync_offset_ms }),
+                            ));
+                        }
+                    }
+                    OBSEvent::InputAudioMonitorTypeChanged { input_name, monitor_type } => {
+                        if targets.contains(&SyncTargetType::Audio) {
+                            let _ = message_tx.send(Self::audio_message(
+                                &input_name,
+                                serde_json::json!({ "monitor_type": monitor_type }),
+                            ));
+                        }
+                    }
+                    OBSEvent::InputAudioTracksChanged { input_name, tracks } => {
+                        if targets.contains(&SyncTargetType::Audio) {
+                            let _ = message_tx.send(Self::audio_message(
+                                &input_name,
+                                serde_json::json!({ "tracks": tracks }),
+                            ));
+                        }
+                    }
+                    OBSEvent::StreamStateChanged { output_active, output_state } => {
+                        if targets.contains(&SyncTargetType::Outputs) {
+                            let _ = message_tx.send(Self::output_message("stream", output_active, &output_state));
+                        }
+                    }
+                    OBSEvent::RecordStateChanged { output_active, output_state } => {
+                        if targets.contains(&SyncTargetType::Outputs) {
+                            let _ = message_tx.send(Self::output_message("record", output_active, &output_state));
+                        }
+                    }
+                    OBSEvent::ReplayBufferStateChanged { output_active, output_state } => {
+                        if targets.contains(&SyncTargetType::Outputs) {
+                            let _ = message_tx.send(Self::output_message("replay_buffer", output_active, &output_state));
+                        }
+                    }
+                    OBSEvent::VirtualcamStateChanged { output_active, output_state } => {
+                        if targets.contains(&SyncTargetType::Outputs) {
+                            let _ = message_tx.send(Self::output_message("virtualcam", output_active, &output_state));
+                        }
+                    }
+                    OBSEvent::ReplayBufferSaved { saved_replay_path } => {
+                        if targets.contains(&SyncTargetType::Outputs) {
+                            println!("Replay saved to {}, asking slaves to save theirs", saved_replay_path);
+                            let msg = SyncMessage::new(
+                                SyncMessageType::ReplayBufferSave,
+                                SyncTargetType::Outputs,
+                                serde_json::json!({}),
+                            );
+                            let _ = message_tx.send(msg);
+                        }
+                    }
+                    OBSEvent::MediaInputPlaybackStarted { input_name }
+                    | OBSEvent::MediaInputPlaybackEnded { input_name } => {
+                        if targets.contains(&SyncTargetType::Media) {
+                            Self::spawn_media_update(&obs_client, &message_tx, input_name, None);
+                        }
+                    }
+                    OBSEvent::MediaInputActionTriggered { input_name, media_action } => {
+                        if targets.contains(&SyncTargetType::Media) {
+                            Self::spawn_media_update(&obs_client, &message_tx, input_name, Some(media_action));
+                        }
+                    }
+                    OBSEvent::CustomEvent { event_data } => {
+                        // Stream Deck and other WebSocket clients ask for a forward with
+                        // `{"obs_sync": "trigger_hotkey", "hotkey_name": ...}` or `"key_sequence"`.
+                        // The hotkey has already fired on the master; only slaves are triggered.
+                        if event_data["obs_sync"].as_str() == Some("trigger_hotkey") {
+                            let hotkey_name = event_data["hotkey_name"].as_str().map(String::from);
+                            let key_sequence = serde_json::from_value(event_data["key_sequence"].clone())
+                                .unwrap_or(None);
+                            Self::forward_hotkey(&hotkey_forwarding, &message_tx, hotkey_name, key_sequence).await;
+                        }
+                    }
+                    OBSEvent::InputSettingsChanged { input_name, settings } => {
+                        if targets.contains(&SyncTargetType::Source) {
+                            let input_kind = input_kinds.read().await.get(&input_name).cloned();
+
+                            // Fast path: text inputs whose event already carries the settings
+                            if let (Some(kind), Some(settings)) = (&input_kind, &settings) {
+                                if input_settings::is_text_kind(kind) {
+                                    Self::handle_text_settings(
+                                        &message_tx,
+                                        &text_snapshots,
+                                        &pending_text,
+                                        &text_notify,
+                                        &input_name,
+                                        kind,
+                                        settings,
+                                    ).await;
+                                    continue;
+                                }
+                            }
+
+                            let obs_client_clone = obs_client.clone();
+                            let message_tx_clone = message_tx.clone();
+                            let input_kinds_clone = input_kinds.clone();
+
+                            // Spawn task to fetch the full settings object
+                            tokio::spawn(async move {
+                                if let Some(msg) = Self::build_input_settings_message(
+                                    &obs_client_clone,
+                                    &input_kinds_clone,
+                                    &input_name,
+                                ).await {
+                                    let _ = message_tx_clone.send(msg);
+                                }
+                            });
+                        }
+                    }
                     _ => {}
                 }
             }
         });
     }
 
+    /// Re-read the current transition from OBS into the cache
+    async fn refresh_transition(
+        obs_client: &Arc<OBSClient>,
+        current_transition: &RwLock<Option<Value>>,
+    ) -> Option<Value> {
+        let client_arc = obs_client.get_client_arc();
+        let client_lock = client_arc.read().await;
+        let client = client_lock.as_ref()?;
+
+        match OBSCommands::get_current_transition(client).await {
+            Ok(transition) => {
+                *current_transition.write().await = Some(transition.clone());
+                Some(transition)
+            }
+            Err(e) => {
+                eprintln!("Failed to get current transition: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Refresh the transition cache and, if Program sync is active, send a `TransitionUpdate`
+    fn spawn_transition_update(
+        obs_client: &Arc<OBSClient>,
+        current_transition: &Arc<RwLock<Option<Value>>>,
+        message_tx: &mpsc::UnboundedSender<SyncMessage>,
+        send: bool,
+    ) {
+        let obs_client = obs_client.clone();
+        let current_transition = current_transition.clone();
+        let message_tx = message_tx.clone();
+
+        tokio::spawn(async move {
+            if let Some(transition) = Self::refresh_transition(&obs_client, &current_transition).await {
+                if send {
+                    let msg = SyncMessage::new(
+                        SyncMessageType::TransitionUpdate,
+                        SyncTargetType::Program,
+                        transition,
+                    );
+                    let _ = message_tx.send(msg);
+                }
+            }
+        });
+    }
+
+    /// Send the current scene collection and profile as `CollectionUpdate`
+    fn spawn_collection_update(
+        obs_client: &Arc<OBSClient>,
+        message_tx: &mpsc::UnboundedSender<SyncMessage>,
+    ) {
+        let obs_client = obs_client.clone();
+        let message_tx = message_tx.clone();
+
+        tokio::spawn(async move {
+            let client_arc = obs_client.get_client_arc();
+            let client_lock = client_arc.read().await;
+            let Some(client) = client_lock.as_ref() else {
+                return;
+            };
+
+            let result = async {
+                let (scene_collection, _) = OBSCommands::get_scene_collections(client).await?;
+                let (profile, _) = OBSCommands::get_profiles(client).await?;
+                Ok::<_, anyhow::Error>((scene_collection, profile))
+            }.await;
+
+            match result {
+                Ok((scene_collection, profile)) => {
+                    let payload = serde_json::json!({
+                        "scene_collection": scene_collection,
+                        "profile": profile,
+                    });
+                    let msg = SyncMessage::new(
+                        SyncMessageType::CollectionUpdate,
+                        SyncTargetType::Program,
+                        payload,
+                    );
+                    let _ = message_tx.send(msg);
+                }
+                Err(e) => {
+                    eprintln!("Failed to get scene collection and profile: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Read the playback state of a media input and send it as `MediaUpdate`,
+    /// so slaves can seek to the same cursor position
+    fn spawn_media_update(
+        obs_client: &Arc<OBSClient>,
+        message_tx: &mpsc::UnboundedSender<SyncMessage>,
+        input_name: String,
+        action: Option<String>,
+    ) {
+        let obs_client = obs_client.clone();
+        let message_tx = message_tx.clone();
+
+        tokio::spawn(async move {
+            let client_arc = obs_client.get_client_arc();
+            let client_lock = client_arc.read().await;
+            let Some(client) = client_lock.as_ref() else {
+                return;
+            };
+
+            match OBSCommands::get_media_input_status(client, &input_name).await {
+                Ok(mut payload) => {
+                    payload["input_name"] = serde_json::json!(input_name);
+                    payload["action"] = serde_json::json!(action);
+                    let msg = SyncMessage::new(
+                        SyncMessageType::MediaUpdate,
+                        SyncTargetType::Media,
+                        payload,
+                    );
+                    let _ = message_tx.send(msg);
+                }
+                Err(e) => {
+                    eprintln!("Failed to get media status: {}", e);
+                }
+            }
+        });
+    }
+
+    fn output_message(output: &str, active: bool, state: &str) -> SyncMessage {
+        let payload = serde_json::json!({
+            "output": output,
+            "active": active,
+            "state": state,
+        });
+        SyncMessage::new(SyncMessageType::OutputStateUpdate, SyncTargetType::Outputs, payload)
+    }
+
+    /// Build an `AudioUpdate` carrying only the changed mixer fields
+    fn audio_message(input_name: &str, fields: Value) -> SyncMessage {
+        let mut payload = fields;
+        payload["input_name"] = serde_json::json!(input_name);
+        SyncMessage::new(SyncMessageType::AudioUpdate, SyncTargetType::Audio, payload)
+    }
+
+    /// Periodically fingerprint the program scene so slaves can compare what they render
+    fn start_visual_check(&self) {
+        let message_tx = self.message_tx.clone();
+        let active_targets = self.active_targets.clone();
+        let obs_client = self.obs_client.clone();
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(tokio::time::Duration::from_millis(VISUAL_CHECK_INTERVAL_MS));
+            loop {
+                interval.tick().await;
+                if message_tx.is_closed() {
+                    break;
+                }
+                if !active_targets.read().await.contains(&SyncTargetType::Visual) {
+                    continue;
+                }
+
+                let client_arc = obs_client.get_client_arc();
+                let client_lock = client_arc.read().await;
+                let Some(client) = client_lock.as_ref() else {
+                    continue;
+                };
+
+                let scene_name = match client.scenes().current_program_scene().await {
+                    Ok(scene_name) => scene_name,
+                    Err(e) => {
+                        eprintln!("Failed to get current scene: {}", e);
+                        continue;
+                    }
+                };
+                match visual::scene_fingerprint(client, &scene_name).await {
+                    Ok(fingerprint) => {
+                        let msg = SyncMessage::new(
+                            SyncMessageType::VisualHash,
+                            SyncTargetType::Visual,
+                            serde_json::json!({
+                                "scene_name": scene_name,
+                                "hash": fingerprint.hash_hex(),
+                                "luma": fingerprint.luma,
+                                "captured_at": chrono::Utc::now().timestamp_millis(),
+                            }),
+                        );
+                        let _ = message_tx.send(msg);
+                    }
+                    Err(e) => {
+                        eprintln!("Failed to fingerprint program scene: {}", e);
+                    }
+                }
+            }
+        });
+    }
+
+    /// Flush coalesced text changes: the first change after a quiet period goes out
+    /// immediately, changes arriving within `TEXT_FLUSH_INTERVAL_MS` collapse to the latest value
+    fn start_text_flush(&self) {
+        let message_tx = self.message_tx.clone();
+        let pending_text = self.pending_text.clone();
+        let text_notify = self.text_notify.clone();
+
+        tokio::spawn(async move {
+            loop {
+                text_notify.notified().await;
+                if message_tx.is_closed() {
+                    break;
+                }
+
+                let pending: Vec<(String, PendingText)> = pending_text.lock().await.drain().collect();
+                for (input_name, pending) in pending {
+                    let payload = serde_json::json!({
+                        "input_name": input_name,
+                        "text": pending.text,
+                        "captured_at": pending.captured_at,
+                    });
+                    let msg = SyncMessage::new(
+                        SyncMessageType::TextUpdate,
+                        SyncTargetType::Source,
+                        payload,
+                    );
+                    let _ = message_tx.send(msg);
+                }
+
+                tokio::time::sleep(tokio::time::Duration::from_millis(TEXT_FLUSH_INTERVAL_MS)).await;
+            }
+        });
+    }
+
+    /// Route a text input settings event without fetching settings from OBS.
+    /// The `text` value is queued for the coalesced fast path; any other changed
+    /// key (font, color, ...) is sent as a regular `InputSettingsUpdate`.
+    async fn handle_text_settings(
+        message_tx: &mpsc::UnboundedSender<SyncMessage>,
+        text_snapshots: &RwLock<HashMap<String, Value>>,
+        pending_text: &Mutex<HashMap<String, PendingText>>,
+        text_notify: &Notify,
+        input_name: &str,
+        input_kind: &str,
+        settings: &Value,
+    ) {
+        let captured_at = chrono::Utc::now().timestamp_millis();
+
+        let mut rest = input_settings::filter_settings(input_kind, settings);
+        let text = rest
+            .as_object_mut()
+            .and_then(|map| map.remove("text"))
+            .and_then(|v| v.as_str().map(|s| s.to_string()));
+
+        let mut snapshots = text_snapshots.write().await;
+        if snapshots.get(input_name) != Some(&rest) {
+            let payload = serde_json::json!({
+                "input_name": input_name,
+                "input_kind": input_kind,
+                "settings": rest,
+            });
+            snapshots.insert(input_name.to_string(), rest);
+            let msg = SyncMessage::new(
+                SyncMessageType::InputSettingsUpdate,
+                SyncTargetType::Source,
+                payload,
+            );
+            let _ = message_tx.send(msg);
+        }
+        drop(snapshots);
+
+        if let Some(text) = text {
+            pending_text
+                .lock()
+                .await
+                .insert(input_name.to_string(), PendingText { text, captured_at });
+            text_notify.notify_one();
+        }
+    }
+
+    /// Build the sync message for an input whose settings changed.
+    /// Image sources are sent as `ImageUpdate` with the file contents, every other
+    /// kind as `InputSettingsUpdate` with machine-specific keys stripped.
+    async fn build_input_settings_message(
+        obs_client: &Arc<OBSClient>,
+        input_kinds: &RwLock<HashMap<String, String>>,
+        input_name: &str,
+    ) -> Option<SyncMessage> {
+        let client_arc = obs_client.get_client_arc();
+        let client_lock = client_arc.read().await;
+        let client = client_lock.as_ref()?;
+
+        let (input_kind, settings) = match OBSCommands::get_input_settings(client, input_name).await {
+            Ok(result) => result,
+            Err(e) => {
+                eprintln!("Failed to get input settings: {}", e);
+                return None;
+            }
+        };
+        drop(client_lock);
+
+        input_kinds
+            .write()
+            .await
+            .insert(input_name.to_string(), input_kind.clone());
+
+        if input_settings::is_image_kind(&input_kind) {
+            let file_path = settings
+                .get("file")
+                .and_then(|v| v.as_str())
+                .unwrap_or("");
+
+            // Read and encode image if file path exists
+            let image_data = if !file_path.is_empty() {
+                Self::read_and_encode_image(file_path).await
+            } else {
+                None
+            };
+
+            let payload = serde_json::json!({
+                "scene_name": "",
+                "source_name": input_name,
+                "file": file_path,
+                "image_data": image_data
+            });
+
+            return Some(SyncMessage::new(
+                SyncMessageType::ImageUpdate,
+                SyncTargetType::Source,
+                payload,
+            ));
+        }
+
+        let payload = serde_json::json!({
+            "input_name": input_name,
+            "input_kind": input_kind,
+            "settings": input_settings::filter_settings(&input_kind, &settings),
+        });
+
+        println!("Sending settings update for input {} ({})", input_name, input_kind);
+        Some(SyncMessage::new(
+            SyncMessageType::InputSettingsUpdate,
+            SyncTargetType::Source,
+            payload,
+        ))
+    }
+
     pub fn send_heartbeat(&self) -> Result<()> {
         self.message_tx.send(SyncMessage::heartbeat())?;
         Ok(())
@@ -201,136 +1167,380 @@ impl MasterSync {
         }
     }
 
-    /// Get image source settings from OBS and encode the file
-    pub async fn get_image_data_for_source(
-        &self,
-        input_name: &str,
-    ) -> Option<(String, String)> {
-        let client_arc = self.obs_client.get_client_arc();
-        let client_lock = client_arc.read().await;
-        
-        if let Some(client) = client_lock.as_ref() {
-            // Get input settings to find the file path
-            match client.inputs().settings::<serde_json::Value>(input_name).await {
-                Ok(settings) => {
-                    // Try to get file path from settings
-                    if let Some(file_path) = settings.settings.get("file").and_then(|v| v.as_str()) {
-                        println!("Found image file for {}: {}", input_name, file_path);
-                        
-                        // Read and encode the image
-                        if let Some(encoded_data) = Self::read_and_encode_image(file_path).await {
-                            return Some((file_path.to_string(), encoded_data));
-                        }
-                    } else {
-                        println!("No file path found in settings for {}", input_name);
-                    }
+    /// Describe one scene item the way `StateSync` and `SceneItemCreated` carry it:
+    /// transform, visibility, lock and z-order, plus the input kind and settings
+    /// (or image contents) a slave needs to recreate the input
+    async fn collect_item_data(
+        client: &obws::Client,
+        input_kinds: &RwLock<HashMap<String, String>>,
+        scene_name: &str,
+        source_name: &str,
+        scene_item_id: i64,
+        index: u32,
+        input_kind: Option<String>,
+    ) -> Value {
+        // Nested scenes and groups have no input settings; when the kind isn't
+        // known yet a failing settings request tells them apart from inputs
+        let is_input = !matches!(input_kind.as_deref(), Some("scene") | Some("group"));
+
+        let (transform, enabled, locked, settings) = futures::join!(
+            async { client.scene_items().transform(scene_name, scene_item_id).await },
+            OBSCommands::get_scene_item_enabled(client, scene_name, scene_item_id),
+            OBSCommands::get_scene_item_locked(client, scene_name, scene_item_id),
+            async {
+                if is_input {
+                    Some(OBSCommands::get_input_settings(client, source_name).await)
+                } else {
+                    None
                 }
-                Err(e) => {
-                    eprintln!("Failed to get settings for {}: {}", input_name, e);
+            },
+        );
+
+        let transform = match transform {
+            Ok(t) => Some(serde_json::json!(SceneItemTransform::from(&t))),
+            Err(e) => {
+                eprintln!("Failed to get transform for {}: {}", source_name, e);
+                None
+            }
+        };
+
+        let enabled = enabled.ok();
+        let locked = locked.ok();
+
+        let settings = match settings {
+            Some(Ok(result)) => Some(result),
+            Some(Err(e)) => {
+                if input_kind.is_some() {
+                    eprintln!("Failed to get settings for {}: {}", source_name, e);
                 }
+                None
+            }
+            None => None,
+        };
+
+        let source_type = match (input_kind, settings.as_ref()) {
+            (Some(kind), _) => kind,
+            (None, Some((kind, _))) => kind.clone(),
+            (None, None) => {
+                let is_scene = OBSCommands::get_scene_names(client)
+                    .await
+                    .map(|names| names.iter().any(|name| name == source_name))
+                    .unwrap_or(false);
+                if is_scene { "scene" } else { "unknown" }.to_string()
             }
+        };
+
+        if let Some((kind, _)) = settings.as_ref() {
+            input_kinds
+                .write()
+                .await
+                .insert(source_name.to_string(), kind.clone());
         }
-        
-        None
+
+        // Image sources carry the file contents, other inputs their full settings object
+        let (image_data, input_settings) = match settings {
+            Some((kind, settings)) if input_settings::is_image_kind(&kind) => {
+                let file_path = settings.get("file").and_then(|v| v.as_str()).unwrap_or("");
+                let image_data = if !file_path.is_empty() {
+                    Self::read_and_encode_image(file_path).await.map(|data| serde_json::json!({
+                        "file": file_path,
+                        "data": data
+                    }))
+                } else {
+                    None
+                };
+                (image_data, None)
+            }
+            Some((kind, settings)) => (None, Some(input_settings::filter_settings(&kind, &settings))),
+            None => (None, None),
+        };
+
+        serde_json::json!({
+            "source_name": source_name,
+            "scene_item_id": scene_item_id,
+            "source_type": source_type,
+            "transform": transform,
+            "image_data": image_data,
+            "input_settings": input_settings,
+            "enabled": enabled,
+            "locked": locked,
+            "index": index,
+        })
     }
 
-    /// Send initial state to newly connected slave
+    /// Item data of every `(scene, item)` pair, collected with bounded concurrency in order
+    async fn collect_items_data(
+        client: &obws::Client,
+        input_kinds: &RwLock<HashMap<String, String>>,
+        items: Vec<(&str, &SceneItem)>,
+    ) -> Vec<Value> {
+        buffered_requests(items.into_iter().map(|(scene_name, item)| {
+            Self::collect_item_data(
+                client,
+                input_kinds,
+                scene_name,
+                &item.source_name,
+                item.id,
+                item.index,
+                Some(OBSCommands::scene_item_kind(item)),
+            )
+        }))
+        .await
+    }
+
+    /// Broadcast the complete state to every slave
     pub async fn send_initial_state(&self) -> Result<()> {
-        println!("Collecting full OBS state for new slave...");
-        let client_arc = self.obs_client.get_client_arc();
-        let client_lock = client_arc.read().await;
-        
-        if let Some(client) = client_lock.as_ref() {
+        if let Some(msg) = self.collect_initial_state().await? {
+            self.message_tx.send(msg)?;
+        }
+        Ok(())
+    }
+
+    /// Complete state as a `StateSync` message, for a newly connected or
+    /// resyncing slave. `None` when OBS is not connected.
+    pub async fn collect_initial_state(&self) -> Result<Option<SyncMessage>> {
+        println!("Collecting full OBS state for slave...");
+        let started = Instant::now();
+        // The many requests below must not hold the client lock, or a
+        // reconnect would wait for the whole collection
+        let client = self.obs_client.client().await;
+
+        if let Some(client) = client.as_deref() {
+            let (
+                current_program_scene,
+                current_preview_scene,
+                studio_mode_enabled,
+                transition,
+                scene_collections,
+                profiles,
+                video_settings,
+                scenes_list,
+            ) = futures::join!(
+                async { client.scenes().current_program_scene().await },
+                async { client.scenes().current_preview_scene().await },
+                OBSCommands::get_studio_mode_enabled(client),
+                OBSCommands::get_current_transition(client),
+                OBSCommands::get_scene_collections(client),
+                OBSCommands::get_profiles(client),
+                async { client.config().video_settings().await },
+                async { client.scenes().list().await },
+            );
+
             // Get current program scene
-            let current_program_scene = match client.scenes().current_program_scene().await {
+            let current_program_scene = match current_program_scene {
                 Ok(scene) => scene,
                 Err(e) => {
                     eprintln!("Failed to get current scene: {}", e);
-                    return Ok(());
+                    return Ok(None);
                 }
             };
 
-            // Get preview scene if in studio mode
-            let current_preview_scene = client.scenes().current_preview_scene().await.ok();
+            // Preview scene only exists in studio mode
+            let current_preview_scene = current_preview_scene.ok();
+            let studio_mode_enabled = studio_mode_enabled.ok();
+            let transition = transition.ok();
+            let scene_collection = scene_collections.ok().map(|(current, _)| current);
+            let profile = profiles.ok().map(|(current, _)| current);
+            let canvas = video_settings.ok().map(|video| {
+                serde_json::json!({
+                    "base_width": video.base_width,
+                    "base_height": video.base_height,
+                    "output_width": video.output_width,
+                    "output_height": video.output_height,
+                    "fps_numerator": video.fps_numerator,
+                    "fps_denominator": video.fps_denominator,
+                })
+            });
 
             // Get all scenes
-            let scenes_list = match client.scenes().list().await {
-                Ok(scenes) => scenes,
+            let scene_names: Vec<String> = match scenes_list {
+                Ok(scenes) => scenes.scenes.into_iter().map(|scene| scene.name).collect(),
                 Err(e) => {
                     eprintln!("Failed to get scenes list: {}", e);
-                    return Ok(());
+                    return Ok(None);
                 }
             };
 
+            let active_targets = self.active_targets.read().await.clone();
+
+            // Item lists of every scene, then of every group found in them
+            let scene_items: Vec<(&str, Vec<SceneItem>)> =
+                buffered_requests(scene_names.iter().map(|scene_name| async move {
+                    match client.scene_items().list(scene_name).await {
+                        Ok(items) => Some((scene_name.as_str(), items)),
+                        Err(e) => {
+                            eprintln!("Failed to get items for scene {}: {}", scene_name, e);
+                            None
+                        }
+                    }
+                }))
+                .await
+                .into_iter()
+                .flatten()
+                .collect();
+
+            let group_names: Vec<&str> = scene_items
+                .iter()
+                .flat_map(|(_, items)| items.iter())
+                .filter(|item| item.is_group == Some(true))
+                .map(|item| item.source_name.as_str())
+                .collect();
+            // Groups carry their children; nested scenes are listed as scenes of their own
+            let group_items: Vec<(&str, Vec<SceneItem>)> =
+                buffered_requests(group_names.into_iter().map(|group_name| async move {
+                    match client.scene_items().list_group(group_name).await {
+                        Ok(items) => Some((group_name, items)),
+                        Err(e) => {
+                            eprintln!("Failed to get items of group {}: {}", group_name, e);
+                            None
+                        }
+                    }
+                }))
+                .await
+                .into_iter()
+                .flatten()
+                .collect();
+
+            // Per-item state of all scenes and groups in one bounded batch
+            let containers: Vec<&(&str, Vec<SceneItem>)> = scene_items.iter().chain(group_items.iter()).collect();
+            let all_items: Vec<(&str, &SceneItem)> = containers
+                .iter()
+                .flat_map(|(name, items)| items.iter().map(move |item| (*name, item)))
+                .collect();
+            let item_count = all_items.len();
+            let mut items_data = Self::collect_items_data(client, &self.input_kinds, all_items)
+                .await
+                .into_iter();
+            let mut collected: HashMap<&str, Vec<Value>> = HashMap::new();
+            for (name, items) in &containers {
+                collected.insert(*name, items_data.by_ref().take(items.len()).collect());
+            }
+
             let mut scenes_data = Vec::new();
-            
-            // For each scene, get all items
-            for scene in scenes_list.scenes {
-                println!("Processing scene: {}", scene.name);
-                
-                match client.scene_items().list(&scene.name).await {
-                    Ok(items) => {
-                        let mut scene_items_data = Vec::new();
-                        
-                        for item in items {
-                            println!("  - Item: {} (id: {})", item.source_name, item.id);
-                            
-                            // Get transform for this item
-                            let transform = match client.scene_items().transform(&scene.name, item.id).await {
-                                Ok(t) => Some(serde_json::json!({
-                                    "position_x": t.position_x,
-                                    "position_y": t.position_y,
-                                    "rotation": t.rotation,
-                                    "scale_x": t.scale_x,
-                                    "scale_y": t.scale_y,
-                                    "width": t.width,
-                                    "height": t.height,
-                                })),
-                                Err(e) => {
-                                    eprintln!("Failed to get transform for {}: {}", item.source_name, e);
-                                    None
-                                }
-                            };
-
-                            // Get source type from item
-                            let source_type = item.input_kind.clone().unwrap_or_else(|| "unknown".to_string());
-
-                            // If it's an image source, get the image data
-                            let image_data = if source_type.contains("image") {
-                                self.get_image_data_for_source(&item.source_name).await
-                                    .map(|(path, data)| serde_json::json!({
-                                        "file": path,
-                                        "data": data
-                                    }))
-                            } else {
-                                None
-                            };
-
-                            scene_items_data.push(serde_json::json!({
-                                "source_name": item.source_name,
-                                "scene_item_id": item.id,
-                                "source_type": source_type,
-                                "transform": transform,
-                                "image_data": image_data,
-                            }));
-                        }
-                        
-                        scenes_data.push(serde_json::json!({
-                            "name": scene.name,
-                            "items": scene_items_data,
-                        }));
+            for (scene_name, _) in &scene_items {
+                let mut items = collected.remove(scene_name).unwrap_or_default();
+                for item in items.iter_mut().filter(|item| item["source_type"] == "group") {
+                    let group_name = item["source_name"].as_str().unwrap_or("").to_string();
+                    if let Some(children) = collected.get(group_name.as_str()) {
+                        item["children"] = Value::Array(children.clone());
+                    }
+                }
+                scenes_data.push(serde_json::json!({
+                    "name": scene_name,
+                    "items": items,
+                }));
+            }
+
+            // Filter lists per source, collected once even if a source is used in several scenes
+            let source_filters = if active_targets.contains(&SyncTargetType::Filter) {
+                let mut source_names: Vec<&str> = scene_names.iter().map(String::as_str).collect();
+                source_names.extend(
+                    containers
+                        .iter()
+                        .flat_map(|(_, items)| items.iter().map(|item| item.source_name.as_str())),
+                );
+                source_names.sort_unstable();
+                source_names.dedup();
+
+                let filters: Vec<(&str, Result<Vec<Value>>)> =
+                    buffered_requests(source_names.into_iter().map(|source_name| async move {
+                        (source_name, OBSCommands::get_source_filters(client, source_name).await)
+                    }))
+                    .await;
+
+                let mut source_filters = serde_json::Map::new();
+                for (source_name, result) in filters {
+                    match result {
+                        Ok(filters) => {
+                            source_filters.insert(source_name.to_string(), Value::Array(filters));
+                        }
+                        Err(e) => {
+                            eprintln!("Failed to collect filters: {}", e);
+                        }
                     }
+                }
+                Some(source_filters)
+            } else {
+                None
+            };
+
+            // Inputs are listed once for the audio and media sections
+            let sync_audio = active_targets.contains(&SyncTargetType::Audio);
+            let sync_media = active_targets.contains(&SyncTargetType::Media);
+            let input_names: Vec<String> = if sync_audio || sync_media {
+                match client.inputs().list(None).await {
+                    Ok(inputs) => inputs.into_iter().map(|input| input.name).collect(),
                     Err(e) => {
-                        eprintln!("Failed to get items for scene {}: {}", scene.name, e);
+                        eprintln!("Failed to get inputs list: {}", e);
+                        Vec::new()
                     }
                 }
-            }
+            } else {
+                Vec::new()
+            };
+
+            // Mixer state of every input that has audio
+            let audio_inputs = if sync_audio {
+                let states: Vec<(&String, Result<Value>)> =
+                    buffered_requests(input_names.iter().map(|input_name| async move {
+                        (input_name, OBSCommands::get_input_audio_state(client, input_name).await)
+                    }))
+                    .await;
+                // Inputs without audio fail the volume request and are skipped
+                Some(
+                    states
+                        .into_iter()
+                        .filter_map(|(input_name, state)| state.ok().map(|state| (input_name.clone(), state)))
+                        .collect::<serde_json::Map<String, Value>>(),
+                )
+            } else {
+                None
+            };
+
+            // Playback state of every media input
+            let media_inputs = if sync_media {
+                let statuses: Vec<(&String, Result<Value>)> =
+                    buffered_requests(input_names.iter().map(|input_name| async move {
+                        (input_name, OBSCommands::get_media_input_status(client, input_name).await)
+                    }))
+                    .await;
+                // Inputs that are not media fail the status request and are skipped
+                Some(
+                    statuses
+                        .into_iter()
+                        .filter_map(|(input_name, status)| status.ok().map(|status| (input_name.clone(), status)))
+                        .collect::<serde_json::Map<String, Value>>(),
+                )
+            } else {
+                None
+            };
+
+            let outputs = if active_targets.contains(&SyncTargetType::Outputs) {
+                match OBSCommands::get_output_status(client).await {
+                    Ok(outputs) => Some(outputs),
+                    Err(e) => {
+                        eprintln!("Failed to get output status: {}", e);
+                        None
+                    }
+                }
+            } else {
+                None
+            };
 
             // Create comprehensive initial state payload
             let payload = serde_json::json!({
+                "scene_collection": scene_collection,
+                "profile": profile,
+                "canvas": canvas,
                 "current_program_scene": current_program_scene,
                 "current_preview_scene": current_preview_scene,
+                "studio_mode_enabled": studio_mode_enabled,
+                "transition": transition,
                 "scenes": scenes_data,
+                "source_filters": source_filters,
+                "audio_inputs": audio_inputs,
+                "media_inputs": media_inputs,
+                "outputs": outputs,
             });
 
             let msg = SyncMessage::new(
@@ -339,10 +1549,71 @@ impl MasterSync {
                 payload,
             );
 
-            self.message_tx.send(msg)?;
-            println!("✓ Sent complete initial state to slave ({} scenes)", scenes_data.len());
+            println!(
+                "✓ Collected complete state ({} scenes, {} items, in {} ms)",
+                scenes_data.len(),
+                item_count,
+                started.elapsed().as_millis()
+            );
+            return Ok(Some(msg));
         }
 
-        Ok(())
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::obs::events::OBSEventHandler;
+    use crate::obs::fake::FakeObs;
+    use serde_json::json;
+    use std::time::Duration;
+
+    /// Next broadcast message of `message_type`, skipping the others
+    async fn next_message(rx: &mut mpsc::UnboundedReceiver<SyncMessage>, message_type: SyncMessageType) -> SyncMessage {
+        let wait = async {
+            loop {
+                let message = rx.recv().await.unwrap();
+                if message.message_type == message_type {
+                    return message;
+                }
+            }
+        };
+        tokio::time::timeout(Duration::from_secs(5), wait)
+            .await
+            .unwrap_or_else(|_| panic!("no {:?} was broadcast", message_type))
+    }
+
+    #[tokio::test]
+    async fn obs_events_are_broadcast() {
+        let mut obs = FakeObs::start().await;
+        let obs_client = Arc::new(OBSClient::new());
+        obs_client.connect(obs.config()).await.unwrap();
+
+        let (master, mut sync_rx) = MasterSync::new(obs_client.clone(), Arc::new(RwLock::new(HotkeyForwarding::default())));
+        let master = Arc::new(master);
+        master
+            .set_active_targets(vec![SyncTargetType::Program, SyncTargetType::Filter])
+            .await
+            .unwrap();
+        let (event_handler, event_rx) = OBSEventHandler::new();
+        event_handler.start_listening(&obs_client).await.unwrap();
+        master.start_monitoring(event_rx).await;
+        // obws' connection and the one for settings events
+        obs.wait_identified(2).await;
+
+        obs.send_event("CurrentProgramSceneChanged", json!({ "sceneName": "Live" }));
+        let message = next_message(&mut sync_rx, SyncMessageType::SceneChange).await;
+        assert_eq!(message.payload["scene_name"], "Live");
+
+        // Unknown to obws, picked up by the settings event connection
+        obs.send_event(
+            "SourceFilterSettingsChanged",
+            json!({ "sourceName": "Camera", "filterName": "Color", "filterSettings": { "gamma": 0.5 } }),
+        );
+        let message = next_message(&mut sync_rx, SyncMessageType::FilterSettingsUpdate).await;
+        assert_eq!(message.payload["source_name"], "Camera");
+        assert_eq!(message.payload["settings"]["gamma"], 0.5);
     }
 }
@@ -2,3 +2,6 @@ pub mod protocol;
 pub mod master;
 pub mod slave;
 pub mod diff;
+pub mod input_settings;
+pub mod capabilities;
+pub mod visual;
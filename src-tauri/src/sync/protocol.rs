@@ -7,7 +7,33 @@ pub enum SyncMessageType {
     SourceUpdate,
     TransformUpdate,
     SceneChange,
+    SceneCreated,
+    SceneRemoved,
+    SceneRenamed,
     ImageUpdate,
+    InputSettingsUpdate,
+    TextUpdate,
+    SceneItemCreated,
+    SceneItemEnabledUpdate,
+    SceneItemLockedUpdate,
+    SceneItemOrderUpdate,
+    FilterCreated,
+    FilterRemoved,
+    FilterRenamed,
+    FilterOrderUpdate,
+    FilterSettingsUpdate,
+    FilterEnabledUpdate,
+    AudioUpdate,
+    StudioModeUpdate,
+    TransitionUpdate,
+    CollectionUpdate,
+    MediaUpdate,
+    OutputStateUpdate,
+    ReplayBufferSave,
+    OutputStatus,      // Slave reports its output state to Master
+    TriggerHotkey,
+    VisualHash,
+    BulkChunk,         // Part of a large message, reassembled by the slave connection
     Heartbeat,
     StateSync,
     StateSyncRequest,  // Slave requests initial state from Master
@@ -19,6 +45,12 @@ pub enum SyncTargetType {
     Source,
     Preview,
     Program,
+    Filter,
+    Audio,
+    Media,
+    Outputs,
+    Hotkeys,
+    Visual,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -75,9 +107,54 @@ pub struct TransformData {
     pub height: f64,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SceneItemEnabledPayload {
+    pub scene_name: String,
+    pub scene_item_id: i64,
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SceneItemLockedPayload {
+    pub scene_name: String,
+    pub scene_item_id: i64,
+    pub locked: bool,
+}
+
+/// Full z-order of a scene; index 0 is the bottom-most item
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SceneItemOrderPayload {
+    pub scene_name: String,
+    pub items: Vec<SceneItemIndexData>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SceneItemIndexData {
+    pub scene_item_id: i64,
+    pub source_name: String,
+    pub index: u32,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SceneChangePayload {
     pub scene_name: String,
+    /// Transition the master used, so slaves switch the same way
+    pub transition: Option<TransitionData>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransitionData {
+    pub name: String,
+    pub kind: String,
+    /// Fixed-duration transitions (e.g. Cut, Stinger) ignore `duration_ms`
+    pub fixed: bool,
+    pub duration_ms: Option<i64>,
+    pub settings: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StudioModePayload {
+    pub enabled: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -91,11 +168,214 @@ pub struct ImageUpdatePayload {
     pub height: Option<f64>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InputSettingsUpdatePayload {
+    pub input_name: String,
+    pub input_kind: String,
+    /// Full settings object with machine-specific keys removed, applied with overlay
+    pub settings: Value,
+}
+
+/// Coalesced text content change for text sources (low-latency path)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TextUpdatePayload {
+    pub input_name: String,
+    pub text: String,
+    /// Master wall clock (ms) when the change was observed, used for latency measurement
+    pub captured_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FilterData {
+    pub name: String,
+    pub kind: String,
+    pub index: u32,
+    pub enabled: bool,
+    pub settings: Value,
+}
+
+/// Payload for `FilterCreated`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FilterCreatedPayload {
+    pub source_name: String,
+    pub filter: FilterData,
+}
+
+/// Payload for `FilterRemoved`, `FilterRenamed`, `FilterSettingsUpdate` and `FilterEnabledUpdate`;
+/// only the fields relevant to the message type are set
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FilterUpdatePayload {
+    pub source_name: String,
+    pub filter_name: String,
+    pub new_filter_name: Option<String>,
+    pub settings: Option<Value>,
+    pub enabled: Option<bool>,
+}
+
+/// Payload for `FilterOrderUpdate`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FilterOrderPayload {
+    pub source_name: String,
+    pub filters: Vec<FilterData>,
+}
+
+/// Mixer state of one input. In `AudioUpdate` only the changed fields are set;
+/// in `StateSync` all of them are.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AudioStateData {
+    pub volume_db: Option<f64>,
+    pub muted: Option<bool>,
+    /// Stereo balance, 0.0 (left) to 1.0 (right)
+    pub balance: Option<f64>,
+    pub sync_offset_ms: Option<i64>,
+    /// `none`, `monitor_only` or `monitor_and_output`
+    pub monitor_type: Option<String>,
+    /// Track routing for tracks 1-6
+    pub tracks: Option<Vec<bool>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AudioUpdatePayload {
+    pub input_name: String,
+    #[serde(flatten)]
+    pub state: AudioStateData,
+}
+
+/// Payload for `CollectionUpdate`, sent when the master switches scene collection or profile
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CollectionPayload {
+    pub scene_collection: String,
+    pub profile: String,
+}
+
+/// Payload for `OutputStateUpdate`. `output` is `stream`, `record`,
+/// `replay_buffer` or `virtualcam`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutputStatePayload {
+    pub output: String,
+    pub active: bool,
+    /// `starting`, `started`, `stopping`, `stopped`, `paused`, `resumed`, ...
+    pub state: String,
+}
+
+/// An OBS key sequence. `key` is an OBS key id such as `OBS_KEY_F13`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeySequence {
+    pub key: String,
+    #[serde(default)]
+    pub shift: bool,
+    #[serde(default)]
+    pub control: bool,
+    #[serde(default)]
+    pub alt: bool,
+    #[serde(default)]
+    pub command: bool,
+}
+
+/// Payload for `TriggerHotkey`; exactly one of the two fields is set
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TriggerHotkeyPayload {
+    pub hotkey_name: Option<String>,
+    pub key_sequence: Option<KeySequence>,
+}
+
+/// Payload for `VisualHash`: fingerprint of the master's program scene
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VisualHashPayload {
+    pub scene_name: String,
+    /// 64-bit difference hash as 16 hex digits (JSON numbers lose precision above 2^53)
+    pub hash: String,
+    /// Mean luma, 0-255
+    pub luma: u8,
+    pub captured_at: i64,
+}
+
+/// Payload for `BulkChunk`: a slice of another serialized `SyncMessage`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkChunkPayload {
+    /// Unique per master; chunks of one transfer arrive in order
+    pub transfer_id: u64,
+    pub index: u32,
+    pub last: bool,
+    pub data: String,
+}
+
+/// Payload for `OutputStatus`, sent from a slave to the master
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutputStatusPayload {
+    /// Slave-local OBS instance the report is for
+    pub instance: String,
+    /// Same layout as `OBSCommands::get_output_status`
+    pub outputs: Value,
+}
+
+/// Payload for `SceneItemCreated`. The item carries everything a slave needs to
+/// provision the input if it does not exist locally.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SceneItemCreatedPayload {
+    pub scene_name: String,
+    pub item: SceneItemData,
+}
+
+/// Payload for `SceneCreated`, `SceneRemoved` and `SceneRenamed`;
+/// `new_scene_name` is only set for `SceneRenamed`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SceneListPayload {
+    pub scene_name: String,
+    pub new_scene_name: Option<String>,
+}
+
+/// Playback state of one media input
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MediaStateData {
+    /// `playing`, `paused`, `stopped`, `ended`, ... (see `OBSCommands::media_state_to_str`)
+    pub state: String,
+    pub cursor_ms: Option<i64>,
+    pub duration_ms: Option<i64>,
+    /// Master wall clock time (ms) at which the cursor was read
+    pub captured_at: i64,
+}
+
+/// Payload for `MediaUpdate`. `action` is set when the operator triggered one
+/// (`play`, `pause`, `stop`, `restart`, `next`, `previous`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MediaUpdatePayload {
+    pub input_name: String,
+    pub action: Option<String>,
+    #[serde(flatten)]
+    pub state: MediaStateData,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CanvasData {
+    pub base_width: u32,
+    pub base_height: u32,
+    pub output_width: u32,
+    pub output_height: u32,
+    pub fps_numerator: u32,
+    pub fps_denominator: u32,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StateSyncPayload {
+    /// Scene collection and profile the rest of the state belongs to
+    pub scene_collection: Option<String>,
+    pub profile: Option<String>,
+    /// Master canvas; transforms only line up on slaves with the same base resolution
+    pub canvas: Option<CanvasData>,
     pub current_program_scene: String,
     pub current_preview_scene: Option<String>,
+    pub studio_mode_enabled: Option<bool>,
+    pub transition: Option<TransitionData>,
     pub scenes: Vec<SceneData>,
+    /// Filter lists keyed by source (inputs and scenes); only present with the Filter target
+    pub source_filters: Option<std::collections::HashMap<String, Vec<FilterData>>>,
+    /// Mixer state keyed by input name; only present with the Audio target
+    pub audio_inputs: Option<std::collections::HashMap<String, AudioStateData>>,
+    /// Active state per output; only present with the Outputs target
+    pub outputs: Option<Value>,
+    /// Playback state keyed by input name; only present with the Media target
+    pub media_inputs: Option<std::collections::HashMap<String, MediaStateData>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -107,7 +387,19 @@ pub struct SceneData {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SceneItemData {
     pub source_name: String,
+    pub scene_item_id: i64,
+    /// Input kind, or `scene` / `group` for nested scenes and groups
     pub source_type: String,
+    pub transform: Option<Value>,
     /// Base64 encoded image data for image sources
     pub image_data: Option<String>,
+    /// Input settings for non-image inputs, with machine-specific keys removed
+    pub input_settings: Option<Value>,
+    pub enabled: Option<bool>,
+    pub locked: Option<bool>,
+    /// Z-order position in the scene (0 = bottom)
+    pub index: u32,
+    /// Items inside a group, addressed with the group name as scene name.
+    /// Nested scenes are not expanded; their items are listed under their own `SceneData`.
+    pub children: Option<Vec<SceneItemData>>,
 }
@@ -1,16 +1,40 @@
-use super::protocol::{SyncMessage, SyncMessageType};
+use super::protocol::{KeySequence, SyncMessage, SyncMessageType, SyncTargetType};
+use super::capabilities;
 use super::diff::{DiffDetector, DiffSeverity};
-use crate::obs::{commands::OBSCommands, OBSClient};
+use super::input_settings;
+use super::visual::{self, VisualFingerprint};
+use crate::obs::commands::{buffered_requests, OBSCommands, SceneItemTransform};
+use crate::obs::{client::OBSConnectionStatus, OBSClient};
 use anyhow::{Context, Result};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::{mpsc, RwLock};
 use tokio::fs;
 
+/// Local state collections slower than this are logged
+const SLOW_STATE_COLLECTION: Duration = Duration::from_millis(500);
+
+/// Fingerprint distance (out of 64 hash bits plus brightness) tolerated between
+/// master and slave program output; scaling and encoding noise stay well below it
+const VISUAL_DISTANCE_THRESHOLD: u32 = 12;
+/// How long the program output must keep differing before an alert is raised
+const VISUAL_MISMATCH_SUSTAIN_MS: i64 = 6000;
+
+/// Ongoing visual mismatch with the master's program output
+struct VisualMismatch {
+    since: i64,
+    alerted: bool,
+}
+
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DesyncAlert {
     pub id: String,
     pub timestamp: i64,
+    /// Local OBS instance the alert belongs to
+    pub instance: String,
     pub scene_name: String,
     pub source_name: String,
     pub message: String,
@@ -24,20 +48,139 @@ pub enum AlertSeverity {
     Error,
 }
 
+/// Slave-side behaviour options, editable from the UI while connected
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SlaveSyncSettings {
+    /// Turn studio mode on when a preview scene arrives and it is off locally
+    pub enable_studio_mode_for_preview: bool,
+    /// Follow the master when it turns studio mode on or off
+    pub mirror_studio_mode: bool,
+    /// Create and rename local scenes to match the master's scene list
+    pub mirror_scene_list: bool,
+    /// Also remove scenes the master removes. Off by default, as it cannot be undone.
+    pub allow_scene_removal: bool,
+    /// Create inputs and scene items that exist on the master but not locally
+    pub provision_missing_sources: bool,
+    /// Switch to the master's scene collection when a same-named one exists locally
+    pub follow_scene_collection: bool,
+    /// Switch to the master's profile when a same-named one exists locally
+    pub follow_profile: bool,
+    /// Start and stop outputs in lock step with the master, per output
+    pub mirror_stream: bool,
+    pub mirror_record: bool,
+    pub mirror_replay_buffer: bool,
+    pub mirror_virtualcam: bool,
+    /// Compare program screenshots with the master and alert on sustained differences
+    pub verify_program_visually: bool,
+}
+
+impl SlaveSyncSettings {
+    /// Whether the output with this sync name follows the master
+    pub fn mirrors_output(&self, output: &str) -> bool {
+        match output {
+            "stream" => self.mirror_stream,
+            "record" => self.mirror_record,
+            "replay_buffer" => self.mirror_replay_buffer,
+            "virtualcam" => self.mirror_virtualcam,
+            _ => false,
+        }
+    }
+
+    fn mirrors_any_output(&self) -> bool {
+        self.mirror_stream || self.mirror_record || self.mirror_replay_buffer || self.mirror_virtualcam
+    }
+}
+
+/// End-to-end latency of text updates, from master capture to slave apply.
+/// Relies on master and slave wall clocks being in sync (NTP); samples are
+/// kept as measured, so a slave clock running behind shows up as negative
+/// latency and in `clock_skew_ms` instead of being hidden.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextLatencyStats {
+    pub samples: u64,
+    pub last_ms: i64,
+    pub average_ms: f64,
+    pub min_ms: i64,
+    pub max_ms: i64,
+    /// At least this much the slave clock is behind the master's (0 when
+    /// no sample was negative)
+    pub clock_skew_ms: i64,
+}
+
+impl TextLatencyStats {
+    /// Weight of the newest sample in the moving average
+    const SMOOTHING: f64 = 0.1;
+
+    fn record(&mut self, latency_ms: i64) {
+        if self.samples == 0 {
+            self.average_ms = latency_ms as f64;
+            self.min_ms = latency_ms;
+            self.max_ms = latency_ms;
+        } else {
+            self.average_ms += (latency_ms as f64 - self.average_ms) * Self::SMOOTHING;
+            self.min_ms = self.min_ms.min(latency_ms);
+            self.max_ms = self.max_ms.max(latency_ms);
+        }
+        self.samples += 1;
+        self.last_ms = latency_ms;
+        self.clock_skew_ms = -self.min_ms.min(0);
+    }
+}
+
 pub struct SlaveSync {
+    /// Name of the local OBS instance this sync drives
+    instance: String,
     obs_client: Arc<OBSClient>,
     alert_tx: mpsc::UnboundedSender<DesyncAlert>,
+    /// Messages back to the master (output status reports)
+    report_tx: mpsc::UnboundedSender<SyncMessage>,
     expected_state: Arc<RwLock<serde_json::Value>>,
+    text_latency: Arc<RwLock<TextLatencyStats>>,
+    settings: Arc<RwLock<SlaveSyncSettings>>,
+    /// Last transition applied locally, to skip redundant requests on every cut
+    applied_transition: Arc<RwLock<Option<serde_json::Value>>>,
+    /// Master timestamp and scene of the latest program cut. A cut may be
+    /// sent ahead of a large full state collected before it, which must not
+    /// switch back to its older scene.
+    last_cut: Arc<RwLock<Option<(i64, String)>>>,
+    /// Local ids of provisioned scene items, keyed by scene and master scene item id
+    item_id_map: Arc<RwLock<HashMap<(String, i64), i64>>>,
+    /// Local OBS capabilities, read on the first message of each OBS
+    /// connection and keyed by its connection id
+    capabilities: Arc<RwLock<Option<(u64, OBSConnectionStatus)>>>,
+    /// Targets already reported as unsupported on the current connection, so
+    /// the alert is raised once
+    unsupported_targets: Arc<RwLock<Vec<SyncTargetType>>>,
+    visual_mismatch: Arc<RwLock<Option<VisualMismatch>>>,
+    visual_check_running: Arc<AtomicBool>,
 }
 
 impl SlaveSync {
-    pub fn new(obs_client: Arc<OBSClient>) -> (Self, mpsc::UnboundedReceiver<DesyncAlert>) {
+    pub fn new(
+        instance: String,
+        obs_client: Arc<OBSClient>,
+        settings: Arc<RwLock<SlaveSyncSettings>>,
+        report_tx: mpsc::UnboundedSender<SyncMessage>,
+    ) -> (Self, mpsc::UnboundedReceiver<DesyncAlert>) {
         let (tx, rx) = mpsc::unbounded_channel();
         (
             Self {
+                instance,
                 obs_client,
                 alert_tx: tx,
+                report_tx,
                 expected_state: Arc::new(RwLock::new(serde_json::json!({}))),
+                text_latency: Arc::new(RwLock::new(TextLatencyStats::default())),
+                settings,
+                applied_transition: Arc::new(RwLock::new(None)),
+                last_cut: Arc::new(RwLock::new(None)),
+                item_id_map: Arc::new(RwLock::new(HashMap::new())),
+                capabilities: Arc::new(RwLock::new(None)),
+                unsupported_targets: Arc::new(RwLock::new(Vec::new())),
+                visual_mismatch: Arc::new(RwLock::new(None)),
+                visual_check_running: Arc::new(AtomicBool::new(false)),
             },
             rx,
         )
@@ -45,9 +188,12 @@ impl SlaveSync {
 
     /// Start periodic state checking task
     pub fn start_periodic_check(&self, interval_secs: u64) {
+        let instance = self.instance.clone();
         let obs_client = self.obs_client.clone();
         let expected_state = self.expected_state.clone();
         let alert_tx = self.alert_tx.clone();
+        let report_tx = self.report_tx.clone();
+        let settings = self.settings.clone();
 
         tokio::spawn(async move {
             let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(interval_secs));
@@ -55,8 +201,33 @@ impl SlaveSync {
             loop {
                 interval.tick().await;
                 
+                // Keep the master's view of our outputs fresh
+                if settings.read().await.mirrors_any_output() {
+                    let client_arc = obs_client.get_client_arc();
+                    let client_lock = client_arc.read().await;
+                    if let Some(client) = client_lock.as_ref() {
+                        Self::report_outputs(&instance, client, &report_tx).await;
+                    }
+                }
+                
+                // Inputs whose mixer state the master told us about
+                let audio_inputs: Vec<String> = expected_state
+                    .read()
+                    .await
+                    .get("audio")
+                    .and_then(|v| v.as_object())
+                    .map(|audio| audio.keys().cloned().collect())
+                    .unwrap_or_default();
+                let media_inputs: Vec<String> = expected_state
+                    .read()
+                    .await
+                    .get("media")
+                    .and_then(|v| v.as_object())
+                    .map(|media| media.keys().cloned().collect())
+                    .unwrap_or_default();
+
                 // Get current local OBS state
-                let local_state = match Self::get_current_obs_state(&obs_client).await {
+                let local_state = match Self::get_current_obs_state(&obs_client, &audio_inputs, &media_inputs).await {
                     Ok(state) => state,
                     Err(e) => {
                         eprintln!("Failed to get local OBS state: {}", e);
@@ -74,7 +245,7 @@ impl SlaveSync {
                 let diffs = DiffDetector::detect_differences(&local_state, &expected);
                 
                 if !diffs.is_empty() {
-                    println!("⚠️  Detected {} state difference(s)", diffs.len());
+                    println!("⚠️  [{}] Detected {} state difference(s)", instance, diffs.len());
                     
                     for diff in diffs {
                         let severity = match diff.severity {
@@ -85,6 +256,7 @@ impl SlaveSync {
                         let alert = DesyncAlert {
                             id: uuid::Uuid::new_v4().to_string(),
                             timestamp: chrono::Utc::now().timestamp_millis(),
+                            instance: instance.clone(),
                             scene_name: diff.scene_name,
                             source_name: diff.source_name,
                             message: diff.description,
@@ -101,66 +273,353 @@ impl SlaveSync {
     }
 
     /// Get current OBS state for comparison
-    async fn get_current_obs_state(obs_client: &Arc<OBSClient>) -> Result<serde_json::Value> {
+    async fn get_current_obs_state(
+        obs_client: &Arc<OBSClient>,
+        audio_inputs: &[String],
+        media_inputs: &[String],
+    ) -> Result<serde_json::Value> {
         let client_arc = obs_client.get_client_arc();
         let client_lock = client_arc.read().await;
         
         if let Some(client) = client_lock.as_ref() {
-            // Get current scene
-            let current_scene = client.scenes().current_program_scene().await
-                .context("Failed to get current scene")?;
+            let started = Instant::now();
+            let (current_scene, scene_collections, profiles) = futures::join!(
+                async { client.scenes().current_program_scene().await },
+                OBSCommands::get_scene_collections(client),
+                OBSCommands::get_profiles(client),
+            );
+            let current_scene = current_scene.context("Failed to get current scene")?;
             
             // Get sources in current scene
-            let items = client.scene_items().list(&current_scene).await
-                .context("Failed to get scene items")?;
+            let sources = Self::collect_local_items(client, &current_scene).await?;
+
+            // Items of groups and nested scenes reachable from the current scene,
+            // one nesting level at a time
+            let mut scenes = serde_json::Map::new();
+            let mut visited = HashSet::from([current_scene.clone()]);
+            let mut pending = Self::container_names(&sources);
+            while !pending.is_empty() {
+                let level: Vec<String> = pending
+                    .drain(..)
+                    .filter(|name| visited.insert(name.clone()))
+                    .collect();
+                let results: Vec<(String, Result<Vec<serde_json::Value>>)> =
+                    buffered_requests(level.into_iter().map(|name| async move {
+                        let items = Self::collect_local_items(client, &name).await;
+                        (name, items)
+                    }))
+                    .await;
+
+                for (name, items) in results {
+                    match items {
+                        Ok(items) => {
+                            pending.extend(Self::container_names(&items));
+                            scenes.insert(name, serde_json::Value::Array(items));
+                        }
+                        Err(e) => {
+                            eprintln!("Failed to get items of {}: {}", name, e);
+                        }
+                    }
+                }
+            }
             
-                        let mut sources = Vec::new();
-                        for item in items {
-                            let transform = client.scene_items().transform(&current_scene, item.id).await.ok();
-                
-                sources.push(serde_json::json!({
-                    "name": item.source_name,
-                    "transform": transform.map(|t| serde_json::json!({
-                        "position_x": t.position_x,
-                        "position_y": t.position_y,
-                        "scale_x": t.scale_x,
-                        "scale_y": t.scale_y,
-                        "rotation": t.rotation,
-                    })),
-                }));
+            let audio: serde_json::Map<String, serde_json::Value> =
+                buffered_requests(audio_inputs.iter().map(|input_name| async move {
+                    (input_name, OBSCommands::get_input_audio_state(client, input_name).await)
+                }))
+                .await
+                .into_iter()
+                .filter_map(|(input_name, state)| state.ok().map(|state| (input_name.clone(), state)))
+                .collect();
+            
+            let media: serde_json::Map<String, serde_json::Value> =
+                buffered_requests(media_inputs.iter().map(|input_name| async move {
+                    (input_name, OBSCommands::get_media_input_status(client, input_name).await)
+                }))
+                .await
+                .into_iter()
+                .filter_map(|(input_name, status)| status.ok().map(|status| (input_name.clone(), status)))
+                .collect();
+            
+            let scene_collection = scene_collections.ok().map(|(current, _)| current);
+            let profile = profiles.ok().map(|(current, _)| current);
+
+            let elapsed = started.elapsed();
+            if elapsed > SLOW_STATE_COLLECTION {
+                println!(
+                    "Local state collection took {} ms ({} scenes, {} items)",
+                    elapsed.as_millis(),
+                    scenes.len() + 1,
+                    sources.len(),
+                );
             }
             
             Ok(serde_json::json!({
+                "scene_collection": scene_collection,
+                "profile": profile,
                 "current_scene": current_scene,
                 "sources": sources,
+                "scenes": scenes,
+                "audio": audio,
+                "media": media,
             }))
         } else {
             Err(anyhow::anyhow!("OBS client not connected"))
         }
     }
 
+    /// Items of a scene or group with the state DiffDetector compares
+    async fn collect_local_items(client: &obws::Client, scene_name: &str) -> Result<Vec<serde_json::Value>> {
+        let items = OBSCommands::get_scene_item_list(client, scene_name).await?;
+
+        let sources = buffered_requests(items.into_iter().map(|item| async move {
+            let scene_item_id = item["scene_item_id"].as_i64().unwrap_or(0);
+            let (transform, enabled, locked) = futures::join!(
+                async { client.scene_items().transform(scene_name, scene_item_id).await },
+                OBSCommands::get_scene_item_enabled(client, scene_name, scene_item_id),
+                OBSCommands::get_scene_item_locked(client, scene_name, scene_item_id),
+            );
+
+            serde_json::json!({
+                "name": item["source_name"],
+                "source_type": item["source_type"],
+                "transform": transform.ok().map(|t| serde_json::json!({
+                    "position_x": t.position_x,
+                    "position_y": t.position_y,
+                    "scale_x": t.scale_x,
+                    "scale_y": t.scale_y,
+                    "rotation": t.rotation,
+                })),
+                "enabled": enabled.ok(),
+                "locked": locked.ok(),
+                "index": item["index"],
+            })
+        }))
+        .await;
+        Ok(sources)
+    }
+
+    /// Names of the groups and nested scenes among `items`
+    fn container_names(items: &[serde_json::Value]) -> Vec<String> {
+        items
+            .iter()
+            .filter(|item| matches!(item["source_type"].as_str(), Some("scene") | Some("group")))
+            .filter_map(|item| item["name"].as_str().map(|name| name.to_string()))
+            .collect()
+    }
+
+    /// Item entry of the expected state, as compared by DiffDetector
+    fn expected_item(item: &serde_json::Value) -> serde_json::Value {
+        serde_json::json!({
+            "name": item["source_name"],
+            "scene_item_id": item["scene_item_id"],
+            "source_type": item["source_type"],
+            "transform": item["transform"],
+            "enabled": item["enabled"],
+            "locked": item["locked"],
+            "index": item["index"],
+        })
+    }
+
     /// Update expected state from sync message
     async fn update_expected_state(&self, message: &SyncMessage) {
         let mut expected = self.expected_state.write().await;
         
         match message.message_type {
             SyncMessageType::SceneChange => {
-                if let Some(scene_name) = message.payload["scene_name"].as_str() {
-                    expected["current_scene"] = serde_json::json!(scene_name);
+                // Only the program scene is compared by DiffDetector
+                if message.target_type == SyncTargetType::Program {
+                    if let Some(scene_name) = message.payload["scene_name"].as_str() {
+                        expected["current_scene"] = serde_json::json!(scene_name);
+                        *self.last_cut.write().await = Some((message.timestamp, scene_name.to_string()));
+                    }
                 }
             }
+            SyncMessageType::CollectionUpdate => {
+                expected["scene_collection"] = message.payload["scene_collection"].clone();
+                expected["profile"] = message.payload["profile"].clone();
+            }
             SyncMessageType::StateSync => {
                 // Full state update
-                if let Some(current_scene) = message.payload["current_program_scene"].as_str() {
+                expected["scene_collection"] = message.payload["scene_collection"].clone();
+                expected["profile"] = message.payload["profile"].clone();
+                if let Some(current_scene) = self.program_scene_of_state(message).await {
                     expected["current_scene"] = serde_json::json!(current_scene);
                 }
-                // Could expand to include full scene data
+
+                // Keep per-scene item state so DiffDetector can compare sources
+                let mut scenes = serde_json::Map::new();
+                if let Some(scene_list) = message.payload["scenes"].as_array() {
+                    for scene in scene_list {
+                        let scene_name = scene["name"].as_str().unwrap_or("").to_string();
+                        let items = scene["items"].as_array().map(|items| items.as_slice()).unwrap_or_default();
+
+                        // Group children are keyed by group name, like the scenes they live in
+                        for group in items.iter().filter(|item| item["children"].is_array()) {
+                            if let (Some(group_name), Some(children)) =
+                                (group["source_name"].as_str(), group["children"].as_array())
+                            {
+                                scenes.insert(
+                                    group_name.to_string(),
+                                    serde_json::Value::Array(children.iter().map(Self::expected_item).collect()),
+                                );
+                            }
+                        }
+
+                        scenes.insert(
+                            scene_name,
+                            serde_json::Value::Array(items.iter().map(Self::expected_item).collect()),
+                        );
+                    }
+                }
+                expected["scenes"] = serde_json::Value::Object(scenes);
+
+                if let Some(audio_inputs) = message.payload["audio_inputs"].as_object() {
+                    expected["audio"] = serde_json::Value::Object(audio_inputs.clone());
+                }
+                if let Some(media_inputs) = message.payload["media_inputs"].as_object() {
+                    expected["media"] = serde_json::Value::Object(media_inputs.clone());
+                }
+            }
+            SyncMessageType::MediaUpdate => {
+                if let Some(input_name) = message.payload["input_name"].as_str() {
+                    if !expected["media"].is_object() {
+                        expected["media"] = serde_json::json!({});
+                    }
+                    expected["media"][input_name] = serde_json::json!({
+                        "state": message.payload["state"],
+                        "cursor_ms": message.payload["cursor_ms"],
+                        "duration_ms": message.payload["duration_ms"],
+                        "captured_at": message.payload["captured_at"],
+                    });
+                }
+            }
+            SyncMessageType::SceneRemoved => {
+                if let (Some(scene_name), Some(scenes)) = (
+                    message.payload["scene_name"].as_str(),
+                    expected.get_mut("scenes").and_then(|v| v.as_object_mut()),
+                ) {
+                    scenes.remove(scene_name);
+                }
+            }
+            SyncMessageType::SceneRenamed => {
+                if let (Some(scene_name), Some(new_scene_name)) = (
+                    message.payload["scene_name"].as_str(),
+                    message.payload["new_scene_name"].as_str(),
+                ) {
+                    if let Some(scenes) = expected.get_mut("scenes").and_then(|v| v.as_object_mut()) {
+                        if let Some(items) = scenes.remove(scene_name) {
+                            scenes.insert(new_scene_name.to_string(), items);
+                        }
+                    }
+                    if expected["current_scene"].as_str() == Some(scene_name) {
+                        expected["current_scene"] = serde_json::json!(new_scene_name);
+                    }
+                }
+            }
+            SyncMessageType::AudioUpdate => {
+                if let Some(input_name) = message.payload["input_name"].as_str() {
+                    if !expected["audio"].is_object() {
+                        expected["audio"] = serde_json::json!({});
+                    }
+                    let entry = &mut expected["audio"][input_name];
+                    if !entry.is_object() {
+                        *entry = serde_json::json!({});
+                    }
+                    if let Some(fields) = message.payload.as_object() {
+                        for (key, value) in fields {
+                            if key != "input_name" {
+                                entry[key] = value.clone();
+                            }
+                        }
+                    }
+                }
+            }
+            SyncMessageType::TransformUpdate => {
+                let scene_name = message.payload["scene_name"].as_str().unwrap_or("");
+                let scene_item_id = message.payload["scene_item_id"].as_i64().unwrap_or(0);
+                if let Some(item) = Self::find_expected_item(&mut expected, scene_name, scene_item_id) {
+                    item["transform"] = message.payload["transform"].clone();
+                }
+            }
+            SyncMessageType::SceneItemCreated => {
+                let scene_name = message.payload["scene_name"].as_str().unwrap_or("");
+                let item = &message.payload["item"];
+                if !expected["scenes"].is_object() {
+                    expected["scenes"] = serde_json::json!({});
+                }
+                let items = &mut expected["scenes"][scene_name];
+                if !items.is_array() {
+                    *items = serde_json::json!([]);
+                }
+                if let Some(items) = items.as_array_mut() {
+                    items.push(Self::expected_item(item));
+                }
+            }
+            SyncMessageType::SceneItemEnabledUpdate => {
+                let scene_name = message.payload["scene_name"].as_str().unwrap_or("");
+                let scene_item_id = message.payload["scene_item_id"].as_i64().unwrap_or(0);
+                if let Some(item) = Self::find_expected_item(&mut expected, scene_name, scene_item_id) {
+                    item["enabled"] = message.payload["enabled"].clone();
+                }
+            }
+            SyncMessageType::SceneItemLockedUpdate => {
+                let scene_name = message.payload["scene_name"].as_str().unwrap_or("");
+                let scene_item_id = message.payload["scene_item_id"].as_i64().unwrap_or(0);
+                if let Some(item) = Self::find_expected_item(&mut expected, scene_name, scene_item_id) {
+                    item["locked"] = message.payload["locked"].clone();
+                }
+            }
+            SyncMessageType::SceneItemOrderUpdate => {
+                let scene_name = message.payload["scene_name"].as_str().unwrap_or("");
+                if let Some(order) = message.payload["items"].as_array() {
+                    for entry in order {
+                        let scene_item_id = entry["scene_item_id"].as_i64().unwrap_or(0);
+                        if let Some(item) = Self::find_expected_item(&mut expected, scene_name, scene_item_id) {
+                            item["index"] = entry["index"].clone();
+                        }
+                    }
+                }
             }
             _ => {}
         }
     }
 
+    /// Find an item in the expected state by scene and scene item id
+    fn find_expected_item<'a>(
+        expected: &'a mut serde_json::Value,
+        scene_name: &str,
+        scene_item_id: i64,
+    ) -> Option<&'a mut serde_json::Value> {
+        expected
+            .get_mut("scenes")?
+            .get_mut(scene_name)?
+            .as_array_mut()?
+            .iter_mut()
+            .find(|item| item["scene_item_id"].as_i64() == Some(scene_item_id))
+    }
+
+    pub async fn get_text_latency(&self) -> TextLatencyStats {
+        self.text_latency.read().await.clone()
+    }
+
     pub async fn apply_sync_message(&self, message: SyncMessage) -> Result<()> {
+        // Skip targets the local OBS cannot apply, with a single alert per target
+        if let Some(reason) = self.unsupported_reason(&message.target_type).await {
+            let mut reported = self.unsupported_targets.write().await;
+            if !reported.contains(&message.target_type) {
+                reported.push(message.target_type.clone());
+                drop(reported);
+                self.send_alert(String::new(), String::new(), reason, AlertSeverity::Error)?;
+            }
+            return Ok(());
+        }
+
+        // Text updates bypass expected state tracking to keep scoreboards fast
+        if message.message_type == SyncMessageType::TextUpdate {
+            return self.apply_text_update(&message).await;
+        }
+
         // Update expected state first
         self.update_expected_state(&message).await;
         
@@ -174,7 +633,16 @@ impl SlaveSync {
                     .as_str()
                     .context("Invalid scene_name in payload")?;
 
-                if let Err(e) = OBSCommands::set_current_program_scene(&client, scene_name).await {
+                if message.target_type == SyncTargetType::Preview {
+                    if let Err(e) = self.apply_preview_scene(&client, scene_name).await {
+                        self.send_alert(
+                            scene_name.to_string(),
+                            String::new(),
+                            format!("Failed to change preview scene: {}", e),
+                            AlertSeverity::Warning,
+                        )?;
+                    }
+                } else if let Err(e) = self.switch_program_scene(&client, scene_name, &message.payload["transition"]).await {
                     self.send_alert(
                         scene_name.to_string(),
                         String::new(),
@@ -183,6 +651,205 @@ impl SlaveSync {
                     )?;
                 }
             }
+            SyncMessageType::CollectionUpdate => {
+                self.apply_collection(&client, &message.payload).await?;
+            }
+            SyncMessageType::OutputStateUpdate => {
+                let output = message.payload["output"]
+                    .as_str()
+                    .context("Invalid output")?;
+                let active = message.payload["active"]
+                    .as_bool()
+                    .context("Invalid active")?;
+                let state = message.payload["state"].as_str().unwrap_or("");
+
+                if self.settings.read().await.mirrors_output(output) {
+                    if let Err(e) = self.apply_output_state(&client, output, active, state).await {
+                        self.send_alert(
+                            String::new(),
+                            String::new(),
+                            format!("Failed to mirror {} state: {}", output, e),
+                            AlertSeverity::Error,
+                        )?;
+                    }
+                    Self::report_outputs(&self.instance, &client, &self.report_tx).await;
+                }
+            }
+            SyncMessageType::ReplayBufferSave => {
+                if self.settings.read().await.mirror_replay_buffer {
+                    if let Err(e) = OBSCommands::save_replay_buffer(&client).await {
+                        self.send_alert(
+                            String::new(),
+                            String::new(),
+                            format!("Failed to save replay buffer: {}", e),
+                            AlertSeverity::Error,
+                        )?;
+                    } else {
+                        println!("Saved replay buffer");
+                    }
+                }
+            }
+            SyncMessageType::VisualHash => {
+                if self.settings.read().await.verify_program_visually {
+                    let scene_name = message.payload["scene_name"]
+                        .as_str()
+                        .context("Invalid scene_name")?;
+                    let hash = message.payload["hash"].as_str().context("Invalid hash")?;
+                    let master = VisualFingerprint {
+                        hash: u64::from_str_radix(hash, 16).context("Invalid hash")?,
+                        luma: message.payload["luma"]
+                            .as_u64()
+                            .and_then(|luma| u8::try_from(luma).ok())
+                            .context("Invalid luma")?,
+                    };
+
+                    self.spawn_visual_check(scene_name.to_string(), master);
+                }
+            }
+            SyncMessageType::TriggerHotkey => {
+                let result = if let Some(hotkey_name) = message.payload["hotkey_name"].as_str() {
+                    OBSCommands::trigger_hotkey_by_name(&client, hotkey_name).await
+                } else {
+                    let sequence: KeySequence = serde_json::from_value(message.payload["key_sequence"].clone())
+                        .context("Invalid key_sequence")?;
+                    OBSCommands::trigger_hotkey_by_sequence(
+                        &client,
+                        &sequence.key,
+                        sequence.shift,
+                        sequence.control,
+                        sequence.alt,
+                        sequence.command,
+                    )
+                    .await
+                };
+
+                if let Err(e) = result {
+                    self.send_alert(
+                        String::new(),
+                        String::new(),
+                        format!("Failed to trigger forwarded hotkey: {}", e),
+                        AlertSeverity::Warning,
+                    )?;
+                }
+            }
+            SyncMessageType::SceneCreated => {
+                let scene_name = message.payload["scene_name"]
+                    .as_str()
+                    .context("Invalid scene_name")?;
+
+                let exists = OBSCommands::get_scene_names(&client)
+                    .await?
+                    .iter()
+                    .any(|name| name == scene_name);
+                if exists {
+                    return Ok(());
+                }
+
+                if self.settings.read().await.mirror_scene_list {
+                    if let Err(e) = OBSCommands::create_scene(&client, scene_name).await {
+                        self.send_alert(
+                            scene_name.to_string(),
+                            String::new(),
+                            format!("Failed to create scene: {}", e),
+                            AlertSeverity::Warning,
+                        )?;
+                    } else {
+                        println!("Created scene: {}", scene_name);
+                    }
+                } else {
+                    self.send_alert(
+                        scene_name.to_string(),
+                        String::new(),
+                        "Scene was created on master but does not exist locally".to_string(),
+                        AlertSeverity::Warning,
+                    )?;
+                }
+            }
+            SyncMessageType::SceneRemoved => {
+                let scene_name = message.payload["scene_name"]
+                    .as_str()
+                    .context("Invalid scene_name")?;
+
+                // Without scene list mirroring an extra local scene is harmless
+                let settings = self.settings.read().await.clone();
+                if !settings.mirror_scene_list {
+                    return Ok(());
+                }
+
+                if !settings.allow_scene_removal {
+                    self.send_alert(
+                        scene_name.to_string(),
+                        String::new(),
+                        "Scene was removed on master; scene removal is disabled on this slave".to_string(),
+                        AlertSeverity::Warning,
+                    )?;
+                } else if let Err(e) = OBSCommands::remove_scene(&client, scene_name).await {
+                    self.send_alert(
+                        scene_name.to_string(),
+                        String::new(),
+                        format!("Failed to remove scene: {}", e),
+                        AlertSeverity::Warning,
+                    )?;
+                } else {
+                    println!("Removed scene: {}", scene_name);
+                }
+            }
+            SyncMessageType::SceneRenamed => {
+                let scene_name = message.payload["scene_name"]
+                    .as_str()
+                    .context("Invalid scene_name")?;
+                let new_scene_name = message.payload["new_scene_name"]
+                    .as_str()
+                    .context("Invalid new_scene_name")?;
+
+                if self.settings.read().await.mirror_scene_list {
+                    if let Err(e) = OBSCommands::rename_scene(&client, scene_name, new_scene_name).await {
+                        self.send_alert(
+                            scene_name.to_string(),
+                            String::new(),
+                            format!("Failed to rename scene to '{}': {}", new_scene_name, e),
+                            AlertSeverity::Warning,
+                        )?;
+                    } else {
+                        println!("Renamed scene: {} -> {}", scene_name, new_scene_name);
+                    }
+                } else {
+                    self.send_alert(
+                        scene_name.to_string(),
+                        String::new(),
+                        format!("Scene was renamed to '{}' on master", new_scene_name),
+                        AlertSeverity::Warning,
+                    )?;
+                }
+            }
+            SyncMessageType::TransitionUpdate => {
+                if let Err(e) = self.apply_transition(&client, &message.payload).await {
+                    self.send_alert(
+                        String::new(),
+                        String::new(),
+                        format!("Failed to update transition: {}", e),
+                        AlertSeverity::Warning,
+                    )?;
+                }
+            }
+            SyncMessageType::StudioModeUpdate => {
+                let enabled = message.payload["enabled"]
+                    .as_bool()
+                    .context("Invalid enabled")?;
+
+                if self.settings.read().await.mirror_studio_mode {
+                    if let Err(e) = OBSCommands::set_studio_mode_enabled(&client, enabled).await {
+                        self.send_alert(
+                            String::new(),
+                            String::new(),
+                            format!("Failed to mirror studio mode: {}", e),
+                            AlertSeverity::Warning,
+                        )?;
+                    } else {
+                        println!("Studio mode {}", if enabled { "enabled" } else { "disabled" });
+                    }
+                }
+            }
             SyncMessageType::TransformUpdate => {
                 let scene_name = message.payload["scene_name"]
                     .as_str()
@@ -191,6 +858,8 @@ impl SlaveSync {
                     .as_i64()
                     .context("Invalid scene_item_id")?;
 
+                let scene_item_id = self.local_item_id(scene_name, scene_item_id).await;
+
                 // Apply transform if included in payload
                 if let Some(transform) = message.payload["transform"].as_object() {
                     if let Err(e) = self.apply_transform(&client, scene_name, scene_item_id, transform).await {
@@ -232,77 +901,415 @@ impl SlaveSync {
                     )?;
                 }
             }
-            SyncMessageType::Heartbeat => {
-                // Just acknowledge heartbeat
-            }
-            SyncMessageType::StateSync => {
-                println!("Applying complete initial state from master...");
-                
-                // Apply all scenes and items
-                if let Some(scenes) = message.payload["scenes"].as_array() {
-                    for scene in scenes {
-                        let scene_name = scene["name"].as_str().unwrap_or("");
-                        println!("Processing scene: {}", scene_name);
-                        
-                        // Apply items in this scene
-                        if let Some(items) = scene["items"].as_array() {
-                            for item in items {
-                                let source_name = item["source_name"].as_str().unwrap_or("");
-                                let scene_item_id = item["scene_item_id"].as_i64().unwrap_or(0);
-                                
-                                println!("  - Applying item: {} (id: {})", source_name, scene_item_id);
-                                
-                                // Apply transform if available
-                                if let Some(transform) = item["transform"].as_object() {
-                                    if let Err(e) = self.apply_transform(
-                                        &client,
-                                        scene_name,
-                                        scene_item_id,
-                                        transform
-                                    ).await {
-                                        eprintln!("Failed to apply transform for {}: {}", source_name, e);
-                                    }
-                                }
-                                
-                                // Apply image data if available
-                                if let Some(image_data) = item["image_data"].as_object() {
-                                    if let (Some(file), Some(data)) = (
-                                        image_data.get("file").and_then(|v| v.as_str()),
-                                        image_data.get("data").and_then(|v| v.as_str())
-                                    ) {
-                                        if let Err(e) = self.handle_image_update(
-                                            &client,
-                                            source_name,
-                                            file,
-                                            Some(data)
-                                        ).await {
-                                            eprintln!("Failed to apply image for {}: {}", source_name, e);
-                                        }
-                                    }
-                                }
-                            }
+            SyncMessageType::SceneItemCreated => {
+                let scene_name = message.payload["scene_name"]
+                    .as_str()
+                    .context("Invalid scene_name")?;
+                let item = &message.payload["item"];
+                let source_name = item["source_name"].as_str().unwrap_or("");
+
+                // Without provisioning the periodic check reports the source as missing
+                if self.settings.read().await.provision_missing_sources {
+                    match self.provision_item(&client, scene_name, item).await {
+                        Ok(Some(_)) => self.apply_item_state(&client, scene_name, item).await,
+                        Ok(None) => {}
+                        Err(e) => {
+                            self.send_alert(
+                                scene_name.to_string(),
+                                source_name.to_string(),
+                                format!("Failed to provision source: {}", e),
+                                AlertSeverity::Warning,
+                            )?;
                         }
                     }
                 }
-                
-                // Apply current program scene
-                if let Some(scene_name) = message.payload["current_program_scene"].as_str() {
-                    if let Err(e) = crate::obs::commands::OBSCommands::set_current_program_scene(&client, scene_name).await {
-                        self.send_alert(
-                            scene_name.to_string(),
-                            String::new(),
-                            format!("Failed to sync initial scene: {}", e),
-                            AlertSeverity::Warning,
-                        )?;
-                    } else {
-                        println!("✓ Applied current program scene: {}", scene_name);
-                    }
-                }
-                
-                // Apply preview scene if in studio mode
-                if let Some(preview_scene) = message.payload["current_preview_scene"].as_str() {
-                    // Note: Setting preview scene requires studio mode to be enabled
-                    println!("Preview scene in master: {}", preview_scene);
+            }
+            SyncMessageType::SceneItemEnabledUpdate => {
+                let scene_name = message.payload["scene_name"]
+                    .as_str()
+                    .context("Invalid scene_name")?;
+                let scene_item_id = message.payload["scene_item_id"]
+                    .as_i64()
+                    .context("Invalid scene_item_id")?;
+                let enabled = message.payload["enabled"]
+                    .as_bool()
+                    .context("Invalid enabled")?;
+                let scene_item_id = self.local_item_id(scene_name, scene_item_id).await;
+
+                if let Err(e) = OBSCommands::set_scene_item_enabled(&client, scene_name, scene_item_id, enabled).await {
+                    self.send_alert(
+                        scene_name.to_string(),
+                        String::new(),
+                        format!("Failed to update visibility of item {}: {}", scene_item_id, e),
+                        AlertSeverity::Warning,
+                    )?;
+                } else {
+                    println!("Applied visibility {} for item {} in scene {}", enabled, scene_item_id, scene_name);
+                }
+            }
+            SyncMessageType::SceneItemLockedUpdate => {
+                let scene_name = message.payload["scene_name"]
+                    .as_str()
+                    .context("Invalid scene_name")?;
+                let scene_item_id = message.payload["scene_item_id"]
+                    .as_i64()
+                    .context("Invalid scene_item_id")?;
+                let locked = message.payload["locked"]
+                    .as_bool()
+                    .context("Invalid locked")?;
+                let scene_item_id = self.local_item_id(scene_name, scene_item_id).await;
+
+                if let Err(e) = OBSCommands::set_scene_item_locked(&client, scene_name, scene_item_id, locked).await {
+                    self.send_alert(
+                        scene_name.to_string(),
+                        String::new(),
+                        format!("Failed to update lock of item {}: {}", scene_item_id, e),
+                        AlertSeverity::Warning,
+                    )?;
+                }
+            }
+            SyncMessageType::SceneItemOrderUpdate => {
+                let scene_name = message.payload["scene_name"]
+                    .as_str()
+                    .context("Invalid scene_name")?;
+                let items = message.payload["items"]
+                    .as_array()
+                    .context("Invalid items")?;
+
+                if let Err(e) = self.apply_scene_order(&client, scene_name, items).await {
+                    self.send_alert(
+                        scene_name.to_string(),
+                        String::new(),
+                        format!("Failed to reorder scene items: {}", e),
+                        AlertSeverity::Warning,
+                    )?;
+                } else {
+                    println!("Applied z-order for scene {}", scene_name);
+                }
+            }
+            SyncMessageType::FilterCreated => {
+                let source_name = message.payload["source_name"]
+                    .as_str()
+                    .context("Invalid source_name")?;
+                let filter = &message.payload["filter"];
+                let filter_name = filter["name"].as_str().context("Invalid filter name")?;
+                let filter_kind = filter["kind"].as_str().context("Invalid filter kind")?;
+
+                let result = async {
+                    OBSCommands::create_source_filter(&client, source_name, filter_name, filter_kind, &filter["settings"]).await?;
+                    if let Some(index) = filter["index"].as_u64() {
+                        OBSCommands::set_source_filter_index(&client, source_name, filter_name, index as u32).await?;
+                    }
+                    Ok::<(), anyhow::Error>(())
+                }.await;
+
+                if let Err(e) = result {
+                    self.send_alert(
+                        String::new(),
+                        source_name.to_string(),
+                        format!("Failed to create filter '{}': {}", filter_name, e),
+                        AlertSeverity::Warning,
+                    )?;
+                }
+            }
+            SyncMessageType::FilterRemoved => {
+                let source_name = message.payload["source_name"]
+                    .as_str()
+                    .context("Invalid source_name")?;
+                let filter_name = message.payload["filter_name"]
+                    .as_str()
+                    .context("Invalid filter_name")?;
+
+                if let Err(e) = OBSCommands::remove_source_filter(&client, source_name, filter_name).await {
+                    self.send_alert(
+                        String::new(),
+                        source_name.to_string(),
+                        format!("Failed to remove filter '{}': {}", filter_name, e),
+                        AlertSeverity::Warning,
+                    )?;
+                }
+            }
+            SyncMessageType::FilterRenamed => {
+                let source_name = message.payload["source_name"]
+                    .as_str()
+                    .context("Invalid source_name")?;
+                let filter_name = message.payload["filter_name"]
+                    .as_str()
+                    .context("Invalid filter_name")?;
+                let new_filter_name = message.payload["new_filter_name"]
+                    .as_str()
+                    .context("Invalid new_filter_name")?;
+
+                if let Err(e) = OBSCommands::rename_source_filter(&client, source_name, filter_name, new_filter_name).await {
+                    self.send_alert(
+                        String::new(),
+                        source_name.to_string(),
+                        format!("Failed to rename filter '{}': {}", filter_name, e),
+                        AlertSeverity::Warning,
+                    )?;
+                }
+            }
+            SyncMessageType::FilterOrderUpdate => {
+                let source_name = message.payload["source_name"]
+                    .as_str()
+                    .context("Invalid source_name")?;
+                let filters = message.payload["filters"]
+                    .as_array()
+                    .context("Invalid filters")?;
+
+                if let Err(e) = self.apply_filter_list(&client, source_name, filters).await {
+                    self.send_alert(
+                        String::new(),
+                        source_name.to_string(),
+                        format!("Failed to reorder filters: {}", e),
+                        AlertSeverity::Warning,
+                    )?;
+                }
+            }
+            SyncMessageType::FilterSettingsUpdate => {
+                let source_name = message.payload["source_name"]
+                    .as_str()
+                    .context("Invalid source_name")?;
+                let filter_name = message.payload["filter_name"]
+                    .as_str()
+                    .context("Invalid filter_name")?;
+
+                if let Err(e) = OBSCommands::set_source_filter_settings(&client, source_name, filter_name, &message.payload["settings"]).await {
+                    self.send_alert(
+                        String::new(),
+                        source_name.to_string(),
+                        format!("Failed to update filter '{}': {}", filter_name, e),
+                        AlertSeverity::Warning,
+                    )?;
+                }
+            }
+            SyncMessageType::FilterEnabledUpdate => {
+                let source_name = message.payload["source_name"]
+                    .as_str()
+                    .context("Invalid source_name")?;
+                let filter_name = message.payload["filter_name"]
+                    .as_str()
+                    .context("Invalid filter_name")?;
+                let enabled = message.payload["enabled"]
+                    .as_bool()
+                    .context("Invalid enabled")?;
+
+                if let Err(e) = OBSCommands::set_source_filter_enabled(&client, source_name, filter_name, enabled).await {
+                    self.send_alert(
+                        String::new(),
+                        source_name.to_string(),
+                        format!("Failed to toggle filter '{}': {}", filter_name, e),
+                        AlertSeverity::Warning,
+                    )?;
+                }
+            }
+            SyncMessageType::AudioUpdate => {
+                let input_name = message.payload["input_name"]
+                    .as_str()
+                    .context("Invalid input_name")?;
+
+                if let Err(e) = self.apply_audio_state(&client, input_name, &message.payload).await {
+                    self.send_alert(
+                        String::new(),
+                        input_name.to_string(),
+                        format!("Failed to update audio: {}", e),
+                        AlertSeverity::Warning,
+                    )?;
+                }
+            }
+            SyncMessageType::MediaUpdate => {
+                let input_name = message.payload["input_name"]
+                    .as_str()
+                    .context("Invalid input_name")?;
+
+                if let Err(e) = self.apply_media_state(
+                    &client,
+                    input_name,
+                    &message.payload,
+                    message.payload["action"].as_str()
+                ).await {
+                    self.send_alert(
+                        String::new(),
+                        input_name.to_string(),
+                        format!("Failed to update media playback: {}", e),
+                        AlertSeverity::Warning,
+                    )?;
+                }
+            }
+            SyncMessageType::InputSettingsUpdate => {
+                let input_name = message.payload["input_name"]
+                    .as_str()
+                    .context("Invalid input_name")?;
+
+                if let Err(e) = self.apply_input_settings(
+                    &client,
+                    input_name,
+                    &message.payload["settings"]
+                ).await {
+                    self.send_alert(
+                        String::new(),
+                        input_name.to_string(),
+                        format!("Failed to update input settings: {}", e),
+                        AlertSeverity::Warning,
+                    )?;
+                } else {
+                    println!("Applied settings update for input {}", input_name);
+                }
+            }
+            SyncMessageType::Heartbeat => {
+                // Just acknowledge heartbeat
+            }
+            SyncMessageType::StateSync => {
+                println!("Applying complete initial state from master...");
+                
+                self.check_canvas(&message.payload["canvas"]).await?;
+                
+                // Everything below is only meaningful in the master's scene collection
+                if let Err(e) = self.apply_collection(&client, &message.payload).await {
+                    eprintln!("Failed to check scene collection: {}", e);
+                }
+                
+                // Create scenes that only exist on the master
+                if self.settings.read().await.mirror_scene_list {
+                    if let Err(e) = self.create_missing_scenes(&client, &message.payload["scenes"]).await {
+                        eprintln!("Failed to create missing scenes: {}", e);
+                    }
+                }
+                
+                // Apply all scenes and items
+                let provision = self.settings.read().await.provision_missing_sources;
+                if let Some(scenes) = message.payload["scenes"].as_array() {
+                    for scene in scenes {
+                        let scene_name = scene["name"].as_str().unwrap_or("");
+                        println!("Processing scene: {}", scene_name);
+                        
+                        // Apply items in this scene
+                        if let Some(items) = scene["items"].as_array() {
+                            for item in items {
+                                if provision {
+                                    if let Err(e) = self.provision_item(&client, scene_name, item).await {
+                                        eprintln!("Failed to provision {}: {}", item["source_name"], e);
+                                    }
+                                }
+                                self.apply_item_state(&client, scene_name, item).await;
+
+                                // Group children are addressed with the group name as scene name
+                                if let (Some(group_name), Some(children)) =
+                                    (item["source_name"].as_str(), item["children"].as_array())
+                                {
+                                    for child in children {
+                                        if provision {
+                                            if let Err(e) = self.provision_item(&client, group_name, child).await {
+                                                eprintln!("Failed to provision {}: {}", child["source_name"], e);
+                                            }
+                                        }
+                                        self.apply_item_state(&client, group_name, child).await;
+                                    }
+                                    if let Err(e) = self.apply_scene_order(&client, group_name, children).await {
+                                        eprintln!("Failed to apply z-order for {}: {}", group_name, e);
+                                    }
+                                }
+                            }
+
+                            // Apply z-order once all items of the scene are processed
+                            if let Err(e) = self.apply_scene_order(&client, scene_name, items).await {
+                                eprintln!("Failed to apply z-order for {}: {}", scene_name, e);
+                            }
+                        }
+                    }
+                }
+                
+                // Apply filter lists if the master synced them
+                if let Some(source_filters) = message.payload["source_filters"].as_object() {
+                    for (source_name, filters) in source_filters {
+                        if let Some(filters) = filters.as_array() {
+                            if let Err(e) = self.apply_filter_list(&client, source_name, filters).await {
+                                eprintln!("Failed to apply filters for {}: {}", source_name, e);
+                            }
+                        }
+                    }
+                }
+                
+                // Apply mixer state if the master synced it
+                if let Some(audio_inputs) = message.payload["audio_inputs"].as_object() {
+                    for (input_name, state) in audio_inputs {
+                        if let Err(e) = self.apply_audio_state(&client, input_name, state).await {
+                            eprintln!("Failed to apply audio for {}: {}", input_name, e);
+                        }
+                    }
+                }
+                
+                // Apply media playback state if the master synced it
+                if let Some(media_inputs) = message.payload["media_inputs"].as_object() {
+                    for (input_name, state) in media_inputs {
+                        if let Err(e) = self.apply_media_state(&client, input_name, state, None).await {
+                            eprintln!("Failed to apply media state for {}: {}", input_name, e);
+                        }
+                    }
+                }
+                
+                // Bring mirrored outputs in line with the master
+                if let Some(outputs) = message.payload["outputs"].as_object() {
+                    let settings = self.settings.read().await.clone();
+                    for (output, state) in outputs {
+                        let Some(active) = state["active"].as_bool() else {
+                            continue;
+                        };
+                        if !settings.mirrors_output(output) {
+                            continue;
+                        }
+                        let state = if state["paused"].as_bool() == Some(true) { "paused" } else { "" };
+                        if let Err(e) = self.apply_output_state(&client, output, active, state).await {
+                            eprintln!("Failed to mirror {} state: {}", output, e);
+                        }
+                    }
+                    if settings.mirrors_any_output() {
+                        Self::report_outputs(&self.instance, &client, &self.report_tx).await;
+                    }
+                }
+                
+                // Apply transition selection before switching scenes
+                if message.payload["transition"].is_object() {
+                    if let Err(e) = self.apply_transition(&client, &message.payload["transition"]).await {
+                        eprintln!("Failed to apply transition: {}", e);
+                    }
+                }
+                
+                // Apply current program scene
+                if let Some(scene_name) = self.program_scene_of_state(&message).await {
+                    if let Err(e) = crate::obs::commands::OBSCommands::set_current_program_scene(&client, &scene_name).await {
+                        self.send_alert(
+                            scene_name.clone(),
+                            String::new(),
+                            format!("Failed to sync initial scene: {}", e),
+                            AlertSeverity::Warning,
+                        )?;
+                    } else {
+                        println!("✓ Applied current program scene: {}", scene_name);
+                    }
+                }
+                
+                // Mirror studio mode before touching the preview scene
+                if let Some(enabled) = message.payload["studio_mode_enabled"].as_bool() {
+                    if self.settings.read().await.mirror_studio_mode {
+                        if let Err(e) = OBSCommands::set_studio_mode_enabled(&client, enabled).await {
+                            eprintln!("Failed to mirror studio mode: {}", e);
+                        }
+                    }
+                }
+
+                // Apply preview scene if in studio mode
+                if let Some(preview_scene) = message.payload["current_preview_scene"].as_str() {
+                    if let Err(e) = self.apply_preview_scene(&client, preview_scene).await {
+                        self.send_alert(
+                            preview_scene.to_string(),
+                            String::new(),
+                            format!("Failed to sync initial preview scene: {}", e),
+                            AlertSeverity::Warning,
+                        )?;
+                    } else {
+                        println!("✓ Applied current preview scene: {}", preview_scene);
+                    }
                 }
                 
                 println!("✓ Initial state fully applied");
@@ -315,29 +1322,675 @@ impl SlaveSync {
 
     async fn apply_transform(
         &self,
-        _client: &obws::Client,
+        client: &obws::Client,
         scene_name: &str,
         scene_item_id: i64,
         transform: &serde_json::Map<String, serde_json::Value>,
     ) -> Result<()> {
-        // Note: Transform application depends on obws library API structure
-        // This is a placeholder implementation
-        // TODO: Implement actual transform application based on obws version
-        
-        println!(
-            "Transform update received for item {} in scene {}: {:?}",
-            scene_item_id, scene_name, transform
+        let transform: SceneItemTransform = serde_json::from_value(serde_json::Value::Object(transform.clone()))
+            .context("Invalid transform")?;
+        OBSCommands::set_scene_item_transform(client, scene_name, scene_item_id, &transform).await
+    }
+
+    /// Local id of a scene item the master refers to. Items that were provisioned
+    /// here got a new id from OBS; all others share the master's id.
+    async fn local_item_id(&self, scene_name: &str, scene_item_id: i64) -> i64 {
+        self.item_id_map
+            .read()
+            .await
+            .get(&(scene_name.to_string(), scene_item_id))
+            .copied()
+            .unwrap_or(scene_item_id)
+    }
+
+    /// Apply transform, visibility, lock, image and input settings of one item
+    /// from a `StateSync` or `SceneItemCreated` description. Failures are logged.
+    async fn apply_item_state(&self, client: &obws::Client, scene_name: &str, item: &serde_json::Value) {
+        let source_name = item["source_name"].as_str().unwrap_or("");
+        let scene_item_id = item["scene_item_id"].as_i64().unwrap_or(0);
+        let scene_item_id = self.local_item_id(scene_name, scene_item_id).await;
+
+        println!("  - Applying item: {} (id: {})", source_name, scene_item_id);
+
+        // Apply transform if available
+        if let Some(transform) = item["transform"].as_object() {
+            if let Err(e) = self.apply_transform(
+                client,
+                scene_name,
+                scene_item_id,
+                transform
+            ).await {
+                eprintln!("Failed to apply transform for {}: {}", source_name, e);
+            }
+        }
+
+        // Apply visibility and lock state if available
+        if let Some(enabled) = item["enabled"].as_bool() {
+            if let Err(e) = OBSCommands::set_scene_item_enabled(client, scene_name, scene_item_id, enabled).await {
+                eprintln!("Failed to apply visibility for {}: {}", source_name, e);
+            }
+        }
+        if let Some(locked) = item["locked"].as_bool() {
+            if let Err(e) = OBSCommands::set_scene_item_locked(client, scene_name, scene_item_id, locked).await {
+                eprintln!("Failed to apply lock for {}: {}", source_name, e);
+            }
+        }
+
+        // Apply image data if available
+        if let Some(image_data) = item["image_data"].as_object() {
+            if let (Some(file), Some(data)) = (
+                image_data.get("file").and_then(|v| v.as_str()),
+                image_data.get("data").and_then(|v| v.as_str())
+            ) {
+                if let Err(e) = self.handle_image_update(
+                    client,
+                    source_name,
+                    file,
+                    Some(data)
+                ).await {
+                    eprintln!("Failed to apply image for {}: {}", source_name, e);
+                }
+            }
+        }
+
+        // Apply input settings if available
+        if item["input_settings"].is_object() {
+            if let Err(e) = self.apply_input_settings(
+                client,
+                source_name,
+                &item["input_settings"]
+            ).await {
+                eprintln!("Failed to apply settings for {}: {}", source_name, e);
+            }
+        }
+    }
+
+    /// Create a scene item the master has but this OBS lacks. Missing inputs are
+    /// created with the master's kind and settings; existing inputs and nested
+    /// scenes are added as a new scene item. Returns the local scene item id if
+    /// an item was created.
+    async fn provision_item(
+        &self,
+        client: &obws::Client,
+        scene_name: &str,
+        item: &serde_json::Value,
+    ) -> Result<Option<i64>> {
+        let source_name = item["source_name"].as_str().context("Invalid source_name")?;
+        let scene_item_id = item["scene_item_id"].as_i64().context("Invalid scene_item_id")?;
+
+        let local_items = OBSCommands::get_scene_item_list(client, scene_name).await?;
+        if local_items.iter().any(|i| i["source_name"].as_str() == Some(source_name)) {
+            return Ok(None);
+        }
+
+        let input_kind = item["source_type"].as_str().unwrap_or("unknown");
+        let enabled = item["enabled"].as_bool();
+        let input_exists = OBSCommands::get_input_names(client)
+            .await?
+            .iter()
+            .any(|name| name == source_name);
+
+        let is_input = !matches!(input_kind, "scene" | "group" | "unknown");
+        let local_id = if input_exists || !is_input {
+            OBSCommands::create_scene_item(client, scene_name, source_name, enabled).await?
+        } else {
+            // Created empty; the settings go through the same local-kind filter
+            // as settings updates, so inputs never point at master devices
+            let local_id = OBSCommands::create_input(
+                client,
+                scene_name,
+                source_name,
+                input_kind,
+                &serde_json::json!({}),
+                enabled,
+            )
+            .await?;
+            if item["input_settings"].is_object() {
+                self.apply_input_settings(client, source_name, &item["input_settings"]).await?;
+            }
+            local_id
+        };
+
+        println!("Provisioned {} in scene {} (item {})", source_name, scene_name, local_id);
+        if local_id != scene_item_id {
+            self.item_id_map
+                .write()
+                .await
+                .insert((scene_name.to_string(), scene_item_id), local_id);
+        }
+        Ok(Some(local_id))
+    }
+
+    /// Why the local OBS cannot apply `target`, if it cannot. Capabilities are
+    /// read on first use after each (re)connect; the client lock must not be
+    /// held by the caller.
+    async fn unsupported_reason(&self, target: &SyncTargetType) -> Option<String> {
+        let connection_id = self.obs_client.connection_id();
+        let cached = matches!(&*self.capabilities.read().await, Some((id, _)) if *id == connection_id);
+        if !cached {
+            let status = self.obs_client.get_status().await;
+            if !status.connected {
+                return None;
+            }
+            *self.capabilities.write().await = Some((connection_id, status));
+            self.unsupported_targets.write().await.clear();
+        }
+        let cache = self.capabilities.read().await;
+        let (_, status) = cache.as_ref()?;
+        capabilities::unsupported_reason(status, target)
+    }
+
+    /// Warn when the master canvas differs from ours; positions and scales are
+    /// applied verbatim, so sources would land in different places
+    async fn check_canvas(&self, master_canvas: &serde_json::Value) -> Result<()> {
+        let master = (
+            master_canvas["base_width"].as_u64(),
+            master_canvas["base_height"].as_u64(),
         );
-        
-        // In production, you would use obws API to apply the transform:
-        // - Extract position_x, position_y
-        // - Extract scale_x, scale_y
-        // - Extract rotation
-        // - Call appropriate obws method to set transform
-        
+        let (Some(master_width), Some(master_height)) = master else {
+            return Ok(());
+        };
+        let Some(local) = self.capabilities.read().await.as_ref().and_then(|(_, s)| s.canvas.clone()) else {
+            return Ok(());
+        };
+
+        if (u64::from(local.base_width), u64::from(local.base_height)) != (master_width, master_height) {
+            self.send_alert(
+                String::new(),
+                String::new(),
+                format!(
+                    "Canvas {}x{} differs from master canvas {}x{}; transforms will not line up",
+                    local.base_width, local.base_height, master_width, master_height
+                ),
+                AlertSeverity::Warning,
+            )?;
+        }
+
+        let master_fps = match (master_canvas["fps_numerator"].as_u64(), master_canvas["fps_denominator"].as_u64()) {
+            (Some(num), Some(den)) if den > 0 => num as f64 / den as f64,
+            _ => return Ok(()),
+        };
+        if (local.fps() - master_fps).abs() > 0.01 {
+            self.send_alert(
+                String::new(),
+                String::new(),
+                format!("Frame rate {:.2} differs from master {:.2}", local.fps(), master_fps),
+                AlertSeverity::Warning,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Compare our program scene with the master's fingerprint in the
+    /// background, as the screenshot would otherwise hold up the messages
+    /// behind it. A hash arriving while a check runs is skipped.
+    fn spawn_visual_check(&self, scene_name: String, master: VisualFingerprint) {
+        if self.visual_check_running.swap(true, Ordering::AcqRel) {
+            return;
+        }
+
+        let instance = self.instance.clone();
+        let obs_client = self.obs_client.clone();
+        let visual_mismatch = self.visual_mismatch.clone();
+        let alert_tx = self.alert_tx.clone();
+        let running = self.visual_check_running.clone();
+
+        tokio::spawn(async move {
+            let client_arc = obs_client.get_client_arc();
+            let client_lock = client_arc.read().await;
+            if let Some(client) = client_lock.as_ref() {
+                match Self::check_visual(client, &visual_mismatch, &scene_name, &master).await {
+                    Ok(Some(message)) => {
+                        let alert = DesyncAlert {
+                            id: uuid::Uuid::new_v4().to_string(),
+                            timestamp: chrono::Utc::now().timestamp_millis(),
+                            instance,
+                            scene_name,
+                            source_name: String::new(),
+                            message,
+                            severity: AlertSeverity::Error,
+                        };
+                        if let Err(e) = alert_tx.send(alert) {
+                            eprintln!("Failed to send desync alert: {}", e);
+                        }
+                    }
+                    Ok(None) => {}
+                    Err(e) => eprintln!("Failed to verify program output: {}", e),
+                }
+            }
+            running.store(false, Ordering::Release);
+        });
+    }
+
+    /// Alert message once the program output has differed from the master's
+    /// fingerprint for `VISUAL_MISMATCH_SUSTAIN_MS`, so transitions and brief
+    /// frame offsets stay quiet
+    async fn check_visual(
+        client: &obws::Client,
+        visual_mismatch: &RwLock<Option<VisualMismatch>>,
+        scene_name: &str,
+        master: &VisualFingerprint,
+    ) -> Result<Option<String>> {
+        // A different program scene is already reported by the structural check
+        let local_scene = client.scenes().current_program_scene().await
+            .context("Failed to get current scene")?;
+        if local_scene != scene_name {
+            *visual_mismatch.write().await = None;
+            return Ok(None);
+        }
+
+        let local = visual::scene_fingerprint(client, scene_name).await?;
+        let distance = local.distance(master);
+        let now = chrono::Utc::now().timestamp_millis();
+
+        let mut mismatch = visual_mismatch.write().await;
+        if distance <= VISUAL_DISTANCE_THRESHOLD {
+            *mismatch = None;
+            return Ok(None);
+        }
+
+        let state = mismatch.get_or_insert(VisualMismatch { since: now, alerted: false });
+        if state.alerted || now - state.since < VISUAL_MISMATCH_SUSTAIN_MS {
+            return Ok(None);
+        }
+        state.alerted = true;
+        let duration_secs = (now - state.since) / 1000;
+
+        Ok(Some(format!(
+            "Program output differs visually from master for {} s (distance {}, brightness {} vs {})",
+            duration_secs, distance, local.luma, master.luma
+        )))
+    }
+
+    /// Make a local output match the master's. Transitional states (starting,
+    /// stopping, reconnecting) are left alone; the final state follows.
+    async fn apply_output_state(
+        &self,
+        client: &obws::Client,
+        output: &str,
+        active: bool,
+        state: &str,
+    ) -> Result<()> {
+        let status = OBSCommands::get_output_status(client).await?;
+        let local = &status[output];
+        if local.is_null() {
+            return Err(anyhow::anyhow!("{} is not available locally", output));
+        }
+
+        if matches!(state, "starting" | "stopping" | "reconnecting") {
+            return Ok(());
+        }
+
+        if local["active"].as_bool() != Some(active) {
+            OBSCommands::set_output_active(client, output, active).await?;
+            println!("{} {}", if active { "Started" } else { "Stopped" }, output);
+        }
+
+        if output == "record" && active {
+            let paused = state == "paused";
+            if local["paused"].as_bool().unwrap_or(false) != paused {
+                OBSCommands::set_record_paused(client, paused).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Send the local output state to the master
+    async fn report_outputs(
+        instance: &str,
+        client: &obws::Client,
+        report_tx: &mpsc::UnboundedSender<SyncMessage>,
+    ) {
+        match OBSCommands::get_output_status(client).await {
+            Ok(outputs) => {
+                let msg = SyncMessage::new(
+                    SyncMessageType::OutputStatus,
+                    SyncTargetType::Outputs,
+                    serde_json::json!({ "instance": instance, "outputs": outputs }),
+                );
+                let _ = report_tx.send(msg);
+            }
+            Err(e) => {
+                eprintln!("Failed to get output status: {}", e);
+            }
+        }
+    }
+
+    /// Compare the local scene collection and profile with the master's. When
+    /// configured to, switch to the same-named ones; otherwise raise an alert.
+    async fn apply_collection(&self, client: &obws::Client, payload: &serde_json::Value) -> Result<()> {
+        let settings = self.settings.read().await.clone();
+
+        if let Some(expected) = payload["scene_collection"].as_str() {
+            let (current, available) = OBSCommands::get_scene_collections(client).await?;
+            if current != expected {
+                if settings.follow_scene_collection && available.iter().any(|c| c == expected) {
+                    OBSCommands::set_current_scene_collection(client, expected).await?;
+                    println!("Switched scene collection to {}", expected);
+                } else {
+                    self.send_alert(
+                        String::new(),
+                        String::new(),
+                        format!(
+                            "Scene collection mismatch: local='{}', master='{}'{}",
+                            current,
+                            expected,
+                            if settings.follow_scene_collection { " (not available locally)" } else { "" }
+                        ),
+                        AlertSeverity::Error,
+                    )?;
+                }
+            }
+        }
+
+        if let Some(expected) = payload["profile"].as_str() {
+            let (current, available) = OBSCommands::get_profiles(client).await?;
+            if current != expected {
+                if settings.follow_profile && available.iter().any(|p| p == expected) {
+                    OBSCommands::set_current_profile(client, expected).await?;
+                    println!("Switched profile to {}", expected);
+                } else {
+                    self.send_alert(
+                        String::new(),
+                        String::new(),
+                        format!(
+                            "Profile mismatch: local='{}', master='{}'{}",
+                            current,
+                            expected,
+                            if settings.follow_profile { " (not available locally)" } else { "" }
+                        ),
+                        AlertSeverity::Warning,
+                    )?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Create every scene of the master's `scenes` list that is missing locally
+    async fn create_missing_scenes(&self, client: &obws::Client, scenes: &serde_json::Value) -> Result<()> {
+        let local_scenes = OBSCommands::get_scene_names(client).await?;
+
+        for scene in scenes.as_array().map(|s| s.as_slice()).unwrap_or_default() {
+            let Some(scene_name) = scene["name"].as_str() else {
+                continue;
+            };
+            if !local_scenes.iter().any(|name| name == scene_name) {
+                OBSCommands::create_scene(client, scene_name).await?;
+                println!("Created scene: {}", scene_name);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Switch the program scene using the master's transition. In studio mode the
+    /// scene goes to preview first and is taken live with the transition button.
+    /// Program scene a full state leaves the slave on: its own, unless a cut
+    /// made after it was collected already arrived
+    async fn program_scene_of_state(&self, message: &SyncMessage) -> Option<String> {
+        match self.last_cut.read().await.as_ref() {
+            Some((cut_at, scene_name)) if *cut_at > message.timestamp => Some(scene_name.clone()),
+            _ => message.payload["current_program_scene"].as_str().map(str::to_string),
+        }
+    }
+
+    async fn switch_program_scene(
+        &self,
+        client: &obws::Client,
+        scene_name: &str,
+        transition: &serde_json::Value,
+    ) -> Result<()> {
+        if transition.is_object() {
+            // A wrong transition is better than a missed cut, so keep going on failure
+            if let Err(e) = self.apply_transition(client, transition).await {
+                eprintln!("Failed to apply transition, switching with current one: {}", e);
+            }
+        }
+
+        if OBSCommands::get_studio_mode_enabled(client).await.unwrap_or(false) {
+            OBSCommands::set_current_preview_scene(client, scene_name).await?;
+            OBSCommands::trigger_studio_mode_transition(client).await
+        } else {
+            OBSCommands::set_current_program_scene(client, scene_name).await
+        }
+    }
+
+    /// Make the local current transition match the master's (name, duration, settings)
+    async fn apply_transition(&self, client: &obws::Client, transition: &serde_json::Value) -> Result<()> {
+        if self.applied_transition.read().await.as_ref() == Some(transition) {
+            return Ok(());
+        }
+
+        let name = transition["name"].as_str().context("Invalid transition name")?;
+        OBSCommands::set_current_transition(client, name).await?;
+
+        if !transition["fixed"].as_bool().unwrap_or(false) {
+            if let Some(duration_ms) = transition["duration_ms"].as_i64() {
+                OBSCommands::set_current_transition_duration(client, duration_ms).await?;
+            }
+        }
+        if transition["settings"].is_object() {
+            OBSCommands::set_current_transition_settings(client, &transition["settings"]).await?;
+        }
+
+        println!("Applied transition: {}", name);
+        *self.applied_transition.write().await = Some(transition.clone());
+        Ok(())
+    }
+
+    /// Set the preview scene. The preview only exists in studio mode, so it is
+    /// turned on first when configured to; otherwise the update is skipped.
+    async fn apply_preview_scene(&self, client: &obws::Client, scene_name: &str) -> Result<()> {
+        if !OBSCommands::get_studio_mode_enabled(client).await? {
+            if !self.settings.read().await.enable_studio_mode_for_preview {
+                println!("Studio mode is off, skipping preview scene: {}", scene_name);
+                return Ok(());
+            }
+            OBSCommands::set_studio_mode_enabled(client, true).await?;
+            println!("Enabled studio mode to apply preview scene");
+        }
+
+        OBSCommands::set_current_preview_scene(client, scene_name).await
+    }
+
+    /// Apply every mixer field present in `state`; absent fields are left unchanged
+    async fn apply_audio_state(
+        &self,
+        client: &obws::Client,
+        input_name: &str,
+        state: &serde_json::Value,
+    ) -> Result<()> {
+        if let Some(volume_db) = state["volume_db"].as_f64() {
+            OBSCommands::set_input_volume_db(client, input_name, volume_db).await?;
+        }
+        if let Some(muted) = state["muted"].as_bool() {
+            OBSCommands::set_input_muted(client, input_name, muted).await?;
+        }
+        if let Some(balance) = state["balance"].as_f64() {
+            OBSCommands::set_input_audio_balance(client, input_name, balance).await?;
+        }
+        if let Some(offset_ms) = state["sync_offset_ms"].as_i64() {
+            OBSCommands::set_input_audio_sync_offset(client, input_name, offset_ms).await?;
+        }
+        if let Some(monitor_type) = state["monitor_type"].as_str() {
+            let monitor_type = OBSCommands::monitor_type_from_str(monitor_type)
+                .context(format!("Unknown monitor type '{}'", monitor_type))?;
+            OBSCommands::set_input_audio_monitor_type(client, input_name, monitor_type).await?;
+        }
+        if let Some(tracks) = state["tracks"].as_array() {
+            let tracks: Vec<bool> = tracks.iter().map(|t| t.as_bool().unwrap_or(false)).collect();
+            OBSCommands::set_input_audio_tracks(client, input_name, &tracks).await?;
+        }
+        Ok(())
+    }
+
+    /// Mirror the master's media playback: replay the triggered action, or bring the
+    /// local state in line, then seek to where the master's cursor is now
+    async fn apply_media_state(
+        &self,
+        client: &obws::Client,
+        input_name: &str,
+        state: &serde_json::Value,
+        action: Option<&str>,
+    ) -> Result<()> {
+        let expected_state = state["state"].as_str().unwrap_or("");
+
+        let action = match action {
+            Some(action) => Some(action),
+            None => {
+                let local = OBSCommands::get_media_input_status(client, input_name).await?;
+                let local_state = local["state"].as_str().unwrap_or("");
+                match (expected_state, local_state) {
+                    ("playing", "playing") | ("paused", "paused") => None,
+                    ("playing", _) => Some("play"),
+                    ("paused", "playing") => Some("pause"),
+                    ("stopped", "playing") | ("stopped", "paused") => Some("stop"),
+                    _ => None,
+                }
+            }
+        };
+
+        if let Some(action) = action {
+            let media_action = OBSCommands::media_action_from_str(action)
+                .context(format!("Unknown media action '{}'", action))?;
+            OBSCommands::trigger_media_input_action(client, input_name, media_action).await?;
+        }
+
+        if matches!(expected_state, "playing" | "paused") {
+            let now = chrono::Utc::now().timestamp_millis();
+            if let Some(cursor_ms) = DiffDetector::expected_media_cursor(state, now) {
+                OBSCommands::set_media_input_cursor(client, input_name, cursor_ms).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Bring the filters of a source in line with the master's list: missing filters
+    /// are created, existing ones updated, and all are moved to the master's order.
+    /// Filters that only exist locally are left untouched.
+    async fn apply_filter_list(
+        &self,
+        client: &obws::Client,
+        source_name: &str,
+        filters: &[serde_json::Value],
+    ) -> Result<()> {
+        let local_filters = OBSCommands::get_source_filters(client, source_name).await?;
+
+        let mut ordered: Vec<&serde_json::Value> = filters.iter().collect();
+        ordered.sort_by_key(|f| f["index"].as_u64().unwrap_or(0));
+
+        for filter in ordered {
+            let filter_name = filter["name"].as_str().context("Invalid filter name")?;
+            let filter_kind = filter["kind"].as_str().unwrap_or("");
+            let settings = &filter["settings"];
+
+            let exists = local_filters
+                .iter()
+                .any(|f| f["name"].as_str() == Some(filter_name));
+            if exists {
+                OBSCommands::set_source_filter_settings(client, source_name, filter_name, settings).await?;
+            } else {
+                println!("Creating filter '{}' ({}) on {}", filter_name, filter_kind, source_name);
+                OBSCommands::create_source_filter(client, source_name, filter_name, filter_kind, settings).await?;
+            }
+
+            if let Some(enabled) = filter["enabled"].as_bool() {
+                OBSCommands::set_source_filter_enabled(client, source_name, filter_name, enabled).await?;
+            }
+            if let Some(index) = filter["index"].as_u64() {
+                OBSCommands::set_source_filter_index(client, source_name, filter_name, index as u32).await?;
+            }
+        }
+
         Ok(())
     }
 
+    /// Reorder scene items to match the master. Items are moved bottom-up, so each
+    /// `SetSceneItemIndex` lands on its final position.
+    async fn apply_scene_order(
+        &self,
+        client: &obws::Client,
+        scene_name: &str,
+        items: &[serde_json::Value],
+    ) -> Result<()> {
+        let mut order: Vec<(i64, u32)> = items
+            .iter()
+            .filter_map(|item| {
+                let scene_item_id = item["scene_item_id"].as_i64()?;
+                let index = item["index"].as_u64()?;
+                Some((scene_item_id, index as u32))
+            })
+            .collect();
+        order.sort_by_key(|(_, index)| *index);
+
+        let mut failed = 0;
+        for (scene_item_id, index) in order {
+            let scene_item_id = self.local_item_id(scene_name, scene_item_id).await;
+            if let Err(e) = OBSCommands::set_scene_item_index(client, scene_name, scene_item_id, index).await {
+                eprintln!("Failed to move item {} to index {}: {}", scene_item_id, index, e);
+                failed += 1;
+            }
+        }
+
+        if failed > 0 {
+            return Err(anyhow::anyhow!("{} item(s) could not be reordered", failed));
+        }
+        Ok(())
+    }
+
+    async fn apply_text_update(&self, message: &SyncMessage) -> Result<()> {
+        let input_name = message.payload["input_name"]
+            .as_str()
+            .context("Invalid input_name")?;
+        let text = message.payload["text"].as_str().unwrap_or("");
+        let captured_at = message.payload["captured_at"]
+            .as_i64()
+            .unwrap_or(message.timestamp);
+
+        let client_arc = self.obs_client.get_client_arc();
+        let client_lock = client_arc.read().await;
+        let client = client_lock.as_ref().context("OBS client not connected")?;
+
+        let settings = serde_json::json!({ "text": text });
+        if let Err(e) = OBSCommands::set_input_settings(client, input_name, &settings).await {
+            self.send_alert(
+                String::new(),
+                input_name.to_string(),
+                format!("Failed to update text: {}", e),
+                AlertSeverity::Warning,
+            )?;
+            return Ok(());
+        }
+        drop(client_lock);
+
+        let latency_ms = chrono::Utc::now().timestamp_millis() - captured_at;
+        self.text_latency.write().await.record(latency_ms);
+        Ok(())
+    }
+
+    async fn apply_input_settings(
+        &self,
+        client: &obws::Client,
+        input_name: &str,
+        settings: &serde_json::Value,
+    ) -> Result<()> {
+        if !settings.is_object() {
+            return Err(anyhow::anyhow!("Settings payload is not an object"));
+        }
+
+        // The master already strips machine-specific keys; filter again by
+        // the kind of the *local* input, so a peer claiming another kind can
+        // never repoint our local devices or windows
+        let (local_kind, _) = OBSCommands::get_input_settings(client, input_name).await?;
+        let settings = input_settings::filter_settings(&local_kind, settings);
+        OBSCommands::set_input_settings(client, input_name, &settings).await
+    }
+
     async fn handle_image_update(
         &self,
         client: &obws::Client,
@@ -416,6 +2069,7 @@ impl SlaveSync {
         let alert = DesyncAlert {
             id: uuid::Uuid::new_v4().to_string(),
             timestamp: chrono::Utc::now().timestamp_millis(),
+            instance: self.instance.clone(),
             scene_name,
             source_name,
             message,
@@ -425,3 +2079,122 @@ impl SlaveSync {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::obs::fake::FakeObs;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn capabilities_are_read_once_per_connection() {
+        let mut obs = FakeObs::start().await;
+        obs.set_available_requests(&["GetVersion", "SetInputMute"]);
+        let obs_client = Arc::new(OBSClient::new());
+        obs_client.connect(obs.config()).await.unwrap();
+
+        let (report_tx, _report_rx) = mpsc::unbounded_channel();
+        let (slave, mut alerts) = SlaveSync::new(
+            "test".to_string(),
+            obs_client.clone(),
+            Arc::new(RwLock::new(SlaveSyncSettings::default())),
+            report_tx,
+        );
+        let mute = || {
+            SyncMessage::new(
+                SyncMessageType::AudioUpdate,
+                SyncTargetType::Audio,
+                json!({ "input_name": "Mic", "muted": true }),
+            )
+        };
+
+        // Refused with a single alert while the connection lacks audio requests
+        slave.apply_sync_message(mute()).await.unwrap();
+        slave.apply_sync_message(mute()).await.unwrap();
+        let alert = alerts.try_recv().unwrap();
+        assert!(alert.message.contains("SetInputVolume"), "{}", alert.message);
+        assert!(alerts.try_recv().is_err());
+
+        // Capabilities are kept for the connection they were read on
+        let audio = capabilities::required_requests(&SyncTargetType::Audio);
+        obs.set_available_requests(audio);
+        slave.apply_sync_message(mute()).await.unwrap();
+        assert!(alerts.try_recv().is_err());
+
+        obs_client.connect(obs.config()).await.unwrap();
+        slave.apply_sync_message(mute()).await.unwrap();
+        let mut requests = Vec::new();
+        while let Ok((request_type, _)) = obs.requests.try_recv() {
+            requests.push(request_type);
+        }
+        assert_eq!(requests, vec!["SetInputMute"]);
+    }
+
+    #[tokio::test]
+    async fn state_sync_applies_group_child_transform() {
+        let mut obs = FakeObs::start().await;
+        let obs_client = Arc::new(OBSClient::new());
+        obs_client.connect(obs.config()).await.unwrap();
+
+        let (report_tx, _report_rx) = mpsc::unbounded_channel();
+        let (slave, _alerts) = SlaveSync::new(
+            "test".to_string(),
+            obs_client,
+            Arc::new(RwLock::new(SlaveSyncSettings::default())),
+            report_tx,
+        );
+
+        let child_transform = json!({
+            "position_x": 120.0,
+            "position_y": 80.0,
+            "rotation": 0.0,
+            "scale_x": 0.5,
+            "scale_y": 0.5,
+            "width": 960.0,
+            "height": 540.0,
+            "crop_left": 10,
+            "crop_right": 0,
+            "crop_top": 0,
+            "crop_bottom": 20,
+        });
+        let state = SyncMessage::new(
+            SyncMessageType::StateSync,
+            SyncTargetType::Program,
+            json!({
+                "scenes": [{
+                    "name": "Main",
+                    "items": [{
+                        "source_name": "Overlay",
+                        "source_type": "group",
+                        "scene_item_id": 1,
+                        "children": [{
+                            "source_name": "Logo",
+                            "source_type": "image_source",
+                            "scene_item_id": 7,
+                            "transform": child_transform,
+                        }],
+                    }],
+                }],
+            }),
+        );
+        slave.apply_sync_message(state).await.unwrap();
+
+        // Every request was answered, so all of them are in the channel
+        let mut transforms = Vec::new();
+        while let Ok((request_type, data)) = obs.requests.try_recv() {
+            if request_type == "SetSceneItemTransform" {
+                transforms.push(data);
+            }
+        }
+        assert_eq!(transforms.len(), 1, "expected one transform request: {:?}", transforms);
+        let request = &transforms[0];
+        assert_eq!(request["sceneName"], "Overlay");
+        assert_eq!(request["sceneItemId"], 7);
+        let transform = &request["sceneItemTransform"];
+        assert_eq!(transform["positionX"], 120.0);
+        assert_eq!(transform["positionY"], 80.0);
+        assert_eq!(transform["scaleX"], 0.5);
+        assert_eq!(transform["cropLeft"], 10);
+        assert_eq!(transform["cropBottom"], 20);
+    }
+}
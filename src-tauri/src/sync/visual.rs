@@ -0,0 +1,209 @@
+use crate::obs::commands::OBSCommands;
+use anyhow::{Context, Result};
+
+/// Screenshot size used for visual checks; small keeps the render and transfer cheap
+pub const SCREENSHOT_WIDTH: u32 = 64;
+pub const SCREENSHOT_HEIGHT: u32 = 36;
+/// BMP is uncompressed, so fingerprints need no image decoder
+pub const SCREENSHOT_FORMAT: &str = "bmp";
+
+/// Perceptual fingerprint of a frame: a 64-bit difference hash (structure)
+/// plus the mean luma (overall brightness), since a flat black frame and a
+/// flat grey frame have the same difference hash
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VisualFingerprint {
+    pub hash: u64,
+    pub luma: u8,
+}
+
+impl VisualFingerprint {
+    /// Fingerprint of an uncompressed 24 or 32-bit BMP
+    pub fn from_bmp(data: &[u8]) -> Option<Self> {
+        let (width, height, gray) = decode_bmp_gray(data)?;
+        let luma = (gray.iter().map(|&v| u64::from(v)).sum::<u64>() / gray.len() as u64) as u8;
+        Some(Self {
+            hash: dhash(width, height, &gray),
+            luma,
+        })
+    }
+
+    /// Differing hash bits, plus one per 8 levels of brightness difference
+    pub fn distance(&self, other: &Self) -> u32 {
+        (self.hash ^ other.hash).count_ones() + u32::from(self.luma.abs_diff(other.luma)) / 8
+    }
+
+    pub fn hash_hex(&self) -> String {
+        format!("{:016x}", self.hash)
+    }
+}
+
+/// Screenshot the given scene and fingerprint it
+pub async fn scene_fingerprint(client: &obws::Client, scene_name: &str) -> Result<VisualFingerprint> {
+    let data = OBSCommands::get_source_screenshot(
+        client,
+        scene_name,
+        SCREENSHOT_FORMAT,
+        SCREENSHOT_WIDTH,
+        SCREENSHOT_HEIGHT,
+    )
+    .await?;
+    VisualFingerprint::from_bmp(&data).context("Unsupported screenshot bitmap")
+}
+
+/// Decode an uncompressed 24/32-bit BMP to grayscale, top row first
+fn decode_bmp_gray(data: &[u8]) -> Option<(usize, usize, Vec<u8>)> {
+    let u16_at = |i: usize| Some(u16::from_le_bytes(data.get(i..i + 2)?.try_into().ok()?));
+    let u32_at = |i: usize| Some(u32::from_le_bytes(data.get(i..i + 4)?.try_into().ok()?));
+    let i32_at = |i: usize| Some(i32::from_le_bytes(data.get(i..i + 4)?.try_into().ok()?));
+
+    if data.get(0..2)? != b"BM" {
+        return None;
+    }
+    let offset = u32_at(10)? as usize;
+    let width = i32_at(18)?;
+    let height = i32_at(22)?;
+    let bits_per_pixel = u16_at(28)?;
+    // 0 = BI_RGB, 3 = BI_BITFIELDS (32-bit BGRA as written by Qt)
+    let compression = u32_at(30)?;
+    if width <= 0 || height == 0 || !matches!(bits_per_pixel, 24 | 32) || !matches!(compression, 0 | 3) {
+        return None;
+    }
+
+    let width = width as usize;
+    // Positive height means rows are stored bottom-up
+    let bottom_up = height > 0;
+    let height = height.unsigned_abs() as usize;
+    let bytes_per_pixel = usize::from(bits_per_pixel / 8);
+    let stride = (width * bytes_per_pixel + 3) & !3;
+
+    let mut gray = Vec::with_capacity(width * height);
+    for y in 0..height {
+        let row = if bottom_up { height - 1 - y } else { y };
+        let row_start = offset + row * stride;
+        let row_data = data.get(row_start..row_start + width * bytes_per_pixel)?;
+        for pixel in row_data.chunks_exact(bytes_per_pixel) {
+            let (b, g, r) = (u32::from(pixel[0]), u32::from(pixel[1]), u32::from(pixel[2]));
+            gray.push(((r * 299 + g * 587 + b * 114) / 1000) as u8);
+        }
+    }
+    Some((width, height, gray))
+}
+
+/// Difference hash over a 9x8 grid of cell averages: each bit tells whether a
+/// cell is brighter than its right neighbour
+fn dhash(width: usize, height: usize, gray: &[u8]) -> u64 {
+    let cell = |cx: usize, cy: usize| -> u32 {
+        let (x0, x1) = (cx * width / 9, ((cx + 1) * width / 9).max(cx * width / 9 + 1));
+        let (y0, y1) = (cy * height / 8, ((cy + 1) * height / 8).max(cy * height / 8 + 1));
+        let mut sum = 0u32;
+        for y in y0..y1.min(height) {
+            for x in x0..x1.min(width) {
+                sum += u32::from(gray[y * width + x]);
+            }
+        }
+        sum / ((x1 - x0) * (y1 - y0)) as u32
+    };
+
+    let mut hash = 0u64;
+    for cy in 0..8 {
+        for cx in 0..8 {
+            hash <<= 1;
+            if cell(cx, cy) > cell(cx + 1, cy) {
+                hash |= 1;
+            }
+        }
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WHITE: [u8; 3] = [255, 255, 255];
+    const BLACK: [u8; 3] = [0, 0, 0];
+
+    /// BI_RGB bitmap from BGR pixels given top row first; a negative
+    /// `height` writes the rows top-down
+    fn bmp(width: usize, height: i32, bits_per_pixel: u16, pixels: &[[u8; 3]]) -> Vec<u8> {
+        let bytes_per_pixel = usize::from(bits_per_pixel / 8);
+        let stride = (width * bytes_per_pixel + 3) & !3;
+        let rows = height.unsigned_abs() as usize;
+
+        let mut data = Vec::new();
+        data.extend_from_slice(b"BM");
+        data.extend_from_slice(&((54 + stride * rows) as u32).to_le_bytes());
+        data.extend_from_slice(&[0; 4]);
+        data.extend_from_slice(&54u32.to_le_bytes());
+        data.extend_from_slice(&40u32.to_le_bytes());
+        data.extend_from_slice(&(width as i32).to_le_bytes());
+        data.extend_from_slice(&height.to_le_bytes());
+        data.extend_from_slice(&1u16.to_le_bytes());
+        data.extend_from_slice(&bits_per_pixel.to_le_bytes());
+        data.extend_from_slice(&[0; 24]);
+
+        for y in 0..rows {
+            let row = if height > 0 { rows - 1 - y } else { y };
+            let start = data.len();
+            for pixel in &pixels[row * width..(row + 1) * width] {
+                data.extend_from_slice(pixel);
+                if bytes_per_pixel == 4 {
+                    data.push(255);
+                }
+            }
+            data.resize(start + stride, 0);
+        }
+        data
+    }
+
+    /// 18x8 frame, white in the left nine columns and black in the rest
+    fn half_white() -> Vec<[u8; 3]> {
+        (0..8)
+            .flat_map(|_| (0..18).map(|x| if x < 9 { WHITE } else { BLACK }))
+            .collect()
+    }
+
+    #[test]
+    fn decodes_rows_top_first() {
+        let pixels = [WHITE, BLACK, BLACK, [0, 0, 255]];
+        let expected = [255, 0, 0, 76];
+
+        let (width, height, gray) = decode_bmp_gray(&bmp(2, 2, 24, &pixels)).unwrap();
+        assert_eq!((width, height), (2, 2));
+        assert_eq!(gray, expected);
+
+        let (_, _, gray) = decode_bmp_gray(&bmp(2, -2, 32, &pixels)).unwrap();
+        assert_eq!(gray, expected);
+    }
+
+    #[test]
+    fn fingerprints_known_bitmap() {
+        // Each 8-cell row only drops in brightness at cells 3 -> 4 (the half
+        // white cell) and 4 -> 5
+        let fingerprint = VisualFingerprint::from_bmp(&bmp(18, 8, 24, &half_white())).unwrap();
+        assert_eq!(fingerprint.hash, 0x1818_1818_1818_1818);
+        assert_eq!(fingerprint.luma, 127);
+        assert_eq!(fingerprint.hash_hex(), "1818181818181818");
+
+        let top_down = VisualFingerprint::from_bmp(&bmp(18, -8, 32, &half_white())).unwrap();
+        assert_eq!(top_down, fingerprint);
+    }
+
+    #[test]
+    fn distance_counts_hash_bits_and_brightness() {
+        let a = VisualFingerprint { hash: 0b1011, luma: 100 };
+        let b = VisualFingerprint { hash: 0b0001, luma: 116 };
+        assert_eq!(a.distance(&a), 0);
+        assert_eq!(a.distance(&b), 2 + 2);
+    }
+
+    #[test]
+    fn rejects_unsupported_bitmaps() {
+        assert!(VisualFingerprint::from_bmp(b"PNG").is_none());
+        assert!(VisualFingerprint::from_bmp(&bmp(2, 2, 16, &[WHITE; 4])).is_none());
+
+        let mut truncated = bmp(18, 8, 24, &half_white());
+        truncated.truncate(100);
+        assert!(VisualFingerprint::from_bmp(&truncated).is_none());
+    }
+}
@@ -10,6 +10,8 @@ import { useDesyncAlerts } from "./hooks/useDesyncAlerts";
 import { ConnectionStatus } from "./components/ConnectionStatus";
 import { MasterControl } from "./components/MasterControl";
 import { SlaveMonitor } from "./components/SlaveMonitor";
+import { SlaveSyncOptions } from "./components/SlaveSyncOptions";
+import { HotkeyForwardingOptions } from "./components/HotkeyForwardingOptions";
 import { SyncTargetSelector } from "./components/SyncTargetSelector";
 import { AlertPanel } from "./components/AlertPanel";
 import { OBSSourceList } from "./components/OBSSourceList";
@@ -268,6 +270,30 @@ function App() {
                         <span className="info-value">{obsStatus.obsVersion}</span>
                       </div>
                     )}
+                    {obsStatus.obsWebSocketVersion && (
+                      <div className="info-item">
+                        <span className="info-label">WebSocket:</span>
+                        <span className="info-value">
+                          {obsStatus.obsWebSocketVersion} (RPC {obsStatus.rpcVersion ?? "?"})
+                        </span>
+                      </div>
+                    )}
+                    {obsStatus.platformDescription && (
+                      <div className="info-item">
+                        <span className="info-label">プラットフォーム:</span>
+                        <span className="info-value">{obsStatus.platformDescription}</span>
+                      </div>
+                    )}
+                    {obsStatus.canvas && (
+                      <div className="info-item">
+                        <span className="info-label">キャンバス:</span>
+                        <span className="info-value">
+                          {obsStatus.canvas.baseWidth}x{obsStatus.canvas.baseHeight} →{" "}
+                          {obsStatus.canvas.outputWidth}x{obsStatus.canvas.outputHeight} @{" "}
+                          {(obsStatus.canvas.fpsNumerator / obsStatus.canvas.fpsDenominator).toFixed(2)} fps
+                        </span>
+                      </div>
+                    )}
                   </div>
                   <button onClick={handleDisconnectOBS} className="btn-danger">
                     切断
@@ -286,6 +312,8 @@ function App() {
                   </h2>
                 </div>
                 <SyncTargetSelector />
+                {appMode === AppMode.Slave && <SlaveSyncOptions />}
+                {appMode === AppMode.Master && <HotkeyForwardingOptions />}
               </section>
             )}
 
@@ -1,4 +1,5 @@
 import { useState, useMemo } from "react";
+import { DEFAULT_OBS_INSTANCE } from "../types/obs";
 import { DesyncAlert } from "../types/sync";
 
 interface AlertPanelProps {
@@ -128,8 +129,13 @@ export const AlertPanel = ({ alerts, onClearAlert, onClearAllAlerts }: AlertPane
                     </button>
                   </div>
                   <div className="alert-content">
-                    {(alert.sceneName || alert.sourceName) && (
+                    {(alert.sceneName || alert.sourceName || alert.instance !== DEFAULT_OBS_INSTANCE) && (
                       <div className="alert-location">
+                        {alert.instance !== DEFAULT_OBS_INSTANCE && (
+                          <span className="location-tag location-instance">
+                            🖥️ {alert.instance}
+                          </span>
+                        )}
                         {alert.sceneName && (
                           <span className="location-tag location-scene">
                             📺 {alert.sceneName}
@@ -440,6 +446,11 @@ export const AlertPanel = ({ alerts, onClearAlert, onClearAllAlerts }: AlertPane
           border-color: var(--primary-color);
         }
 
+        .location-tag.location-instance {
+          color: var(--text-secondary);
+          border-color: var(--border-color);
+        }
+
         .location-tag.location-source {
           color: var(--warning-color);
           border-color: var(--warning-color);
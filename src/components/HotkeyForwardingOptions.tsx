@@ -0,0 +1,200 @@
+import { useState } from "react";
+import { useHotkeyForwarding } from "../hooks/useHotkeyForwarding";
+import { KeySequence } from "../types/sync";
+
+const emptySequence: KeySequence = {
+  key: "",
+  shift: false,
+  control: false,
+  alt: false,
+  command: false,
+};
+
+const formatSequence = (sequence: KeySequence) =>
+  [
+    sequence.control && "Ctrl",
+    sequence.alt && "Alt",
+    sequence.shift && "Shift",
+    sequence.command && "Cmd",
+    sequence.key,
+  ]
+    .filter(Boolean)
+    .join(" + ");
+
+export const HotkeyForwardingOptions = () => {
+  const { forwarding, hotkeys, error, updateForwarding, triggerHotkey } = useHotkeyForwarding();
+  const [newSequence, setNewSequence] = useState<KeySequence>(emptySequence);
+
+  const handleToggleHotkey = async (name: string) => {
+    const hotkeyNames = forwarding.hotkeyNames.includes(name)
+      ? forwarding.hotkeyNames.filter((n) => n !== name)
+      : [...forwarding.hotkeyNames, name];
+    try {
+      await updateForwarding({ ...forwarding, hotkeyNames });
+    } catch (error) {
+      console.error("Failed to update hotkey forwarding:", error);
+    }
+  };
+
+  const handleAddSequence = async () => {
+    if (!newSequence.key) return;
+    try {
+      await updateForwarding({
+        ...forwarding,
+        keySequences: [...forwarding.keySequences, newSequence],
+      });
+      setNewSequence(emptySequence);
+    } catch (error) {
+      console.error("Failed to update hotkey forwarding:", error);
+    }
+  };
+
+  const handleRemoveSequence = async (index: number) => {
+    try {
+      await updateForwarding({
+        ...forwarding,
+        keySequences: forwarding.keySequences.filter((_, i) => i !== index),
+      });
+    } catch (error) {
+      console.error("Failed to update hotkey forwarding:", error);
+    }
+  };
+
+  const handleTrigger = async (hotkeyName?: string, keySequence?: KeySequence) => {
+    try {
+      await triggerHotkey(hotkeyName, keySequence);
+    } catch (error) {
+      console.error("Failed to trigger hotkey:", error);
+    }
+  };
+
+  return (
+    <div className="hotkey-forwarding">
+      <h3 className="hotkey-title">ホットキー転送</h3>
+      <p className="hotkey-note">
+        チェックしたホットキーをMasterから発火すると、すべてのSlaveでも同じホットキーが発火します
+      </p>
+
+      <div className="hotkey-list">
+        {hotkeys.map((name) => (
+          <label key={name} className="hotkey-row">
+            <input
+              type="checkbox"
+              checked={forwarding.hotkeyNames.includes(name)}
+              onChange={() => handleToggleHotkey(name)}
+            />
+            <span className="hotkey-name">{name}</span>
+            {forwarding.hotkeyNames.includes(name) && (
+              <button className="btn-ghost" onClick={() => handleTrigger(name)}>
+                発火
+              </button>
+            )}
+          </label>
+        ))}
+      </div>
+
+      <h4 className="hotkey-subtitle">キーシーケンス</h4>
+      {forwarding.keySequences.map((sequence, index) => (
+        <div key={index} className="hotkey-row">
+          <code className="hotkey-name">{formatSequence(sequence)}</code>
+          <button className="btn-ghost" onClick={() => handleTrigger(undefined, sequence)}>
+            発火
+          </button>
+          <button className="btn-ghost" onClick={() => handleRemoveSequence(index)}>
+            削除
+          </button>
+        </div>
+      ))}
+      <div className="hotkey-row">
+        <input
+          type="text"
+          value={newSequence.key}
+          onChange={(e) => setNewSequence({ ...newSequence, key: e.target.value })}
+          placeholder="OBS_KEY_F13"
+        />
+        {(["control", "alt", "shift", "command"] as const).map((modifier) => (
+          <label key={modifier} className="hotkey-modifier">
+            <input
+              type="checkbox"
+              checked={newSequence[modifier]}
+              onChange={() => setNewSequence({ ...newSequence, [modifier]: !newSequence[modifier] })}
+            />
+            {modifier}
+          </label>
+        ))}
+        <button className="btn-ghost" onClick={handleAddSequence} disabled={!newSequence.key}>
+          追加
+        </button>
+      </div>
+
+      {error && <p className="hotkey-error">{error}</p>}
+
+      <style>{`
+        .hotkey-forwarding {
+          display: flex;
+          flex-direction: column;
+          gap: 0.75rem;
+          margin-top: 1.5rem;
+        }
+
+        .hotkey-title {
+          margin: 0;
+          font-size: 1rem;
+          font-weight: 700;
+          color: var(--text-primary);
+        }
+
+        .hotkey-note {
+          margin: 0;
+          font-size: 0.875rem;
+          color: var(--text-secondary);
+        }
+
+        .hotkey-list {
+          display: flex;
+          flex-direction: column;
+          gap: 0.5rem;
+          max-height: 16rem;
+          overflow-y: auto;
+        }
+
+        .hotkey-subtitle {
+          margin: 0.5rem 0 0;
+          font-size: 0.875rem;
+          font-weight: 700;
+          color: var(--text-primary);
+        }
+
+        .hotkey-row {
+          display: flex;
+          align-items: center;
+          gap: 0.75rem;
+          padding: 0.5rem 1rem;
+          background: var(--bg-color);
+          border: 1px solid var(--border-color);
+          border-radius: 0.5rem;
+        }
+
+        .hotkey-name {
+          flex: 1;
+          font-size: 0.875rem;
+          color: var(--text-primary);
+        }
+
+        .hotkey-modifier {
+          display: flex;
+          align-items: center;
+          gap: 0.25rem;
+          font-size: 0.75rem;
+          color: var(--text-secondary);
+        }
+
+        .hotkey-error {
+          margin: 0;
+          font-size: 0.875rem;
+          color: var(--danger-color);
+        }
+      `}</style>
+    </div>
+  );
+};
@@ -1,17 +1,95 @@
-import { useState } from "react";
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
 import { useNetworkStatus } from "../hooks/useNetworkStatus";
-import { ConnectionState } from "../types/network";
+import {
+  ClientQueueMetrics,
+  ConnectionState,
+  SlowClientPolicy,
+  TransportConfig,
+} from "../types/network";
+import { DEFAULT_OBS_INSTANCE, OBSOutputState } from "../types/obs";
+import { SlaveOutputReport } from "../types/sync";
+import { OBSInstanceList } from "./OBSInstanceList";
+import { TransportSelector } from "./TransportSelector";
+
+const outputLabels = [
+  { key: "stream", label: "配信" },
+  { key: "record", label: "録画" },
+  { key: "replay_buffer", label: "リプレイ" },
+  { key: "virtualcam", label: "仮想カメラ" },
+] as const;
+
+const defaultBackpressure = {
+  maxQueueMessages: 1024,
+  maxQueueBytes: 64 * 1024 * 1024,
+};
+
+const formatOutputState = (state: OBSOutputState | null) => {
+  if (!state) return "—";
+  if (state.paused) return "⏸️";
+  return state.active ? "🔴" : "⚪";
+};
 
 export const MasterControl = () => {
   const [port, setPort] = useState(8080);
+  const [autoPort, setAutoPort] = useState(false);
+  const [bindAddress, setBindAddress] = useState("0.0.0.0");
   const [isStarting, setIsStarting] = useState(false);
   const [isStopping, setIsStopping] = useState(false);
+  const [sourceInstance, setSourceInstance] = useState(DEFAULT_OBS_INSTANCE);
+  const [transport, setTransport] = useState<TransportConfig>({ kind: "webSocket" });
+  const [slaveOutputs, setSlaveOutputs] = useState<SlaveOutputReport[]>([]);
+  const [slowClientPolicy, setSlowClientPolicy] = useState<SlowClientPolicy>("resync");
+  const [queueMetrics, setQueueMetrics] = useState<ClientQueueMetrics[]>([]);
   const { status, startMasterServer, stopMasterServer } = useNetworkStatus();
 
+  const isRunning = status.state === ConnectionState.Connected;
+
+  useEffect(() => {
+    if (!isRunning) {
+      setQueueMetrics([]);
+      return;
+    }
+    const update = () => {
+      invoke<ClientQueueMetrics[]>("get_client_queue_metrics")
+        .then(setQueueMetrics)
+        .catch((err) => console.error("Failed to get client queue metrics:", err));
+    };
+    update();
+    const interval = window.setInterval(update, 1000);
+    return () => clearInterval(interval);
+  }, [isRunning]);
+
+  useEffect(() => {
+    if (!isRunning) {
+      setSlaveOutputs([]);
+      return;
+    }
+    const update = () => {
+      invoke<SlaveOutputReport[]>("get_slave_output_status")
+        .then(setSlaveOutputs)
+        .catch((err) => console.error("Failed to get slave output status:", err));
+    };
+    update();
+    const interval = window.setInterval(update, 2000);
+    return () => clearInterval(interval);
+  }, [isRunning]);
+
+  const handleSaveReplay = async () => {
+    try {
+      await invoke("save_replay_buffer");
+    } catch (error) {
+      console.error("Failed to save replay buffer:", error);
+    }
+  };
+
   const handleStart = async () => {
     setIsStarting(true);
     try {
-      await startMasterServer(port);
+      await startMasterServer(autoPort ? 0 : port, sourceInstance, transport, bindAddress, {
+        ...defaultBackpressure,
+        slowClientPolicy,
+      });
     } catch (error) {
       console.error("Failed to start master server:", error);
     } finally {
@@ -36,25 +114,90 @@ export const MasterControl = () => {
   return (
     <div className="control-content">
       <div className="control-form">
-        <div className="form-group">
-          <label htmlFor="master-port">
-            <span className="label-icon">🔌</span>
-            リスニングポート
-          </label>
-          <input
-            id="master-port"
-            type="number"
-            value={port}
-            onChange={(e) => setPort(Number(e.target.value))}
-            disabled={isConnected || isStarting}
-            min={1024}
-            max={65535}
-            placeholder="8080"
-          />
-          <span className="input-hint">
-            Slaveノードが接続するポート番号（1024-65535）
-          </span>
-        </div>
+        <TransportSelector
+          transport={transport}
+          onChange={setTransport}
+          disabled={isConnected || isStarting}
+        />
+
+        {transport.kind === "webSocket" && (
+          <div className="form-group">
+            <label htmlFor="master-bind">
+              <span className="label-icon">🌐</span>
+              待ち受けアドレス
+            </label>
+            <input
+              id="master-bind"
+              type="text"
+              value={bindAddress}
+              onChange={(e) => setBindAddress(e.target.value)}
+              disabled={isConnected || isStarting}
+              placeholder="0.0.0.0"
+            />
+            <span className="input-hint">
+              0.0.0.0 はすべてのIPv4インターフェース、:: はIPv6です。特定のNICだけで待ち受ける場合はそのIPアドレスを入力してください
+            </span>
+          </div>
+        )}
+
+        {transport.kind === "webSocket" && (
+          <div className="form-group">
+            <label htmlFor="master-port">
+              <span className="label-icon">🔌</span>
+              リスニングポート
+            </label>
+            <input
+              id="master-port"
+              type="number"
+              value={port}
+              onChange={(e) => setPort(Number(e.target.value))}
+              disabled={isConnected || isStarting || autoPort}
+              min={1024}
+              max={65535}
+              placeholder="8080"
+            />
+            <label className="auto-port">
+              <input
+                type="checkbox"
+                checked={autoPort}
+                onChange={(e) => setAutoPort(e.target.checked)}
+                disabled={isConnected || isStarting}
+              />
+              空いているポートを自動で選ぶ
+            </label>
+            <span className="input-hint">
+              Slaveノードが接続するポート番号（1024-65535）
+            </span>
+          </div>
+        )}
+
+        {transport.kind === "webSocket" && (
+          <div className="form-group">
+            <label htmlFor="slow-client-policy">
+              <span className="label-icon">🐢</span>
+              遅いSlaveの扱い
+            </label>
+            <select
+              id="slow-client-policy"
+              value={slowClientPolicy}
+              onChange={(e) => setSlowClientPolicy(e.target.value as SlowClientPolicy)}
+              disabled={isConnected || isStarting}
+            >
+              <option value="resync">溜まった更新を破棄して全体を再同期</option>
+              <option value="disconnect">切断する</option>
+            </select>
+            <span className="input-hint">
+              送信キューが上限（{defaultBackpressure.maxQueueMessages}件 / {defaultBackpressure.maxQueueBytes / 1024 / 1024}MB）を超えたSlaveへの対応
+            </span>
+          </div>
+        )}
+
+        <OBSInstanceList
+          selectionMode="single"
+          selected={[sourceInstance]}
+          onChange={(selected) => setSourceInstance(selected[0] ?? DEFAULT_OBS_INSTANCE)}
+          disabled={isConnected || isStarting}
+        />
 
         <div className="control-actions">
           {!isConnected && !isConnecting ? (
@@ -105,8 +248,8 @@ export const MasterControl = () => {
           </div>
           <div className="status-panel-content">
             <div className="status-item">
-              <span className="status-label">ポート:</span>
-              <span className="status-value">{port}</span>
+              <span className="status-label">待ち受け:</span>
+              <span className="status-value">{status.listenAddress ?? "OBS中継"}</span>
             </div>
             <div className="status-item">
               <span className="status-label">接続中のクライアント:</span>
@@ -114,14 +257,56 @@ export const MasterControl = () => {
                 {status.connectedClients || 0} 台
               </span>
             </div>
+            {queueMetrics.map((metrics) => (
+              <div key={metrics.clientId} className="status-item">
+                <span className="status-label">送信キュー {metrics.clientId}:</span>
+                <span className="status-value">
+                  {metrics.depth} 件（{(metrics.bytes / 1024).toFixed(0)} KB / 最大 {metrics.maxDepth} 件 / 統合 {metrics.coalesced} / 溢れ {metrics.overflows}）
+                </span>
+              </div>
+            ))}
             <div className="status-item">
               <span className="status-label">接続URL:</span>
-              <code className="status-code">ws://&lt;your-ip&gt;:{port}</code>
+              <code className="status-code">
+                ws://&lt;your-ip&gt;:{status.listenAddress?.split(":").pop() ?? port}
+              </code>
             </div>
           </div>
         </div>
       )}
 
+      {isRunning && (
+        <div className="status-panel status-panel-outputs">
+          <div className="status-panel-header">
+            <span className="status-icon">🔴</span>
+            <h4>Slaveの出力状態</h4>
+            <button onClick={handleSaveReplay} className="btn-ghost btn-replay">
+              💾 リプレイを保存
+            </button>
+          </div>
+          <div className="status-panel-content">
+            {slaveOutputs.length === 0 ? (
+              <p className="input-hint">出力状態を報告しているSlaveはありません</p>
+            ) : (
+              slaveOutputs.map((report) => (
+                <div key={`${report.clientId}/${report.instance}`} className="status-item">
+                  <span className="status-label">
+                    {report.clientId.slice(0, 8)} / {report.instance}
+                  </span>
+                  <span className="status-value">
+                    {outputLabels.map(({ key, label }) => (
+                      <span key={key} className="output-badge">
+                        {label} {formatOutputState(report.outputs[key])}
+                      </span>
+                    ))}
+                  </span>
+                </div>
+              ))
+            )}
+          </div>
+        </div>
+      )}
+
       {status.lastError && (
         <div className="status-panel status-panel-error">
           <div className="status-panel-header">
@@ -185,6 +370,19 @@ export const MasterControl = () => {
           margin-bottom: 1rem;
         }
 
+        .status-panel-outputs {
+          border-color: var(--border-color);
+        }
+
+        .btn-replay {
+          margin-left: auto;
+        }
+
+        .output-badge {
+          margin-left: 0.75rem;
+          font-size: 0.875rem;
+        }
+
         .status-panel-header h4 {
           font-size: 1.125rem;
           font-weight: 700;
@@ -238,6 +436,14 @@ export const MasterControl = () => {
           font-size: 0.875rem;
           color: var(--primary-light);
         }
+
+        .auto-port {
+          display: flex;
+          align-items: center;
+          gap: 0.5rem;
+          font-size: 0.875rem;
+          color: var(--text-secondary);
+        }
       `}</style>
     </div>
   );
@@ -0,0 +1,164 @@
+import { useState } from "react";
+import { useOBSInstances } from "../hooks/useOBSInstances";
+import { DEFAULT_OBS_INSTANCE } from "../types/obs";
+
+interface OBSInstanceListProps {
+  // "single": Master source, "multiple": Slave targets
+  selectionMode: "single" | "multiple";
+  selected: string[];
+  onChange: (selected: string[]) => void;
+  disabled?: boolean;
+}
+
+export const OBSInstanceList = ({ selectionMode, selected, onChange, disabled }: OBSInstanceListProps) => {
+  const { instances, error, connectInstance, disconnectInstance, removeInstance } = useOBSInstances();
+  const [name, setName] = useState("");
+  const [host, setHost] = useState("localhost");
+  const [port, setPort] = useState(4456);
+  const [password, setPassword] = useState("");
+
+  const handleSelect = (instance: string) => {
+    if (selectionMode === "single") {
+      onChange([instance]);
+    } else if (selected.includes(instance)) {
+      onChange(selected.filter((n) => n !== instance));
+    } else {
+      onChange([...selected, instance]);
+    }
+  };
+
+  const handleAdd = async () => {
+    try {
+      await connectInstance(name, { host, port, password: password || undefined });
+      setName("");
+      setPassword("");
+    } catch (error) {
+      console.error("Failed to connect OBS instance:", error);
+    }
+  };
+
+  const handleRemove = async (instance: string) => {
+    try {
+      await removeInstance(instance);
+      onChange(selected.filter((n) => n !== instance));
+    } catch (error) {
+      console.error("Failed to remove OBS instance:", error);
+    }
+  };
+
+  const handleDisconnect = async (instance: string) => {
+    try {
+      await disconnectInstance(instance);
+    } catch (error) {
+      console.error("Failed to disconnect OBS instance:", error);
+    }
+  };
+
+  return (
+    <div className="form-group obs-instance-list">
+      <label>
+        <span className="label-icon">🖥️</span>
+        {selectionMode === "single" ? "同期元のOBSインスタンス" : "同期先のOBSインスタンス"}
+      </label>
+      {instances.map(({ name: instance, status }) => (
+        <div key={instance} className="instance-row">
+          <input
+            type={selectionMode === "single" ? "radio" : "checkbox"}
+            checked={selected.includes(instance)}
+            onChange={() => handleSelect(instance)}
+            disabled={disabled || !status.connected}
+          />
+          <span className="instance-name">{instance}</span>
+          <span className="instance-status">
+            {status.connected ? `🟢 OBS ${status.obsVersion ?? ""}` : "⚪ 未接続"}
+          </span>
+          {instance !== DEFAULT_OBS_INSTANCE && (
+            <>
+              {status.connected && (
+                <button className="btn-ghost" onClick={() => handleDisconnect(instance)} disabled={disabled}>
+                  切断
+                </button>
+              )}
+              <button className="btn-ghost" onClick={() => handleRemove(instance)} disabled={disabled}>
+                削除
+              </button>
+            </>
+          )}
+        </div>
+      ))}
+      <div className="instance-row">
+        <input
+          type="text"
+          value={name}
+          onChange={(e) => setName(e.target.value)}
+          placeholder="名前"
+          disabled={disabled}
+        />
+        <input
+          type="text"
+          value={host}
+          onChange={(e) => setHost(e.target.value)}
+          placeholder="localhost"
+          disabled={disabled}
+        />
+        <input
+          type="number"
+          value={port}
+          onChange={(e) => setPort(Number(e.target.value))}
+          min={1}
+          max={65535}
+          disabled={disabled}
+        />
+        <input
+          type="password"
+          value={password}
+          onChange={(e) => setPassword(e.target.value)}
+          placeholder="パスワード"
+          disabled={disabled}
+        />
+        <button className="btn-ghost" onClick={handleAdd} disabled={disabled || !name}>
+          追加
+        </button>
+      </div>
+      <span className="input-hint">
+        同じPCで複数のOBSを動かしている場合は、WebSocketポートごとにインスタンスを追加できます
+      </span>
+      {error && <span className="instance-error">{error}</span>}
+
+      <style>{`
+        .instance-row {
+          display: flex;
+          align-items: center;
+          gap: 0.75rem;
+          padding: 0.5rem 1rem;
+          background: var(--bg-color);
+          border: 1px solid var(--border-color);
+          border-radius: 0.5rem;
+        }
+
+        .instance-row input[type="text"],
+        .instance-row input[type="number"],
+        .instance-row input[type="password"] {
+          min-width: 0;
+          flex: 1;
+        }
+
+        .instance-name {
+          flex: 1;
+          font-weight: 600;
+          color: var(--text-primary);
+        }
+
+        .instance-status {
+          font-size: 0.875rem;
+          color: var(--text-secondary);
+        }
+
+        .instance-error {
+          font-size: 0.875rem;
+          color: var(--danger-color);
+        }
+      `}</style>
+    </div>
+  );
+};
@@ -1,18 +1,25 @@
-import { useState } from "react";
+import { useState, useEffect } from "react";
+import { invoke } from "@tauri-apps/api/core";
 import { useNetworkStatus } from "../hooks/useNetworkStatus";
-import { ConnectionState } from "../types/network";
+import { ConnectionState, TransportConfig } from "../types/network";
+import { TextLatencyStats } from "../types/sync";
+import { DEFAULT_OBS_INSTANCE } from "../types/obs";
+import { OBSInstanceList } from "./OBSInstanceList";
+import { TransportSelector } from "./TransportSelector";
 
 export const SlaveMonitor = () => {
   const [host, setHost] = useState("192.168.1.100");
   const [port, setPort] = useState(8080);
   const [isConnecting, setIsConnecting] = useState(false);
   const [isDisconnecting, setIsDisconnecting] = useState(false);
+  const [targetInstances, setTargetInstances] = useState<string[]>([DEFAULT_OBS_INSTANCE]);
+  const [transport, setTransport] = useState<TransportConfig>({ kind: "webSocket" });
   const { status, connectToMaster, disconnectFromMaster } = useNetworkStatus();
 
   const handleConnect = async () => {
     setIsConnecting(true);
     try {
-      await connectToMaster({ host, port });
+      await connectToMaster({ host, port, transport }, targetInstances);
     } catch (error) {
       console.error("Failed to connect to master:", error);
     } finally {
@@ -33,53 +40,93 @@ export const SlaveMonitor = () => {
 
   const isConnected = status.state === ConnectionState.Connected;
 
+  const [textLatency, setTextLatency] = useState<TextLatencyStats | null>(null);
+
+  // Poll text sync latency while connected
+  useEffect(() => {
+    if (!isConnected) {
+      setTextLatency(null);
+      return;
+    }
+
+    const updateLatency = async () => {
+      try {
+        const stats = await invoke<TextLatencyStats | null>("get_text_sync_latency");
+        setTextLatency(stats);
+      } catch (err) {
+        console.error("Failed to get text sync latency:", err);
+      }
+    };
+
+    updateLatency();
+    const interval = window.setInterval(updateLatency, 1000);
+    return () => clearInterval(interval);
+  }, [isConnected]);
+
   return (
     <div className="control-content">
       <div className="control-form">
-        <div className="form-group">
-          <label htmlFor="master-host">
-            <span className="label-icon">🌐</span>
-            MasterサーバーのIPアドレス
-          </label>
-          <input
-            id="master-host"
-            type="text"
-            value={host}
-            onChange={(e) => setHost(e.target.value)}
-            disabled={isConnected || isConnecting}
-            placeholder="192.168.1.100"
-          />
-          <span className="input-hint">
-            MasterサーバーのIPアドレスを入力してください
-          </span>
-        </div>
+        <TransportSelector
+          transport={transport}
+          onChange={setTransport}
+          disabled={isConnected || isConnecting}
+        />
+
+        {transport.kind === "webSocket" && (
+          <>
+            <div className="form-group">
+              <label htmlFor="master-host">
+                <span className="label-icon">🌐</span>
+                MasterサーバーのIPアドレス
+              </label>
+              <input
+                id="master-host"
+                type="text"
+                value={host}
+                onChange={(e) => setHost(e.target.value)}
+                disabled={isConnected || isConnecting}
+                placeholder="192.168.1.100"
+              />
+              <span className="input-hint">
+                MasterサーバーのIPアドレスを入力してください
+              </span>
+            </div>
 
-        <div className="form-group">
-          <label htmlFor="master-port">
-            <span className="label-icon">🔌</span>
-            ポート番号
-          </label>
-          <input
-            id="master-port"
-            type="number"
-            value={port}
-            onChange={(e) => setPort(Number(e.target.value))}
-            disabled={isConnected || isConnecting}
-            min={1024}
-            max={65535}
-            placeholder="8080"
-          />
-          <span className="input-hint">
-            Masterサーバーのポート番号（通常は8080）
-          </span>
-        </div>
+            <div className="form-group">
+              <label htmlFor="master-port">
+                <span className="label-icon">🔌</span>
+                ポート番号
+              </label>
+              <input
+                id="master-port"
+                type="number"
+                value={port}
+                onChange={(e) => setPort(Number(e.target.value))}
+                disabled={isConnected || isConnecting}
+                min={1024}
+                max={65535}
+                placeholder="8080"
+              />
+              <span className="input-hint">
+                Masterサーバーのポート番号（通常は8080）
+              </span>
+            </div>
+          </>
+        )}
+
+        <OBSInstanceList
+          selectionMode="multiple"
+          selected={targetInstances}
+          onChange={setTargetInstances}
+          disabled={isConnected || isConnecting}
+        />
 
         <div className="control-actions">
           {!isConnected ? (
             <button
               onClick={handleConnect}
               className="btn-primary btn-large"
-              disabled={isConnecting}
+              disabled={isConnecting || targetInstances.length === 0}
             >
               {isConnecting ? (
                 <>
@@ -124,7 +171,13 @@ export const SlaveMonitor = () => {
           <div className="status-panel-content">
             <div className="status-item">
               <span className="status-label">接続先:</span>
-              <span className="status-value">{host}:{port}</span>
+              <span className="status-value">
+                {transport.kind === "obsRelay" ? `OBS中継（${transport.instance}）` : `${host}:${port}`}
+              </span>
+            </div>
+            <div className="status-item">
+              <span className="status-label">同期先のOBS:</span>
+              <span className="status-value">{targetInstances.join(", ")}</span>
             </div>
             <div className="status-item">
               <span className="status-label">ステータス:</span>
@@ -132,6 +185,22 @@ export const SlaveMonitor = () => {
                 🟢 同期中
               </span>
             </div>
+            <div className="status-item">
+              <span className="status-label">テキスト同期レイテンシ:</span>
+              <span className="status-value">
+                {textLatency && textLatency.samples > 0
+                  ? `${textLatency.lastMs} ms（平均 ${textLatency.averageMs.toFixed(1)} ms / 最大 ${textLatency.maxMs} ms）`
+                  : "—"}
+              </span>
+            </div>
+            {textLatency && textLatency.clockSkewMs > 0 && (
+              <div className="status-item">
+                <span className="status-label">時計のずれ:</span>
+                <span className="status-value">
+                  Slaveの時計がMasterより {textLatency.clockSkewMs} ms 以上遅れています（NTPを確認してください）
+                </span>
+              </div>
+            )}
           </div>
           <div className="sync-info">
             <p className="sync-info-text">
@@ -0,0 +1,149 @@
+import { useSlaveSettings } from "../hooks/useSlaveSettings";
+import { SlaveSyncSettings } from "../types/sync";
+
+interface SyncOption {
+  key: keyof SlaveSyncSettings;
+  title: string;
+  description: string;
+}
+
+const options: SyncOption[] = [
+  {
+    key: "enableStudioModeForPreview",
+    title: "プレビュー同期時にスタジオモードを有効化",
+    description: "スタジオモードがオフの場合、自動的にオンにしてからプレビューシーンを設定します",
+  },
+  {
+    key: "mirrorStudioMode",
+    title: "スタジオモードをMasterに合わせる",
+    description: "Masterでスタジオモードを切り替えると、Slaveも同じ状態になります",
+  },
+  {
+    key: "mirrorSceneList",
+    title: "シーンの追加・名前変更をMasterに合わせる",
+    description: "Masterで追加・名前変更されたシーンをSlaveでも作成・名前変更します",
+  },
+  {
+    key: "allowSceneRemoval",
+    title: "シーンの削除を許可",
+    description: "Masterで削除されたシーンをSlaveでも削除します（元に戻せません）",
+  },
+  {
+    key: "provisionMissingSources",
+    title: "不足しているソースを自動作成",
+    description: "Masterにあり、Slaveにないソースを同じ種類・設定で作成してシーンに追加します",
+  },
+  {
+    key: "followSceneCollection",
+    title: "シーンコレクションをMasterに合わせる",
+    description: "Masterと同じ名前のシーンコレクションがあれば自動的に切り替えます",
+  },
+  {
+    key: "followProfile",
+    title: "プロファイルをMasterに合わせる",
+    description: "Masterと同じ名前のプロファイルがあれば自動的に切り替えます",
+  },
+  {
+    key: "mirrorStream",
+    title: "配信の開始・停止をMasterに合わせる",
+    description: "Masterで配信を開始・停止すると、Slaveでも同じ操作を行います",
+  },
+  {
+    key: "mirrorRecord",
+    title: "録画の開始・停止・一時停止をMasterに合わせる",
+    description: "Masterで録画を開始・停止・一時停止すると、Slaveでも同じ操作を行います",
+  },
+  {
+    key: "mirrorReplayBuffer",
+    title: "リプレイバッファをMasterに合わせる",
+    description: "リプレイバッファの開始・停止と保存をMasterと同時に行います",
+  },
+  {
+    key: "mirrorVirtualcam",
+    title: "仮想カメラをMasterに合わせる",
+    description: "Masterで仮想カメラを開始・停止すると、Slaveでも同じ操作を行います",
+  },
+  {
+    key: "verifyProgramVisually",
+    title: "プログラム映像をMasterと比較",
+    description: "縮小スクリーンショットを比較し、黒画面など映像の違いが続くとアラートを出します（Masterで「映像チェック」を有効にしてください）",
+  },
+];
+
+export const SlaveSyncOptions = () => {
+  const { settings, updateSettings } = useSlaveSettings();
+
+  const handleToggle = async (key: keyof SlaveSyncSettings) => {
+    try {
+      await updateSettings({ [key]: !settings[key] });
+    } catch (error) {
+      console.error("Failed to update slave sync settings:", error);
+    }
+  };
+
+  return (
+    <div className="slave-sync-options">
+      <h3 className="options-title">Slave同期オプション</h3>
+      {options.map((option) => (
+        <label key={option.key} className="option-row">
+          <input
+            type="checkbox"
+            checked={Boolean(settings[option.key])}
+            onChange={() => handleToggle(option.key)}
+          />
+          <div className="option-content">
+            <span className="option-title">{option.title}</span>
+            <span className="option-description">{option.description}</span>
+          </div>
+        </label>
+      ))}
+
+      <style>{`
+        .slave-sync-options {
+          display: flex;
+          flex-direction: column;
+          gap: 0.75rem;
+          margin-top: 1.5rem;
+        }
+
+        .options-title {
+          margin: 0;
+          font-size: 1rem;
+          font-weight: 700;
+          color: var(--text-primary);
+        }
+
+        .option-row {
+          display: flex;
+          align-items: flex-start;
+          gap: 0.75rem;
+          padding: 0.75rem 1rem;
+          background: var(--bg-color);
+          border: 1px solid var(--border-color);
+          border-radius: 0.5rem;
+          cursor: pointer;
+        }
+
+        .option-row input {
+          margin-top: 0.25rem;
+        }
+
+        .option-content {
+          display: flex;
+          flex-direction: column;
+          gap: 0.25rem;
+        }
+
+        .option-title {
+          font-weight: 600;
+          color: var(--text-primary);
+        }
+
+        .option-description {
+          font-size: 0.875rem;
+          color: var(--text-secondary);
+        }
+      `}</style>
+    </div>
+  );
+};
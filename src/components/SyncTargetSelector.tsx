@@ -7,7 +7,7 @@ export const SyncTargetSelector = () => {
     SyncTargetType.Program,
     SyncTargetType.Source,
   ]);
-  const { setSyncTargets } = useSyncState();
+  const { setSyncTargets, error } = useSyncState();
 
   const handleToggleTarget = async (target: SyncTargetType) => {
     const newTargets = selectedTargets.includes(target)
@@ -44,6 +44,36 @@ export const SyncTargetSelector = () => {
       title: "プログラム",
       description: "ライブ出力中のシーン",
     },
+    {
+      type: SyncTargetType.Filter,
+      icon: "🎨",
+      title: "フィルタ",
+      description: "色補正、クロマキー、LUTなどのソースフィルタ",
+    },
+    {
+      type: SyncTargetType.Audio,
+      icon: "🔊",
+      title: "オーディオ",
+      description: "音量、ミュート、バランス、同期オフセット、モニタリング",
+    },
+    {
+      type: SyncTargetType.Media,
+      icon: "🎬",
+      title: "メディア再生",
+      description: "メディアソースの再生・一時停止・停止・シーク位置",
+    },
+    {
+      type: SyncTargetType.Outputs,
+      icon: "🔴",
+      title: "出力 (配信・録画)",
+      description: "配信・録画・リプレイバッファ・仮想カメラの開始と停止",
+    },
+    {
+      type: SyncTargetType.Visual,
+      icon: "🖼️",
+      title: "映像チェック",
+      description: "プログラム映像の縮小スクリーンショットをSlaveと比較",
+    },
   ];
 
   return (
@@ -76,6 +106,13 @@ export const SyncTargetSelector = () => {
         })}
       </div>
 
+      {error && (
+        <div className="selector-error">
+          <span className="info-icon">⛔</span>
+          <span>{error}</span>
+        </div>
+      )}
+
       <div className="selector-info">
         <span className="info-icon">💡</span>
         <span>
@@ -185,6 +222,18 @@ export const SyncTargetSelector = () => {
           color: var(--text-secondary);
         }
 
+        .selector-error {
+          display: flex;
+          align-items: center;
+          gap: 0.5rem;
+          padding: 1rem;
+          background: rgba(239, 68, 68, 0.1);
+          border: 1px solid var(--danger-color);
+          border-radius: 0.5rem;
+          font-size: 0.875rem;
+          color: var(--text-primary);
+        }
+
         .info-icon {
           font-size: 1.25rem;
         }
@@ -0,0 +1,77 @@
+import { useOBSInstances } from "../hooks/useOBSInstances";
+import { TransportConfig } from "../types/network";
+
+interface TransportSelectorProps {
+  transport: TransportConfig;
+  onChange: (transport: TransportConfig) => void;
+  disabled?: boolean;
+}
+
+export const TransportSelector = ({ transport, onChange, disabled }: TransportSelectorProps) => {
+  const { instances } = useOBSInstances();
+  const connected = instances.filter(({ status }) => status.connected);
+
+  const handleRelayInstance = (instance: string) => {
+    onChange({ kind: "obsRelay", instance });
+  };
+
+  return (
+    <div className="form-group transport-selector">
+      <label>
+        <span className="label-icon">🛰️</span>
+        通信方式
+      </label>
+      <div className="transport-row">
+        <label className="transport-option">
+          <input
+            type="radio"
+            checked={transport.kind === "webSocket"}
+            onChange={() => onChange({ kind: "webSocket" })}
+            disabled={disabled}
+          />
+          直接接続（WebSocket）
+        </label>
+        <label className="transport-option">
+          <input
+            type="radio"
+            checked={transport.kind === "obsRelay"}
+            onChange={() => handleRelayInstance(connected[0]?.name ?? "")}
+            disabled={disabled || connected.length === 0}
+          />
+          OBS経由で中継
+        </label>
+      </div>
+      {transport.kind === "obsRelay" && (
+        <select
+          value={transport.instance}
+          onChange={(e) => handleRelayInstance(e.target.value)}
+          disabled={disabled}
+        >
+          {connected.map(({ name }) => (
+            <option key={name} value={name}>
+              {name}
+            </option>
+          ))}
+        </select>
+      )}
+      <span className="input-hint">
+        OBSのWebSocketポートしか通らない環境では、MasterとSlaveが共通で接続しているOBSを中継に使えます
+      </span>
+
+      <style>{`
+        .transport-row {
+          display: flex;
+          gap: 1.5rem;
+        }
+
+        .transport-option {
+          display: flex;
+          align-items: center;
+          gap: 0.5rem;
+          font-size: 0.875rem;
+          color: var(--text-primary);
+        }
+      `}</style>
+    </div>
+  );
+};
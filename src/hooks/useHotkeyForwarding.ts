@@ -0,0 +1,56 @@
+import { useState, useCallback, useEffect } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { HotkeyForwarding, KeySequence } from "../types/sync";
+
+const defaultForwarding: HotkeyForwarding = {
+  hotkeyNames: [],
+  keySequences: [],
+};
+
+export const useHotkeyForwarding = () => {
+  const [forwarding, setForwarding] = useState<HotkeyForwarding>(defaultForwarding);
+  const [hotkeys, setHotkeys] = useState<string[]>([]);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    invoke<HotkeyForwarding>("get_hotkey_forwarding")
+      .then(setForwarding)
+      .catch((err) => console.error("Failed to get hotkey forwarding:", err));
+    invoke<string[]>("get_obs_hotkeys")
+      .then(setHotkeys)
+      .catch((err) => console.error("Failed to get OBS hotkeys:", err));
+  }, []);
+
+  const updateForwarding = useCallback(async (next: HotkeyForwarding) => {
+    setForwarding(next);
+    try {
+      await invoke("set_hotkey_forwarding", { forwarding: next });
+      setError(null);
+    } catch (err) {
+      const errorMessage = err instanceof Error ? err.message : String(err);
+      setError(errorMessage);
+      // Revert on error
+      setForwarding(forwarding);
+      throw err;
+    }
+  }, [forwarding]);
+
+  const triggerHotkey = useCallback(async (hotkeyName?: string, keySequence?: KeySequence) => {
+    try {
+      await invoke("trigger_hotkey", { hotkeyName, keySequence });
+      setError(null);
+    } catch (err) {
+      const errorMessage = err instanceof Error ? err.message : String(err);
+      setError(errorMessage);
+      throw err;
+    }
+  }, []);
+
+  return {
+    forwarding,
+    hotkeys,
+    error,
+    updateForwarding,
+    triggerHotkey,
+  };
+};
@@ -1,11 +1,12 @@
 import { useState, useCallback, useEffect, useRef } from "react";
 import { invoke } from "@tauri-apps/api/core";
-import { NetworkStatus, ConnectionState } from "../types/network";
-
-interface NetworkConfig {
-  host: string;
-  port: number;
-}
+import {
+  BackpressureConfig,
+  NetworkConfig,
+  NetworkStatus,
+  ConnectionState,
+  TransportConfig,
+} from "../types/network";
 
 export const useNetworkStatus = () => {
   const [status, setStatus] = useState<NetworkStatus>({
@@ -28,11 +29,27 @@ export const useNetworkStatus = () => {
     }
   }, []);
 
-  const startMasterServer = useCallback(async (port: number) => {
+  const startMasterServer = useCallback(async (
+    port: number,
+    sourceInstance?: string,
+    transport?: TransportConfig,
+    bindAddress?: string,
+    backpressure?: BackpressureConfig
+  ) => {
     try {
       setStatus({ state: ConnectionState.Connecting });
-      await invoke("start_master_server", { port });
-      setStatus({ state: ConnectionState.Connected, connectedClients: 0 });
+      const listenAddress = await invoke<string | null>("start_master_server", {
+        port,
+        sourceInstance,
+        transport,
+        bindAddress,
+        backpressure,
+      });
+      setStatus({
+        state: ConnectionState.Connected,
+        connectedClients: 0,
+        listenAddress: listenAddress ?? undefined,
+      });
       setError(null);
       
       // Start polling for client count
@@ -67,10 +84,10 @@ export const useNetworkStatus = () => {
     }
   }, []);
 
-  const connectToMaster = useCallback(async (config: NetworkConfig) => {
+  const connectToMaster = useCallback(async (config: NetworkConfig, instances?: string[]) => {
     try {
       setStatus({ state: ConnectionState.Connecting });
-      await invoke("connect_to_master", { config });
+      await invoke("connect_to_master", { config, instances });
       setStatus({ state: ConnectionState.Connected });
       setError(null);
     } catch (err) {
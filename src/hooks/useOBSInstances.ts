@@ -0,0 +1,59 @@
+import { useState, useCallback, useEffect } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { OBSConnectionConfig, OBSInstanceStatus } from "../types/obs";
+
+export const useOBSInstances = () => {
+  const [instances, setInstances] = useState<OBSInstanceStatus[]>([]);
+  const [error, setError] = useState<string | null>(null);
+
+  const refresh = useCallback(async () => {
+    try {
+      setInstances(await invoke<OBSInstanceStatus[]>("get_obs_instances"));
+    } catch (err) {
+      console.error("Failed to get OBS instances:", err);
+    }
+  }, []);
+
+  useEffect(() => {
+    refresh();
+    const interval = window.setInterval(refresh, 3000);
+    return () => clearInterval(interval);
+  }, [refresh]);
+
+  const run = useCallback(async (action: () => Promise<unknown>) => {
+    try {
+      await action();
+      setError(null);
+    } catch (err) {
+      const errorMessage = err instanceof Error ? err.message : String(err);
+      setError(errorMessage);
+      throw err;
+    } finally {
+      refresh();
+    }
+  }, [refresh]);
+
+  const connectInstance = useCallback(
+    (instance: string, config: OBSConnectionConfig) =>
+      run(() => invoke("connect_obs", { config, instance })),
+    [run]
+  );
+
+  const disconnectInstance = useCallback(
+    (instance: string) => run(() => invoke("disconnect_obs", { instance })),
+    [run]
+  );
+
+  const removeInstance = useCallback(
+    (instance: string) => run(() => invoke("remove_obs_instance", { instance })),
+    [run]
+  );
+
+  return {
+    instances,
+    error,
+    connectInstance,
+    disconnectInstance,
+    removeInstance,
+  };
+};
@@ -0,0 +1,50 @@
+import { useState, useCallback, useEffect } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { SlaveSyncSettings } from "../types/sync";
+
+const defaultSettings: SlaveSyncSettings = {
+  enableStudioModeForPreview: false,
+  mirrorStudioMode: false,
+  mirrorSceneList: false,
+  allowSceneRemoval: false,
+  provisionMissingSources: false,
+  followSceneCollection: false,
+  followProfile: false,
+  mirrorStream: false,
+  mirrorRecord: false,
+  mirrorReplayBuffer: false,
+  mirrorVirtualcam: false,
+  verifyProgramVisually: false,
+};
+
+export const useSlaveSettings = () => {
+  const [settings, setSettings] = useState<SlaveSyncSettings>(defaultSettings);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    invoke<SlaveSyncSettings>("get_slave_sync_settings")
+      .then(setSettings)
+      .catch((err) => console.error("Failed to get slave sync settings:", err));
+  }, []);
+
+  const updateSettings = useCallback(async (changes: Partial<SlaveSyncSettings>) => {
+    const next = { ...settings, ...changes };
+    setSettings(next);
+    try {
+      await invoke("set_slave_sync_settings", { settings: next });
+      setError(null);
+    } catch (err) {
+      const errorMessage = err instanceof Error ? err.message : String(err);
+      setError(errorMessage);
+      // Revert on error
+      setSettings(settings);
+      throw err;
+    }
+  }, [settings]);
+
+  return {
+    settings,
+    error,
+    updateSettings,
+  };
+};
@@ -1,8 +1,15 @@
 // ネットワーク通信関連の型定義
 
+// 同期メッセージの経路（Rust: network::TransportConfig）
+export type TransportConfig =
+  | { kind: "webSocket" }
+  // 共有OBSのカスタムイベント経由（OBSのWebSocketポートしか通らない環境向け）
+  | { kind: "obsRelay"; instance: string };
+
 export interface NetworkConfig {
   host: string;
   port: number;
+  transport?: TransportConfig;
 }
 
 export interface MasterServerConfig extends NetworkConfig {
@@ -14,6 +21,24 @@ export interface SlaveClientConfig extends NetworkConfig {
   maxReconnectAttempts?: number;
 }
 
+// 送信キューが溢れたSlaveの扱い
+export type SlowClientPolicy = "resync" | "disconnect";
+
+export interface BackpressureConfig {
+  maxQueueMessages: number;
+  maxQueueBytes: number;
+  slowClientPolicy: SlowClientPolicy;
+}
+
+export interface ClientQueueMetrics {
+  clientId: string;
+  depth: number;
+  bytes: number;
+  maxDepth: number;
+  coalesced: number;
+  overflows: number;
+}
+
 export enum ConnectionState {
   Disconnected = "disconnected",
   Connecting = "connecting",
@@ -24,6 +49,8 @@ export enum ConnectionState {
 export interface NetworkStatus {
   state: ConnectionState;
   connectedClients?: number;
+  // Masterが実際に待ち受けているアドレス（ポート自動選択時は選ばれたポート）
+  listenAddress?: string;
   lastError?: string;
 }
 
@@ -6,12 +6,34 @@ export interface OBSConnectionConfig {
   password?: string;
 }
 
+export interface OBSCanvasInfo {
+  baseWidth: number;
+  baseHeight: number;
+  outputWidth: number;
+  outputHeight: number;
+  fpsNumerator: number;
+  fpsDenominator: number;
+}
+
 export interface OBSConnectionStatus {
   connected: boolean;
   obsVersion?: string;
   obsWebSocketVersion?: string;
+  rpcVersion?: number;
+  platform?: string;
+  platformDescription?: string;
+  availableRequests?: string[];
+  supportedImageFormats?: string[];
+  canvas?: OBSCanvasInfo;
 }
 
+export interface OBSInstanceStatus {
+  name: string;
+  status: OBSConnectionStatus;
+}
+
+export const DEFAULT_OBS_INSTANCE = "default";
+
 export interface OBSSource {
   sourceName: string;
   sourceType: string;
@@ -52,10 +74,66 @@ export interface OBSImageSourceSettings {
   height?: number;
 }
 
+export interface OBSSourceFilter {
+  name: string;
+  kind: string;
+  index: number;
+  enabled: boolean;
+  settings: Record<string, unknown>;
+}
+
+export type OBSMonitorType = "none" | "monitor_only" | "monitor_and_output";
+
+export interface OBSAudioState {
+  volumeDb?: number;
+  muted?: boolean;
+  balance?: number;
+  syncOffsetMs?: number;
+  monitorType?: OBSMonitorType;
+  tracks?: boolean[];
+}
+
+export type OBSMediaPlaybackState =
+  | "none"
+  | "playing"
+  | "opening"
+  | "buffering"
+  | "paused"
+  | "stopped"
+  | "ended"
+  | "error";
+
+export interface OBSMediaState {
+  state: OBSMediaPlaybackState;
+  cursorMs?: number;
+  durationMs?: number;
+  capturedAt: number;
+}
+
+export type OBSOutputName = "stream" | "record" | "replay_buffer" | "virtualcam";
+
+export interface OBSOutputState {
+  active: boolean;
+  paused?: boolean;
+}
+
+export interface OBSOutputStatus {
+  stream: OBSOutputState;
+  record: OBSOutputState;
+  replay_buffer: OBSOutputState | null;
+  virtualcam: OBSOutputState | null;
+}
+
 export enum SyncTargetType {
   Source = "source",
   Preview = "preview",
   Program = "program",
+  Filter = "filter",
+  Audio = "audio",
+  Media = "media",
+  Outputs = "outputs",
+  Hotkeys = "hotkeys",
+  Visual = "visual",
 }
 
 export interface SyncTarget {
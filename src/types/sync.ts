@@ -1,6 +1,6 @@
 // 同期関連の型定義
 
-import { OBSSceneItem, OBSTransform, SyncTargetType } from "./obs";
+import { OBSAudioState, OBSMediaState, OBSOutputName, OBSOutputStatus, OBSSceneItem, OBSSourceFilter, OBSTransform, SyncTargetType } from "./obs";
 
 export enum AppMode {
   Master = "master",
@@ -11,7 +11,33 @@ export enum SyncMessageType {
   SourceUpdate = "source_update",
   TransformUpdate = "transform_update",
   SceneChange = "scene_change",
+  SceneCreated = "scene_created",
+  SceneRemoved = "scene_removed",
+  SceneRenamed = "scene_renamed",
   ImageUpdate = "image_update",
+  InputSettingsUpdate = "input_settings_update",
+  TextUpdate = "text_update",
+  SceneItemCreated = "scene_item_created",
+  SceneItemEnabledUpdate = "scene_item_enabled_update",
+  SceneItemLockedUpdate = "scene_item_locked_update",
+  SceneItemOrderUpdate = "scene_item_order_update",
+  FilterCreated = "filter_created",
+  FilterRemoved = "filter_removed",
+  FilterRenamed = "filter_renamed",
+  FilterOrderUpdate = "filter_order_update",
+  FilterSettingsUpdate = "filter_settings_update",
+  FilterEnabledUpdate = "filter_enabled_update",
+  AudioUpdate = "audio_update",
+  StudioModeUpdate = "studio_mode_update",
+  TransitionUpdate = "transition_update",
+  CollectionUpdate = "collection_update",
+  MediaUpdate = "media_update",
+  OutputStateUpdate = "output_state_update",
+  ReplayBufferSave = "replay_buffer_save",
+  OutputStatus = "output_status",
+  TriggerHotkey = "trigger_hotkey",
+  VisualHash = "visual_hash",
+  BulkChunk = "bulk_chunk",
   Heartbeat = "heartbeat",
   StateSync = "state_sync",
 }
@@ -34,8 +60,69 @@ export interface TransformUpdatePayload {
   transform: OBSTransform;
 }
 
+export interface SceneItemEnabledPayload {
+  sceneName: string;
+  sceneItemId: number;
+  enabled: boolean;
+}
+
+export interface SceneItemLockedPayload {
+  sceneName: string;
+  sceneItemId: number;
+  locked: boolean;
+}
+
+export interface SceneItemOrderPayload {
+  sceneName: string;
+  items: {
+    sceneItemId: number;
+    sourceName: string;
+    index: number;
+  }[];
+}
+
+export interface SceneItemData {
+  sourceName: string;
+  sceneItemId: number;
+  sourceType: string;
+  transform?: Partial<OBSTransform>;
+  inputSettings?: Record<string, unknown>;
+  enabled?: boolean;
+  locked?: boolean;
+  index: number;
+  children?: SceneItemData[];
+}
+
+export interface SceneItemCreatedPayload {
+  sceneName: string;
+  item: SceneItemData;
+}
+
+export interface CollectionPayload {
+  sceneCollection: string;
+  profile: string;
+}
+
+export interface SceneListPayload {
+  sceneName: string;
+  newSceneName?: string;
+}
+
+export interface TransitionData {
+  name: string;
+  kind: string;
+  fixed: boolean;
+  durationMs?: number;
+  settings?: Record<string, unknown>;
+}
+
 export interface SceneChangePayload {
   sceneName: string;
+  transition?: TransitionData;
+}
+
+export interface StudioModePayload {
+  enabled: boolean;
 }
 
 export interface ImageUpdatePayload {
@@ -46,10 +133,109 @@ export interface ImageUpdatePayload {
   height?: number;
 }
 
+export interface InputSettingsUpdatePayload {
+  inputName: string;
+  inputKind: string;
+  settings: Record<string, unknown>;
+}
+
+export interface TextUpdatePayload {
+  inputName: string;
+  text: string;
+  capturedAt: number;
+}
+
+export interface FilterCreatedPayload {
+  sourceName: string;
+  filter: OBSSourceFilter;
+}
+
+export interface FilterUpdatePayload {
+  sourceName: string;
+  filterName: string;
+  newFilterName?: string;
+  settings?: Record<string, unknown>;
+  enabled?: boolean;
+}
+
+export interface FilterOrderPayload {
+  sourceName: string;
+  filters: OBSSourceFilter[];
+}
+
+export interface AudioUpdatePayload extends OBSAudioState {
+  inputName: string;
+}
+
+export type MediaAction = "play" | "pause" | "stop" | "restart" | "next" | "previous";
+
+export interface MediaUpdatePayload extends OBSMediaState {
+  inputName: string;
+  action?: MediaAction;
+}
+
+export interface OutputStatePayload {
+  output: OBSOutputName;
+  active: boolean;
+  state: string;
+}
+
+export interface OutputStatusPayload {
+  instance: string;
+  outputs: OBSOutputStatus;
+}
+
+export interface SlaveOutputReport {
+  clientId: string;
+  instance: string;
+  outputs: OBSOutputStatus;
+  reportedAt: number;
+}
+
+export interface KeySequence {
+  key: string;
+  shift: boolean;
+  control: boolean;
+  alt: boolean;
+  command: boolean;
+}
+
+export interface TriggerHotkeyPayload {
+  hotkeyName?: string;
+  keySequence?: KeySequence;
+}
+
+export interface VisualHashPayload {
+  sceneName: string;
+  hash: string;
+  luma: number;
+  capturedAt: number;
+}
+
+export interface BulkChunkPayload {
+  transferId: number;
+  index: number;
+  last: boolean;
+  data: string;
+}
+
+export interface HotkeyForwarding {
+  hotkeyNames: string[];
+  keySequences: KeySequence[];
+}
+
 export interface StateSyncPayload {
+  sceneCollection?: string;
+  profile?: string;
   currentScene: string;
   previewScene?: string;
+  studioModeEnabled?: boolean;
+  transition?: TransitionData;
   sources: OBSSceneItem[];
+  sourceFilters?: Record<string, OBSSourceFilter[]>;
+  audioInputs?: Record<string, OBSAudioState>;
+  mediaInputs?: Record<string, OBSMediaState>;
+  outputs?: OBSOutputStatus;
 }
 
 export interface SyncState {
@@ -62,8 +248,34 @@ export interface SyncState {
 export interface DesyncAlert {
   id: string;
   timestamp: number;
+  instance: string;
   sceneName: string;
   sourceName: string;
   message: string;
   severity: "warning" | "error";
 }
+
+export interface TextLatencyStats {
+  samples: number;
+  lastMs: number;
+  averageMs: number;
+  minMs: number;
+  maxMs: number;
+  // Slaveの時計がMasterより遅れている量の下限（負のレイテンシから推定）
+  clockSkewMs: number;
+}
+
+export interface SlaveSyncSettings {
+  enableStudioModeForPreview: boolean;
+  mirrorStudioMode: boolean;
+  mirrorSceneList: boolean;
+  allowSceneRemoval: boolean;
+  provisionMissingSources: boolean;
+  followSceneCollection: boolean;
+  followProfile: boolean;
+  mirrorStream: boolean;
+  mirrorRecord: boolean;
+  mirrorReplayBuffer: boolean;
+  mirrorVirtualcam: boolean;
+  verifyProgramVisually: boolean;
+}